@@ -0,0 +1,297 @@
+use std::path::PathBuf;
+
+/// How results are written outside of the REPL.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Plain,
+    Json,
+    Latex,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "plain" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            "latex" => Some(OutputFormat::Latex),
+            _ => None,
+        }
+    }
+}
+
+/// A command line option, `value` naming its argument if it takes one.
+pub struct CliOption {
+    pub short: Option<&'static str>,
+    pub long: &'static str,
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+pub const OPTIONS: &[CliOption] = &[
+    CliOption {
+        short: Some("-e"),
+        long: "--expression",
+        value: Some("EXPR"),
+        help: "evaluate EXPR, can be repeated",
+    },
+    CliOption {
+        short: Some("-f"),
+        long: "--file",
+        value: Some("FILE"),
+        help: "run a script line by line",
+    },
+    CliOption {
+        short: None,
+        long: "--keep-going",
+        value: None,
+        help: "run every expression or line of a script or of stdin despite errors",
+    },
+    CliOption {
+        short: None,
+        long: "--map",
+        value: Some("EXPR"),
+        help: "evaluate EXPR for each line of stdin bound to x",
+    },
+    CliOption {
+        short: None,
+        long: "--reduce",
+        value: Some("EXPR"),
+        help: "fold the lines of stdin into acc with EXPR",
+    },
+    CliOption {
+        short: None,
+        long: "--precision",
+        value: Some("N"),
+        help: "print N digits after the decimal point",
+    },
+    CliOption {
+        short: None,
+        long: "--format",
+        value: Some("FORMAT"),
+        help: "print results as plain, json or latex",
+    },
+    CliOption {
+        short: None,
+        long: "--json",
+        value: None,
+        help: "print one JSON object per evaluated line, as --format json",
+    },
+    CliOption {
+        short: None,
+        long: "--degrees",
+        value: None,
+        help: "use degrees in trigonometric functions",
+    },
+    CliOption {
+        short: None,
+        long: "--no-config",
+        value: None,
+        help: "use the default settings, ignoring the configuration and units files",
+    },
+    CliOption {
+        short: None,
+        long: "--config",
+        value: Some("PATH"),
+        help: "read and write the configuration at PATH",
+    },
+    CliOption {
+        short: Some("-V"),
+        long: "--version",
+        value: None,
+        help: "print the version",
+    },
+    CliOption {
+        short: Some("-h"),
+        long: "--help",
+        value: None,
+        help: "print this help",
+    },
+];
+
+/// The parsed command line, what is left once options are taken out being
+/// one expression.
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    pub expressions: Vec<String>,
+    pub file: Option<String>,
+    pub keep_going: bool,
+    pub map: Option<String>,
+    pub reduce: Option<String>,
+    pub precision: Option<usize>,
+    pub format: OutputFormat,
+    pub degrees: bool,
+    pub no_config: bool,
+    pub config: Option<PathBuf>,
+    pub version: bool,
+    pub help: bool,
+    pub positional: Vec<String>,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self {
+            expressions: Vec::new(),
+            file: None,
+            keep_going: false,
+            map: None,
+            reduce: None,
+            precision: None,
+            format: OutputFormat::Plain,
+            degrees: false,
+            no_config: false,
+            config: None,
+            version: false,
+            help: false,
+            positional: Vec::new(),
+        }
+    }
+}
+
+/// An argument which is not an option but an expression such as `-3` or
+/// `-(1+2)`.
+fn is_negative_expression(arg: &str) -> bool {
+    arg == "-"
+        || arg
+            .strip_prefix('-')
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| c.is_ascii_digit() || c == '.' || c == '(' || c == ' ')
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+    let mut cli = Cli::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            cli.positional.extend(args.by_ref());
+            break;
+        }
+        if !arg.starts_with('-') || is_negative_expression(&arg) {
+            cli.positional.push(arg);
+            continue;
+        }
+        // --name=value is --name value
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if arg.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let option = OPTIONS
+            .iter()
+            .find(|o| o.long == name || o.short == Some(name))
+            .ok_or_else(|| format!("Unknown option {name}, put expressions after --"))?;
+        let value = match (option.value, inline) {
+            (Some(_), Some(value)) => Some(value),
+            (Some(v), None) => Some(
+                args.next()
+                    .ok_or_else(|| format!("{} needs a value {v}", option.long))?,
+            ),
+            (None, Some(_)) => return Err(format!("{} does not take a value", option.long)),
+            (None, None) => None,
+        };
+        match (option.long, value) {
+            ("--expression", Some(v)) => cli.expressions.push(v),
+            ("--file", Some(v)) => cli.file = Some(v),
+            ("--keep-going", _) => cli.keep_going = true,
+            ("--map", Some(v)) => cli.map = Some(v),
+            ("--reduce", Some(v)) => cli.reduce = Some(v),
+            ("--precision", Some(v)) => {
+                cli.precision = Some(v.parse().map_err(|_| {
+                    format!("The precision must be a non negative integer, not {v}")
+                })?)
+            }
+            ("--format", Some(v)) => {
+                cli.format = OutputFormat::from_name(&v)
+                    .ok_or_else(|| format!("The format is plain, json or latex, not {v}"))?
+            }
+            ("--json", _) => cli.format = OutputFormat::Json,
+            ("--degrees", _) => cli.degrees = true,
+            ("--no-config", _) => cli.no_config = true,
+            ("--config", Some(v)) => cli.config = Some(PathBuf::from(v)),
+            ("--version", _) => cli.version = true,
+            ("--help", _) => cli.help = true,
+            _ => unreachable!("every option is handled"),
+        }
+    }
+    if cli.map.is_some() && cli.reduce.is_some() {
+        return Err("--map and --reduce cannot be used together".to_string());
+    }
+    Ok(cli)
+}
+
+/// The help, listing the options of `OPTIONS`.
+pub fn help() -> String {
+    let names: Vec<String> = OPTIONS
+        .iter()
+        .map(|o| {
+            let short = match o.short {
+                Some(s) => format!("{s}, "),
+                None => "    ".to_string(),
+            };
+            match o.value {
+                Some(v) => format!("{short}{} {v}", o.long),
+                None => format!("{short}{}", o.long),
+            }
+        })
+        .collect();
+    let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+    let mut help = String::from(
+        "Usage: mini-calc [OPTIONS] [EXPRESSION | FILE]\n\n\
+         Without arguments mini-calc starts the REPL, or evaluates each line of stdin\n\
+         when it is not a terminal. The remaining arguments form one expression, or\n\
+         name a script to run.\n\nOptions:\n",
+    );
+    for (name, option) in names.iter().zip(OPTIONS) {
+        help += &format!("  {name:width$}  {}\n", option.help);
+    }
+    help += &format!(
+        "  {:width$}  the remaining arguments are an expression\n",
+        "--"
+    );
+    help
+}
+
+#[cfg(test)]
+mod test {
+    use super::{help, parse_args, Cli, OutputFormat};
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    pub fn test_parse_expressions() {
+        let cli = parse(&["-e", "1+1", "--expression=x", "2", "-", "3"]).unwrap();
+        assert_eq!(cli.expressions, vec!["1+1", "x"]);
+        assert_eq!(cli.positional, vec!["2", "-", "3"]);
+        let cli = parse(&["--degrees", "--", "-x", "--format"]).unwrap();
+        assert!(cli.degrees);
+        assert_eq!(cli.positional, vec!["-x", "--format"]);
+    }
+
+    #[test]
+    pub fn test_parse_values() {
+        let cli = parse(&["--precision", "3", "--format", "latex", "-3"]).unwrap();
+        assert_eq!(cli.precision, Some(3));
+        assert_eq!(cli.format, OutputFormat::Latex);
+        assert_eq!(cli.positional, vec!["-3"]);
+        assert_eq!(parse(&["--json"]).unwrap().format, OutputFormat::Json);
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--precision"]).is_err());
+        assert!(parse(&["-x"]).is_err());
+    }
+
+    #[test]
+    pub fn test_parse_map_and_reduce() {
+        assert_eq!(parse(&["--map", "x^2"]).unwrap().map, Some("x^2".to_string()));
+        assert_eq!(
+            parse(&["--map", "x^2", "--reduce", "acc+x"]).err(),
+            Some("--map and --reduce cannot be used together".to_string())
+        );
+    }
+
+    #[test]
+    pub fn test_help_lists_options() {
+        let help = help();
+        assert!(help.contains("-e, --expression EXPR  evaluate EXPR, can be repeated"));
+        assert!(help.contains("    --no-config"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::configuration::loader::history_path;
+
+/// The lines entered in the REPL, kept across sessions in the history file.
+pub struct History {
+    entries: Vec<String>,
+    size: usize,
+    dedup: bool,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn new(size: usize, dedup: bool) -> Self {
+        Self {
+            entries: Vec::new(),
+            size,
+            dedup,
+            path: None,
+        }
+    }
+
+    /// The history of the previous sessions, empty if there is no file yet.
+    pub fn load(size: usize, dedup: bool) -> Self {
+        let mut history = History::new(size, dedup);
+        history.path = history_path();
+        if let Some(content) = history
+            .path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+        {
+            content.lines().for_each(|line| {
+                history.push(line);
+            });
+        }
+        history
+    }
+
+    /// Adds a line, dropping the oldest entries past the size. With
+    /// deduplication an earlier identical entry is removed, its index being
+    /// returned so that the line editor can forget it too.
+    pub fn push(&mut self, line: &str) -> Option<usize> {
+        if self.size == 0 || line.trim().is_empty() {
+            return None;
+        }
+        let removed = if self.dedup {
+            self.entries.iter().position(|e| e == line)
+        } else {
+            None
+        };
+        if let Some(i) = removed {
+            self.entries.remove(i);
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > self.size {
+            self.entries.drain(..self.entries.len() - self.size);
+        }
+        removed
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// The entry numbered `number` in the listing, starting at 1.
+    pub fn get(&self, number: usize) -> Option<&String> {
+        number.checked_sub(1).and_then(|i| self.entries.get(i))
+    }
+
+    /// The numbered entries containing `text`.
+    pub fn search(&self, text: &str) -> Vec<(usize, &String)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.contains(text))
+            .map(|(i, e)| (i + 1, e))
+            .collect()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        match &self.path {
+            Some(path) => {
+                let mut content = self.entries.join("\n");
+                content.push('\n');
+                fs::write(path, content)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Lists numbered entries the way the `history` command prints them.
+pub fn format_entries<'a>(entries: impl Iterator<Item = (usize, &'a String)>) -> String {
+    entries
+        .map(|(number, entry)| format!(" {number:>4}  {entry}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_entries, History};
+
+    #[test]
+    pub fn test_dedup_and_size() {
+        let mut history = History::new(3, true);
+        for line in ["1+1", "x = 2", "1+1", "", "y"] {
+            history.push(line);
+        }
+        assert_eq!(history.entries(), ["x = 2", "1+1", "y"]);
+        assert_eq!(history.push("x = 2"), Some(0));
+        history.push("z");
+        assert_eq!(history.entries(), ["y", "x = 2", "z"]);
+
+        let mut history = History::new(3, false);
+        ["a", "a", "b", "a"].iter().for_each(|l| {
+            history.push(l);
+        });
+        assert_eq!(history.entries(), ["a", "b", "a"]);
+        assert_eq!(History::new(0, true).push("a"), None);
+    }
+
+    #[test]
+    pub fn test_search() {
+        let mut history = History::new(10, true);
+        for line in ["sin(1)", "2+2", "sin(2)"] {
+            history.push(line);
+        }
+        assert_eq!(history.get(2), Some(&"2+2".to_string()));
+        assert_eq!(history.get(0), None);
+        assert_eq!(
+            format_entries(history.search("sin").into_iter()),
+            "    1  sin(1)\n    3  sin(2)\n"
+        );
+    }
+}
@@ -1,7 +1,14 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use ansi_term::{ANSIGenericString, Color};
 use confy::ConfyError;
 use serde::{Deserialize, Serialize};
 
+use crate::exact_math::rationals::FractionDisplay;
+use crate::utils::float_utils::{Notation, DEFAULT_DECIMAL_DIGITS};
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Greeting {
     pub greeting_message: String,
@@ -14,13 +21,82 @@ pub struct Prompt {
     pub prompt_color: String,
 }
 
+pub const DEFAULT_RECURSION_LIMIT: usize = 1000;
+pub const DEFAULT_MAX_DENOMINATOR: u64 = 1_000_000_000_000;
+pub const DEFAULT_TOLERANCE: f64 = 1e-12;
+pub const DEFAULT_HISTORY_SIZE: usize = 1000;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     pub general_color: String,
+    #[serde(default = "default_recursion_limit")]
+    pub recursion_limit: usize,
+    #[serde(default = "default_max_denominator")]
+    pub max_denominator: u64,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    #[serde(default = "default_complex_display")]
+    pub complex_display: String,
+    #[serde(default = "default_output_base")]
+    pub output_base: u32,
+    #[serde(default = "default_notation")]
+    pub notation: String,
+    #[serde(default = "default_decimal_digits")]
+    pub decimal_digits: usize,
+    #[serde(default)]
+    pub trim_zeros: bool,
+    #[serde(default = "default_fraction_display")]
+    pub fraction_display: String,
+    #[serde(default)]
+    pub thousands_separator: String,
+    #[serde(default = "default_history_size")]
+    pub history_size: usize,
+    #[serde(default = "default_history_dedup")]
+    pub history_dedup: bool,
     pub greeting: Greeting,
     pub prompt: Prompt,
 }
 
+fn default_recursion_limit() -> usize {
+    DEFAULT_RECURSION_LIMIT
+}
+
+fn default_max_denominator() -> u64 {
+    DEFAULT_MAX_DENOMINATOR
+}
+
+fn default_tolerance() -> f64 {
+    DEFAULT_TOLERANCE
+}
+
+fn default_complex_display() -> String {
+    "rectangular".to_string()
+}
+
+fn default_output_base() -> u32 {
+    10
+}
+
+fn default_notation() -> String {
+    "auto".to_string()
+}
+
+fn default_decimal_digits() -> usize {
+    DEFAULT_DECIMAL_DIGITS
+}
+
+fn default_fraction_display() -> String {
+    "fraction".to_string()
+}
+
+fn default_history_size() -> usize {
+    DEFAULT_HISTORY_SIZE
+}
+
+fn default_history_dedup() -> bool {
+    true
+}
+
 #[derive(Clone)]
 pub struct Loaded<'a> {
     pub general_color: Color,
@@ -28,6 +104,18 @@ pub struct Loaded<'a> {
     pub greeting_color: Color,
     pub prompt: String,
     pub prompt_style: Color,
+    pub recursion_limit: usize,
+    pub max_denominator: u64,
+    pub tolerance: f64,
+    pub polar: bool,
+    pub output_base: u32,
+    pub notation: Notation,
+    pub decimal_digits: usize,
+    pub trim_zeros: bool,
+    pub fraction_display: FractionDisplay,
+    pub thousands_separator: Option<char>,
+    pub history_size: usize,
+    pub history_dedup: bool,
 }
 
 impl Default for Greeting {
@@ -55,17 +143,89 @@ impl Default for Config {
             general_color: "purple".to_string(),
             greeting: Greeting::default(),
             prompt: Prompt::default(),
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            max_denominator: DEFAULT_MAX_DENOMINATOR,
+            tolerance: DEFAULT_TOLERANCE,
+            complex_display: default_complex_display(),
+            output_base: default_output_base(),
+            notation: default_notation(),
+            decimal_digits: DEFAULT_DECIMAL_DIGITS,
+            trim_zeros: false,
+            fraction_display: default_fraction_display(),
+            thousands_separator: "".to_string(),
+            history_size: DEFAULT_HISTORY_SIZE,
+            history_dedup: true,
         }
     }
 }
 
+/// Where the configuration is read from and written to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigSource {
+    /// `mini-calc.toml` in the confy directory
+    Default,
+    Path(PathBuf),
+    /// The defaults, nothing is read or written
+    Disabled,
+}
+
+thread_local! {
+    static CONFIG_SOURCE: RefCell<ConfigSource> = const { RefCell::new(ConfigSource::Default) };
+}
+
+pub fn set_config_source(source: ConfigSource) {
+    CONFIG_SOURCE.with(|s| *s.borrow_mut() = source);
+}
+
+fn config_source() -> ConfigSource {
+    CONFIG_SOURCE.with(|s| s.borrow().clone())
+}
+
 pub fn load() -> Result<Config, confy::ConfyError> {
-    let cfg: Config = confy::load("mini-calc", Some("mini-calc"))?;
+    let cfg: Config = match config_source() {
+        ConfigSource::Default => confy::load("mini-calc", Some("mini-calc"))?,
+        ConfigSource::Path(path) => confy::load_path(path)?,
+        ConfigSource::Disabled => Config::default(),
+    };
     Ok(cfg)
 }
 
+/// Units added to the builtin ones, read from `units.toml` next to the
+/// configuration, as `furlong = "201.168 m"` lines under `[units]`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct UnitsFile {
+    #[serde(default)]
+    pub units: BTreeMap<String, String>,
+}
+
+pub fn load_units() -> Result<UnitsFile, confy::ConfyError> {
+    let units: UnitsFile = match config_source() {
+        ConfigSource::Default => confy::load("mini-calc", Some("units"))?,
+        ConfigSource::Path(path) => confy::load_path(path.with_file_name("units.toml"))?,
+        ConfigSource::Disabled => UnitsFile::default(),
+    };
+    Ok(units)
+}
+
+/// The REPL history file, `history` next to the configuration, none when the
+/// configuration is disabled.
+pub fn history_path() -> Option<PathBuf> {
+    let config = match config_source() {
+        ConfigSource::Default => {
+            confy::get_configuration_file_path("mini-calc", Some("mini-calc")).ok()?
+        }
+        ConfigSource::Path(path) => path,
+        ConfigSource::Disabled => return None,
+    };
+    Some(config.with_file_name("history"))
+}
+
 pub fn write_config(c: &Config) -> Result<(), ConfyError> {
-    confy::store("mini-calc", Some("mini-calc"), c)?;
+    match config_source() {
+        ConfigSource::Default => confy::store("mini-calc", Some("mini-calc"), c)?,
+        ConfigSource::Path(path) => confy::store_path(path, c)?,
+        ConfigSource::Disabled => (),
+    }
     Ok(())
 }
 
@@ -143,5 +303,38 @@ pub fn load_config<'a>(config: Config) -> Loaded<'a> {
             .paint(replace_variable(config.greeting.greeting_message)),
         prompt: config.prompt.prompt,
         prompt_style: load_color(config.prompt.prompt_color),
+        recursion_limit: config.recursion_limit,
+        max_denominator: config.max_denominator,
+        tolerance: config.tolerance,
+        polar: config.complex_display == "polar",
+        output_base: config.output_base,
+        notation: Notation::from_name(&config.notation).unwrap_or(Notation::Auto),
+        decimal_digits: config.decimal_digits,
+        trim_zeros: config.trim_zeros,
+        fraction_display: FractionDisplay::from_name(&config.fraction_display)
+            .unwrap_or(FractionDisplay::Fraction),
+        thousands_separator: config.thousands_separator.chars().next(),
+        history_size: config.history_size,
+        history_dedup: config.history_dedup,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::{load_units, set_config_source, ConfigSource};
+
+    #[test]
+    pub fn test_load_units_next_to_config_path() {
+        let dir = std::env::temp_dir().join(format!("mini-calc-units-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("units.toml"), "[units]\nfurlong = \"201.168 m\"\n").unwrap();
+        set_config_source(ConfigSource::Path(dir.join("mini-calc.toml")));
+        let units = load_units();
+        set_config_source(ConfigSource::Default);
+        fs::remove_dir_all(&dir).unwrap();
+        let units = units.unwrap().units;
+        assert_eq!(units.get("furlong").map(String::as_str), Some("201.168 m"));
     }
 }
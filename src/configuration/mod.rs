@@ -1 +1,3 @@
+pub mod cli;
+pub mod history;
 pub mod loader;
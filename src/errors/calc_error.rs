@@ -0,0 +1,106 @@
+use std::fmt::{Display, Formatter};
+
+use crate::errors::parse_error::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    UnknownFunction(String),
+    UndefinedVariable(String),
+    TypeMismatch {
+        context: String,
+        found: String,
+    },
+    ArityMismatch {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+    DivisionByZero,
+    ProtectedName(String),
+    RecursionLimit {
+        function: String,
+        limit: usize,
+    },
+    DimensionMismatch(String),
+    Overflow(String),
+    NotDifferentiable(String),
+    NoConvergence {
+        function: String,
+        estimate: f64,
+    },
+    ParseError(ParseError),
+}
+
+impl Display for CalcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalcError::UnknownFunction(s) => write!(f, "The function {s} is unknown"),
+            CalcError::UndefinedVariable(s) => {
+                write!(f, "The variable {s} is not initialized yet")
+            }
+            CalcError::TypeMismatch { context, found } => {
+                write!(f, "{context} cannot be applied to {found}")
+            }
+            CalcError::ArityMismatch {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "The function {function} expects {expected} argument(s) but {found} were given"
+            ),
+            CalcError::DivisionByZero => write!(f, "Division by zero"),
+            CalcError::ProtectedName(s) => {
+                write!(f, "{s} is a builtin and cannot be redefined or removed")
+            }
+            CalcError::RecursionLimit { function, limit } => write!(
+                f,
+                "The function {function} exceeded the maximum recursion depth of {limit}"
+            ),
+            CalcError::DimensionMismatch(s) => write!(f, "Dimension mismatch: {s}"),
+            CalcError::Overflow(s) => write!(f, "Integer overflow in {s}"),
+            CalcError::NotDifferentiable(s) => {
+                write!(f, "The function {s} cannot be differentiated")
+            }
+            CalcError::NoConvergence { function, estimate } => write!(
+                f,
+                "The function {function} did not converge (error estimate {estimate:e})"
+            ),
+            CalcError::ParseError(e) => write!(f, "Parse error: {e}"),
+        }
+    }
+}
+
+impl CalcError {
+    /// The name of the variant, stable for programs matching on errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CalcError::UnknownFunction(_) => "unknown_function",
+            CalcError::UndefinedVariable(_) => "undefined_variable",
+            CalcError::TypeMismatch { .. } => "type_mismatch",
+            CalcError::ArityMismatch { .. } => "arity_mismatch",
+            CalcError::DivisionByZero => "division_by_zero",
+            CalcError::ProtectedName(_) => "protected_name",
+            CalcError::RecursionLimit { .. } => "recursion_limit",
+            CalcError::DimensionMismatch(_) => "dimension_mismatch",
+            CalcError::Overflow(_) => "overflow",
+            CalcError::NotDifferentiable(_) => "not_differentiable",
+            CalcError::NoConvergence { .. } => "no_convergence",
+            CalcError::ParseError(_) => "parse_error",
+        }
+    }
+
+    pub fn incompatible(operator: &str, left: &str, right: &str) -> Self {
+        CalcError::TypeMismatch {
+            context: format!("The {operator} operator"),
+            found: format!("{left} and {right}"),
+        }
+    }
+
+    pub fn wrong_type(function: &str, found: &str) -> Self {
+        CalcError::TypeMismatch {
+            context: format!("The function {function}"),
+            found: found.to_string(),
+        }
+    }
+}
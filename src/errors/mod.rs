@@ -0,0 +1,2 @@
+pub mod calc_error;
+pub mod parse_error;
@@ -0,0 +1,88 @@
+use std::fmt::{Display, Formatter};
+
+use crate::lexing::token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+    pub opened: Option<Span>,
+}
+
+impl ParseError {
+    pub fn new(message: String, span: Span) -> Self {
+        ParseError {
+            message,
+            span,
+            opened: None,
+        }
+    }
+
+    pub fn unclosed(message: String, span: Span, opened: Span) -> Self {
+        ParseError {
+            message,
+            span,
+            opened: Some(opened),
+        }
+    }
+
+    /// Renders the source line with `^^^` under the offending span and `-`
+    /// under the delimiter that was left open, followed by the message.
+    pub fn render(&self, line: &str) -> String {
+        let column = |offset: usize| {
+            if offset <= line.len() {
+                line[..offset].chars().count()
+            } else {
+                line.chars().count() + offset - line.len()
+            }
+        };
+
+        let start = column(self.span.start);
+        let end = column(self.span.end).max(start + 1);
+        let mut markers = vec![' '; end];
+        if let Some(opened) = self.opened {
+            let (o_start, o_end) = (column(opened.start), column(opened.end));
+            if o_end > markers.len() {
+                markers.resize(o_end, ' ');
+            }
+            markers[o_start..o_end].iter_mut().for_each(|c| *c = '-');
+        }
+        markers[start..end].iter_mut().for_each(|c| *c = '^');
+
+        let markers: String = markers.into_iter().collect();
+        format!("{line}\n{} {}", markers.trim_end(), self.message)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.span.start)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::errors::parse_error::ParseError;
+    use crate::lexing::token::Span;
+
+    #[test]
+    pub fn test_render() {
+        let error = ParseError::new(
+            "expected an expression, found `)`".to_string(),
+            Span { start: 6, end: 7 },
+        );
+        let expected = "2 + * )\n      ^ expected an expression, found `)`";
+        assert_eq!(error.render("2 + * )"), expected);
+    }
+
+    #[test]
+    pub fn test_render_unclosed() {
+        let error = ParseError::unclosed(
+            "expected `)` to close `(` opened here".to_string(),
+            Span { start: 6, end: 7 },
+            Span { start: 2, end: 3 },
+        );
+        let expected = "1+(2*3\n  -   ^ expected `)` to close `(` opened here";
+        assert_eq!(error.render("1+(2*3"), expected);
+    }
+}
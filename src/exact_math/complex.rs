@@ -0,0 +1,385 @@
+use std::cell::Cell;
+use std::f64::consts::PI;
+use std::fmt::Display;
+
+use crate::errors::calc_error::CalcError;
+use crate::exact_math::polynomial::rational_parameter;
+use crate::exact_math::radicals::Radicals;
+use crate::exact_math::rationals::Rationals;
+use crate::interpreting::function::{add, divide, minus, mult, to_float};
+use crate::parsing::ast::Parameters;
+use crate::utils::float_utils::format_float;
+
+// exact integer powers are computed by repeated squaring up to this exponent
+const MAX_EXACT_EXPONENT: i64 = 1 << 16;
+
+thread_local! {
+    static POLAR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Chooses between the `a+bi` and the `r∠θ` display of complex numbers.
+pub fn set_polar_display(polar: bool) {
+    POLAR.with(|p| p.set(polar));
+}
+
+/// A complex number in floating point, real part first.
+pub type FloatComplex = (f64, f64);
+
+pub fn float_mul(a: FloatComplex, b: FloatComplex) -> FloatComplex {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+pub fn float_div(a: FloatComplex, b: FloatComplex) -> FloatComplex {
+    let norm = b.0 * b.0 + b.1 * b.1;
+    (
+        (a.0 * b.0 + a.1 * b.1) / norm,
+        (a.1 * b.0 - a.0 * b.1) / norm,
+    )
+}
+
+fn float_add(a: FloatComplex, b: FloatComplex) -> FloatComplex {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn float_exp(z: FloatComplex) -> FloatComplex {
+    let r = z.0.exp();
+    (r * z.1.cos(), r * z.1.sin())
+}
+
+/// The principal logarithm, the argument being in `(-π, π]`.
+fn float_ln(z: FloatComplex) -> FloatComplex {
+    (z.0.hypot(z.1).ln(), z.1.atan2(z.0))
+}
+
+/// The principal square root, with a non negative real part.
+fn float_sqrt(z: FloatComplex) -> FloatComplex {
+    let r = z.0.hypot(z.1);
+    let re = ((r + z.0) / 2.0).sqrt();
+    let im = ((r - z.0) / 2.0).sqrt();
+    (re, if z.1 < 0.0 { -im } else { im })
+}
+
+/// The complex function of a builtin name on a complex argument.
+fn float_function(name: &str, z: FloatComplex) -> Option<FloatComplex> {
+    let i = (0.0, 1.0);
+    let one = (1.0, 0.0);
+    let (re, im) = z;
+    Some(match name {
+        "exp" => float_exp(z),
+        "ln" | "log" => float_ln(z),
+        "sqrt" => float_sqrt(z),
+        "cos" => (re.cos() * im.cosh(), -re.sin() * im.sinh()),
+        "sin" => (re.sin() * im.cosh(), re.cos() * im.sinh()),
+        "tan" => float_div(float_function("sin", z)?, float_function("cos", z)?),
+        "cosh" => (re.cosh() * im.cos(), re.sinh() * im.sin()),
+        "sinh" => (re.sinh() * im.cos(), re.cosh() * im.sin()),
+        "tanh" => float_div(float_function("sinh", z)?, float_function("cosh", z)?),
+        // the real cuts are approached from above, as in C99
+        "asin" if im == 0.0 && re.abs() > 1.0 => (PI / 2.0 * re.signum(), re.abs().acosh()),
+        // asin z = -i ln(iz + √(1-z²))
+        "asin" => {
+            let root = float_sqrt(float_add(one, float_mul((-1.0, 0.0), float_mul(z, z))));
+            float_mul((0.0, -1.0), float_ln(float_add(float_mul(i, z), root)))
+        }
+        // acos z = π/2 - asin z
+        "acos" => {
+            let asin = float_function("asin", z)?;
+            (PI / 2.0 - asin.0, -asin.1)
+        }
+        "atan" if re == 0.0 && im.abs() > 1.0 => (PI / 2.0 * im.signum(), (1.0 / im).atanh()),
+        // atan z = i/2 ln((i+z)/(i-z))
+        "atan" => {
+            let quotient = float_div(float_add(i, z), (-re, 1.0 - im));
+            float_mul((0.0, 0.5), float_ln(quotient))
+        }
+        _ => return None,
+    })
+}
+
+/// Whether the value is an exact or float zero.
+fn is_zero(p: &Parameters) -> bool {
+    to_float(p) == Some(0.0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Complex {
+    pub re: Parameters,
+    pub im: Parameters,
+}
+
+impl Complex {
+    /// Parts are integers, rationals, radicals or floats.
+    pub fn new(re: Parameters, im: Parameters) -> Self {
+        Complex { re, im }
+    }
+
+    pub fn i() -> Self {
+        Complex::new(Parameters::Int(0), Parameters::Int(1))
+    }
+
+    /// The number with the given parts, real when the imaginary one is zero.
+    pub fn from_parts(re: Parameters, im: Parameters) -> Parameters {
+        // integral rationals are written as integers
+        let normalize = |p| match p {
+            Parameters::Rational(r) => rational_parameter(r),
+            p => p,
+        };
+        if is_zero(&im) {
+            normalize(re)
+        } else {
+            Parameters::Complex(Box::from(Complex::new(normalize(re), normalize(im))))
+        }
+    }
+
+    /// Like `from_parts` for float results, a part below the rounding error
+    /// of the other one being dropped, so that `e^(iπ)` is `-1`.
+    pub fn from_floats(z: FloatComplex) -> Parameters {
+        let scale = 4.0 * f64::EPSILON * z.0.abs().max(z.1.abs());
+        let re = if z.0.abs() <= scale { 0.0 } else { z.0 };
+        let im = if z.1.abs() <= scale { 0.0 } else { z.1 };
+        Complex::from_parts(Parameters::Float(re), Parameters::Float(im))
+    }
+
+    /// The value as a complex number, for real numbers and complex numbers.
+    pub fn from_parameter(p: &Parameters) -> Option<Self> {
+        match p {
+            Parameters::Int(_)
+            | Parameters::Float(_)
+            | Parameters::Rational(_)
+            | Parameters::Radical(_) => Some(Complex::new(p.clone(), Parameters::Int(0))),
+            Parameters::Complex(z) => Some(*z.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn to_floats(&self) -> FloatComplex {
+        (
+            to_float(&self.re).unwrap_or(f64::NAN),
+            to_float(&self.im).unwrap_or(f64::NAN),
+        )
+    }
+
+    fn is_exact(&self) -> bool {
+        !matches!(self.re, Parameters::Float(_)) && !matches!(self.im, Parameters::Float(_))
+    }
+
+    pub fn conj(&self) -> Result<Parameters, CalcError> {
+        Ok(Complex::from_parts(
+            self.re.clone(),
+            minus(self.im.clone(), Parameters::Null, None)?,
+        ))
+    }
+
+    /// The squared modulus `re² + im²`.
+    fn norm(&self) -> Result<Parameters, CalcError> {
+        add(
+            mult(self.re.clone(), self.re.clone(), None)?,
+            mult(self.im.clone(), self.im.clone(), None)?,
+            None,
+        )
+    }
+
+    /// The modulus, exact when the squared modulus is rational.
+    pub fn abs(&self) -> Result<Parameters, CalcError> {
+        let exact = match self.norm()? {
+            Parameters::Int(i) => Radicals::sqrt(&Rationals::new(1, i)),
+            Parameters::Rational(r) => Radicals::sqrt(&r),
+            _ => None,
+        };
+        Ok(match exact {
+            Some(r) => match r.to_rational() {
+                Some(q) => rational_parameter(q),
+                None => Parameters::Radical(r),
+            },
+            None => {
+                let (re, im) = self.to_floats();
+                Parameters::Float(re.hypot(im))
+            }
+        })
+    }
+
+    pub fn arg(&self) -> f64 {
+        let (re, im) = self.to_floats();
+        im.atan2(re)
+    }
+
+    pub fn add(&self, other: &Self) -> Result<Parameters, CalcError> {
+        Ok(Complex::from_parts(
+            add(self.re.clone(), other.re.clone(), None)?,
+            add(self.im.clone(), other.im.clone(), None)?,
+        ))
+    }
+
+    pub fn sub(&self, other: &Self) -> Result<Parameters, CalcError> {
+        Ok(Complex::from_parts(
+            minus(self.re.clone(), other.re.clone(), None)?,
+            minus(self.im.clone(), other.im.clone(), None)?,
+        ))
+    }
+
+    pub fn mul(&self, other: &Self) -> Result<Parameters, CalcError> {
+        let (a, b, c, d) = (&self.re, &self.im, &other.re, &other.im);
+        let re = minus(
+            mult(a.clone(), c.clone(), None)?,
+            mult(b.clone(), d.clone(), None)?,
+            None,
+        )?;
+        let im = add(
+            mult(a.clone(), d.clone(), None)?,
+            mult(b.clone(), c.clone(), None)?,
+            None,
+        )?;
+        Ok(Complex::from_parts(re, im))
+    }
+
+    /// `(a+bi)/(c+di) = (a+bi)(c-di)/(c²+d²)`.
+    pub fn div(&self, other: &Self) -> Result<Parameters, CalcError> {
+        let norm = other.norm()?;
+        if is_zero(&norm) {
+            return Err(CalcError::DivisionByZero);
+        }
+        let conj = Complex::from_parameter(&other.conj()?).unwrap();
+        let Complex { re, im } = Complex::from_parameter(&self.mul(&conj)?).unwrap();
+        Ok(Complex::from_parts(
+            divide(re, norm.clone(), None)?,
+            divide(im, norm, None)?,
+        ))
+    }
+
+    /// Exact by repeated squaring for exact integer powers, `exp(w ln z)`
+    /// otherwise.
+    pub fn pow(&self, other: &Self) -> Result<Parameters, CalcError> {
+        match (&other.re, is_zero(&other.im)) {
+            (Parameters::Int(n), true) if self.is_exact() && n.abs() <= MAX_EXACT_EXPONENT => {
+                let mut result = Parameters::Int(1);
+                let mut base = Parameters::Complex(Box::from(self.clone()));
+                let mut n = n.unsigned_abs();
+                while n > 0 {
+                    if n & 1 == 1 {
+                        result = mult(result, base.clone(), None)?;
+                    }
+                    base = mult(base.clone(), base, None)?;
+                    n >>= 1;
+                }
+                match other.re {
+                    Parameters::Int(n) if n < 0 => divide(Parameters::Int(1), result, None),
+                    _ => Ok(result),
+                }
+            }
+            _ => {
+                let z = self.to_floats();
+                if z == (0.0, 0.0) {
+                    return Ok(Parameters::Float(0.0));
+                }
+                Ok(Complex::from_floats(float_exp(float_mul(
+                    other.to_floats(),
+                    float_ln(z),
+                ))))
+            }
+        }
+    }
+
+    /// The principal value of a builtin function, `None` for functions that
+    /// have no complex counterpart.
+    pub fn function(&self, name: &str) -> Option<Parameters> {
+        // √(-r) = i√r is kept exact for a negative rational
+        if let ("sqrt", Some(r), true) = (name, to_rational(&self.re), is_zero(&self.im)) {
+            if let Some(root) = Radicals::sqrt(&r.abs()) {
+                return Some(Complex::from_parts(
+                    Parameters::Int(0),
+                    match root.to_rational() {
+                        Some(q) => rational_parameter(q),
+                        None => Parameters::Radical(root),
+                    },
+                ));
+            }
+        }
+        float_function(name, self.to_floats()).map(Complex::from_floats)
+    }
+}
+
+fn to_rational(p: &Parameters) -> Option<Rationals> {
+    match p {
+        Parameters::Int(i) => Some(Rationals::new(1, *i)),
+        Parameters::Rational(r) => Some(r.clone()),
+        _ => None,
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if POLAR.with(|p| p.get()) {
+            let modulus = self.abs().unwrap_or(Parameters::Float(f64::NAN));
+            return write!(f, "{modulus}∠{}", format_float(self.arg()));
+        }
+        let negative = to_float(&self.im).is_some_and(|f| f < 0.0);
+        let magnitude = if negative {
+            self.im.clone().abs(None)
+        } else {
+            self.im.clone()
+        };
+        // integers and floats are juxtaposed, others are multiplied
+        let imaginary = match &magnitude {
+            Parameters::Int(1) => "i".to_string(),
+            Parameters::Int(_) | Parameters::Float(_) => format!("{magnitude}i"),
+            _ => format!("{magnitude}*i"),
+        };
+        let sign = if negative { "-" } else { "+" };
+        if is_zero(&self.re) {
+            write!(f, "{}{imaginary}", if negative { "-" } else { "" })
+        } else {
+            write!(f, "{}{sign}{imaginary}", self.re)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Complex;
+    use crate::exact_math::rationals::Rationals;
+    use crate::parsing::ast::Parameters;
+
+    fn complex(re: i64, im: i64) -> Complex {
+        Complex::new(Parameters::Int(re), Parameters::Int(im))
+    }
+
+    fn value(re: i64, im: i64) -> Parameters {
+        Parameters::Complex(Box::from(complex(re, im)))
+    }
+
+    #[test]
+    pub fn test_display() {
+        assert_eq!(complex(1, 2).to_string(), "1+2i");
+        assert_eq!(complex(3, -1).to_string(), "3-i");
+        assert_eq!(complex(0, -2).to_string(), "-2i");
+        let half = Complex::new(
+            Parameters::Int(1),
+            Parameters::Rational(Rationals::new(2, 1)),
+        );
+        assert_eq!(half.to_string(), "1+1/2*i");
+    }
+
+    #[test]
+    pub fn test_exact_arithmetic() {
+        assert_eq!(complex(1, 2).mul(&complex(3, -1)), Ok(value(5, 5)));
+        assert_eq!(complex(5, 5).div(&complex(3, -1)), Ok(value(1, 2)));
+        // (1+i)^2 = 2i, i^2 = -1
+        assert_eq!(complex(1, 1).pow(&complex(2, 0)), Ok(value(0, 2)));
+        assert_eq!(Complex::i().pow(&complex(2, 0)), Ok(Parameters::Int(-1)));
+        assert!(complex(1, 1).div(&complex(0, 0)).is_err());
+    }
+
+    #[test]
+    pub fn test_functions() {
+        assert_eq!(complex(-4, 0).function("sqrt"), Some(value(0, 2)));
+        assert_eq!(complex(3, 4).abs(), Ok(Parameters::Int(5)));
+        // e^(iπ) = -1
+        let z = Complex::new(Parameters::Int(0), Parameters::Float(std::f64::consts::PI));
+        assert_eq!(z.function("exp"), Some(Parameters::Float(-1.0)));
+        let (re, im) = match complex(-1, 0).function("ln") {
+            Some(Parameters::Complex(z)) => z.to_floats(),
+            p => panic!("expected a complex number, found {p:?}"),
+        };
+        assert_eq!((re, im), (0.0, std::f64::consts::PI));
+    }
+}
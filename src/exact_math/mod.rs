@@ -1 +1,6 @@
+pub mod complex;
+pub mod polynomial;
+pub mod radicals;
 pub mod rationals;
+pub mod symbolic;
+pub mod units;
@@ -0,0 +1,684 @@
+use std::f64::consts::PI;
+use std::{fmt::Display, ops};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive};
+
+use crate::exact_math::complex::{float_div, float_mul, Complex, FloatComplex};
+use crate::exact_math::radicals::Radicals;
+use crate::exact_math::rationals::Rationals;
+use crate::exact_math::symbolic::Symbolic;
+use crate::parsing::ast::Parameters;
+use crate::utils::integer_utils::int_or_big;
+
+// the rational root theorem is only tried when there are at most this many candidates
+const MAX_CANDIDATES: usize = 100_000;
+const MAX_ITERATIONS: usize = 1000;
+
+/// A polynomial in one variable with rational coefficients, stored from the
+/// constant term up. The leading coefficient is never zero, so the zero
+/// polynomial has no coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    pub coefficients: Vec<Rationals>,
+    pub variable: String,
+}
+
+fn zero() -> Rationals {
+    Rationals::new(1, 0)
+}
+
+/// A rational as the simplest number parameter.
+pub fn rational_parameter(r: Rationals) -> Parameters {
+    let r = r.reduce();
+    if r.under.is_one() {
+        int_or_big(r.over)
+    } else {
+        Parameters::Rational(r)
+    }
+}
+
+/// The positive divisors of `n`, `None` when `n` is too large to be factored
+/// by trial division.
+fn divisors(n: &BigInt) -> Option<Vec<BigInt>> {
+    let n = n.abs().to_u64().filter(|n| *n <= 1 << 40)?;
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let mut d = 1;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            small.push(BigInt::from(d));
+            if d * d != n {
+                large.push(BigInt::from(n / d));
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    Some(small)
+}
+
+impl Polynomial {
+    pub fn new(coefficients: Vec<Rationals>, variable: &str) -> Self {
+        let mut coefficients: Vec<Rationals> =
+            coefficients.into_iter().map(|c| c.reduce()).collect();
+        while coefficients.last().is_some_and(|c| c.clone().is_null()) {
+            coefficients.pop();
+        }
+        Polynomial {
+            coefficients,
+            variable: variable.to_string(),
+        }
+    }
+
+    pub fn constant(c: Rationals, variable: &str) -> Self {
+        Polynomial::new(vec![c], variable)
+    }
+
+    /// The polynomial `x`.
+    pub fn variable(variable: &str) -> Self {
+        Polynomial::new(vec![zero(), Rationals::new(1, 1)], variable)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// The degree, zero for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    fn leading(&self) -> Rationals {
+        self.coefficients.last().cloned().unwrap_or_else(zero)
+    }
+
+    /// The polynomial in `variable` equal to a symbolic expression, `None` when
+    /// the expression involves anything else than rational numbers, `variable`
+    /// and non negative integer powers.
+    pub fn from_symbolic(s: &Symbolic, variable: &str) -> Option<Self> {
+        match s {
+            Symbolic::Number(Parameters::Int(i)) => {
+                Some(Polynomial::constant(Rationals::new(1, *i), variable))
+            }
+            Symbolic::Number(Parameters::Rational(r)) => {
+                Some(Polynomial::constant(r.clone(), variable))
+            }
+            Symbolic::Symbol(x) if x == variable => Some(Polynomial::variable(variable)),
+            Symbolic::Sum(terms) => terms
+                .iter()
+                .try_fold(Polynomial::constant(zero(), variable), |acc, t| {
+                    Some(acc + Polynomial::from_symbolic(t, variable)?)
+                }),
+            Symbolic::Product(factors) => factors.iter().try_fold(
+                Polynomial::constant(Rationals::new(1, 1), variable),
+                |acc, f| Some(acc * Polynomial::from_symbolic(f, variable)?),
+            ),
+            Symbolic::Power(base, exponent) => match exponent.as_ref() {
+                Symbolic::Number(Parameters::Int(n)) if *n >= 0 => {
+                    Some(Polynomial::from_symbolic(base, variable)?.pow(u32::try_from(*n).ok()?))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn to_symbolic(&self) -> Symbolic {
+        let terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .map(|(n, c)| {
+                Symbolic::Product(vec![
+                    Symbolic::Number(rational_parameter(c.clone())),
+                    Symbolic::Power(
+                        Box::from(Symbolic::Symbol(self.variable.clone())),
+                        Box::from(Symbolic::Number(Parameters::Int(n as i64))),
+                    ),
+                ])
+            })
+            .collect();
+        Symbolic::Sum(terms).simplify()
+    }
+
+    /// Exact value at a rational, by Horner's rule.
+    pub fn evaluate(&self, x: &Rationals) -> Rationals {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(zero(), |acc, c| acc * x.clone() + c.clone())
+    }
+
+    fn evaluate_complex(&self, z: FloatComplex) -> FloatComplex {
+        self.coefficients.iter().rev().fold((0.0, 0.0), |acc, c| {
+            let (re, im) = float_mul(acc, z);
+            (re + c.clone().approx(), im)
+        })
+    }
+
+    pub fn derivative(&self) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(n, c)| c.clone() * Rationals::new(1, n as i64))
+            .collect();
+        Polynomial::new(coefficients, &self.variable)
+    }
+
+    pub fn pow(&self, n: u32) -> Self {
+        (0..n).fold(
+            Polynomial::constant(Rationals::new(1, 1), &self.variable),
+            |acc, _| acc * self.clone(),
+        )
+    }
+
+    /// Euclidean division, `self = q·other + r` with `deg r < deg other`.
+    /// `None` when dividing by the zero polynomial.
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+        let mut quotient = vec![zero(); self.degree().saturating_sub(other.degree()) + 1];
+        let mut remainder = self.clone();
+        let leading = other.leading();
+        while !remainder.is_zero() && remainder.degree() >= other.degree() {
+            let shift = remainder.degree() - other.degree();
+            let factor = remainder.leading() / leading.clone();
+            quotient[shift] = factor.clone();
+            let mut coefficients = remainder.coefficients.clone();
+            for (n, c) in other.coefficients.iter().enumerate() {
+                coefficients[n + shift] =
+                    coefficients[n + shift].clone() - factor.clone() * c.clone();
+            }
+            // the leading term cancels exactly
+            coefficients.pop();
+            remainder = Polynomial::new(coefficients, &self.variable);
+        }
+        Some((Polynomial::new(quotient, &self.variable), remainder))
+    }
+
+    /// The polynomial divided by its leading coefficient.
+    pub fn monic(&self) -> Self {
+        let leading = self.leading();
+        if self.is_zero() {
+            return self.clone();
+        }
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|c| c.clone() / leading.clone())
+            .collect();
+        Polynomial::new(coefficients, &self.variable)
+    }
+
+    /// The monic greatest common divisor, by Euclid's algorithm.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b).unwrap();
+            (a, b) = (b, r);
+        }
+        a.monic()
+    }
+
+    /// The constant `c` and the polynomial with coprime integer coefficients
+    /// and a positive leading one whose product is `self`.
+    fn primitive(&self) -> (Rationals, Self) {
+        let lcm = self
+            .coefficients
+            .iter()
+            .fold(BigInt::one(), |acc, c| acc.lcm(&c.clone().reduce().under));
+        let integers: Vec<BigInt> = self
+            .coefficients
+            .iter()
+            .map(|c| {
+                let c = c.clone().reduce();
+                c.over * (&lcm / c.under)
+            })
+            .collect();
+        let mut gcd = integers.iter().fold(BigInt::from(0), |acc, c| acc.gcd(c));
+        if self.leading() < zero() {
+            gcd = -gcd;
+        }
+        let coefficients = integers
+            .into_iter()
+            .map(|c| Rationals::from_bigint(BigInt::one(), c / &gcd))
+            .collect();
+        (
+            Rationals::from_bigint(lcm, gcd).reduce(),
+            Polynomial::new(coefficients, &self.variable),
+        )
+    }
+
+    /// Factors out the rational roots: a constant, then factors with integer
+    /// coefficients and their multiplicity, `x` and one `qx-p` for each root
+    /// `p/q` in increasing order, what is left being the last factor.
+    pub fn factor(&self) -> (Rationals, Vec<(Self, usize)>) {
+        if self.degree() == 0 {
+            return (self.leading(), Vec::new());
+        }
+        let (constant, mut p) = self.primitive();
+        let mut factors = Vec::new();
+        let zeros = p
+            .coefficients
+            .iter()
+            .take_while(|c| (*c).clone().is_null())
+            .count();
+        if zeros > 0 {
+            factors.push((Polynomial::variable(&p.variable), zeros));
+            p = Polynomial::new(p.coefficients[zeros..].to_vec(), &p.variable);
+        }
+        let mut candidates = p.rational_root_candidates();
+        candidates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for r in candidates {
+            let r = r.reduce();
+            let linear = Polynomial::new(
+                vec![
+                    Rationals::from_bigint(BigInt::one(), -r.over.clone()),
+                    Rationals::from_bigint(BigInt::one(), r.under.clone()),
+                ],
+                &p.variable,
+            );
+            let mut multiplicity = 0;
+            while p.degree() > 0 && p.evaluate(&r).is_null() {
+                multiplicity += 1;
+                p = p.div_rem(&linear).unwrap().0;
+            }
+            if multiplicity > 0 {
+                factors.push((linear, multiplicity));
+            }
+        }
+        if p.degree() > 0 {
+            factors.push((p, 1));
+        }
+        (constant, factors)
+    }
+
+    /// Candidates `±p/q` of the rational root theorem, `p` dividing the
+    /// constant term and `q` the leading coefficient once the coefficients
+    /// are made integers.
+    fn rational_root_candidates(&self) -> Vec<Rationals> {
+        let lcm = self
+            .coefficients
+            .iter()
+            .fold(BigInt::one(), |acc, c| acc.lcm(&c.clone().reduce().under));
+        let integer = |c: &Rationals| {
+            let c = c.clone().reduce();
+            c.over * (&lcm / c.under)
+        };
+        let (constant, leading) = (integer(&self.coefficients[0]), integer(&self.leading()));
+        let (Some(ps), Some(qs)) = (divisors(&constant), divisors(&leading)) else {
+            return Vec::new();
+        };
+        if ps.len().saturating_mul(qs.len()) > MAX_CANDIDATES {
+            return Vec::new();
+        }
+        let mut candidates = Vec::new();
+        for p in &ps {
+            for q in &qs {
+                if p.gcd(q).is_one() {
+                    candidates.push(Rationals::from_bigint(q.clone(), p.clone()));
+                    candidates.push(Rationals::from_bigint(q.clone(), -p.clone()));
+                }
+            }
+        }
+        candidates
+    }
+
+    /// The roots of a cubic without rational roots, with the trigonometric
+    /// form when all three are real and Cardano's otherwise, the complex pair
+    /// then coming from the quadratic left by the real root.
+    fn cubic_roots(&self) -> Vec<FloatComplex> {
+        let c: Vec<f64> = self
+            .monic()
+            .coefficients
+            .into_iter()
+            .map(|c| c.approx())
+            .collect();
+        let (a, b, c) = (c[2], c[1], c[0]);
+        // x = t - a/3 gives t³ + pt + q
+        let p = b - a * a / 3.0;
+        let q = 2.0 * a.powi(3) / 27.0 - a * b / 3.0 + c;
+        let discriminant = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+        let roots = if p == 0.0 || discriminant > 0.0 {
+            let s = discriminant.sqrt();
+            let t = if p == 0.0 {
+                (-q).cbrt()
+            } else {
+                (-q / 2.0 + s).cbrt() + (-q / 2.0 - s).cbrt()
+            };
+            // t² + t·t0 + t0² + p = 0
+            let im = (3.0 * t * t + 4.0 * p).max(0.0).sqrt() / 2.0;
+            vec![(t, 0.0), (-t / 2.0, -im), (-t / 2.0, im)]
+        } else {
+            let r = 2.0 * (-p / 3.0).sqrt();
+            let phi = ((3.0 * q / (2.0 * p)) * (-3.0 / p).sqrt())
+                .clamp(-1.0, 1.0)
+                .acos()
+                / 3.0;
+            (0..3)
+                .map(|k| (r * (phi - 2.0 * PI * k as f64 / 3.0).cos(), 0.0))
+                .collect()
+        };
+        roots.into_iter().map(|(t, im)| (t - a / 3.0, im)).collect()
+    }
+
+    /// All the complex roots by the Durand-Kerner iteration.
+    fn durand_kerner(&self) -> Vec<FloatComplex> {
+        let p = self.monic();
+        let n = p.degree();
+        let seed = (0.4, 0.9);
+        let mut roots: Vec<FloatComplex> = (0..n)
+            .scan((1.0, 0.0), |z, _| {
+                *z = float_mul(*z, seed);
+                Some(*z)
+            })
+            .collect();
+        for _ in 0..MAX_ITERATIONS {
+            let mut change: f64 = 0.0;
+            for i in 0..n {
+                let mut denominator = (1.0, 0.0);
+                for j in 0..n {
+                    if i != j {
+                        let d = (roots[i].0 - roots[j].0, roots[i].1 - roots[j].1);
+                        denominator = float_mul(denominator, d);
+                    }
+                }
+                let step = float_div(p.evaluate_complex(roots[i]), denominator);
+                roots[i] = (roots[i].0 - step.0, roots[i].1 - step.1);
+                change = change.max(step.0.hypot(step.1));
+            }
+            if change < 1e-14 {
+                break;
+            }
+        }
+        roots
+    }
+
+    /// The complex roots ordered by real then imaginary part, repeated by
+    /// multiplicity. Rational roots are exact, so are the roots of a quadratic
+    /// factor, the others are approximated.
+    pub fn roots(&self) -> Vec<Parameters> {
+        let mut roots: Vec<Parameters> = Vec::new();
+        if self.is_zero() {
+            return roots;
+        }
+        let mut p = self.clone();
+        for r in p.rational_root_candidates() {
+            let linear =
+                Polynomial::new(vec![zero() - r.clone(), Rationals::new(1, 1)], &p.variable);
+            while p.degree() > 0 && p.evaluate(&r).is_null() {
+                roots.push(rational_parameter(r.clone()));
+                p = p.div_rem(&linear).unwrap().0;
+            }
+        }
+        // candidates are not computed when the coefficients are too large
+        while p.degree() > 0 && p.coefficients[0].clone().is_null() {
+            roots.push(Parameters::Int(0));
+            p = Polynomial::new(p.coefficients[1..].to_vec(), &p.variable);
+        }
+
+        match p.degree() {
+            0 => (),
+            1 => roots.push(rational_parameter(
+                zero() - p.coefficients[0].clone() / p.leading(),
+            )),
+            2 => {
+                let (c, b, a) = (
+                    p.coefficients[0].clone(),
+                    p.coefficients[1].clone(),
+                    p.coefficients[2].clone(),
+                );
+                let discriminant = b.clone() * b.clone() - Rationals::new(1, 4) * a.clone() * c;
+                let two_a = Radicals::from_rational(Rationals::new(1, 2) * a);
+                let minus_b = Radicals::from_rational(zero() - b);
+                let exact = |r: Radicals| match r.to_rational() {
+                    Some(r) => rational_parameter(r),
+                    None => Parameters::Radical(r),
+                };
+                let real = discriminant >= zero();
+                let magnitude = if real {
+                    discriminant
+                } else {
+                    zero() - discriminant
+                };
+                // (-b ± √Δ)/2a, with √Δ = i√(-Δ) for a conjugate pair
+                let (re, im) = match Radicals::sqrt(&magnitude) {
+                    Some(s) if real => (
+                        vec![minus_b.clone() - s.clone(), minus_b + s]
+                            .into_iter()
+                            .map(|r| exact(r.checked_div(&two_a).unwrap()))
+                            .collect(),
+                        vec![Parameters::Int(0); 2],
+                    ),
+                    Some(s) => (
+                        vec![exact(minus_b.checked_div(&two_a).unwrap()); 2],
+                        vec![-s.clone(), s]
+                            .into_iter()
+                            .map(|r| exact(r.checked_div(&two_a).unwrap()))
+                            .collect(),
+                    ),
+                    None => {
+                        let (a, b) = (two_a.approx(), minus_b.approx());
+                        let s = magnitude.approx().sqrt();
+                        if real {
+                            let re = vec![(b - s) / a, (b + s) / a];
+                            (
+                                re.into_iter().map(Parameters::Float).collect(),
+                                vec![Parameters::Int(0); 2],
+                            )
+                        } else {
+                            let im = vec![-s / a, s / a];
+                            (
+                                vec![Parameters::Float(b / a); 2],
+                                im.into_iter().map(Parameters::Float).collect(),
+                            )
+                        }
+                    }
+                };
+                roots.extend(
+                    re.into_iter()
+                        .zip(im)
+                        .map(|(re, im)| Complex::from_parts(re, im)),
+                );
+            }
+            3 => roots.extend(p.cubic_roots().into_iter().map(Complex::from_floats)),
+            _ => {
+                let complex = p.durand_kerner();
+                let scale = complex
+                    .iter()
+                    .fold(1.0_f64, |m, z| m.max(z.0.abs()).max(z.1.abs()));
+                // roots close enough to the real axis are real
+                roots.extend(complex.into_iter().map(|(re, im)| {
+                    let im = if im.abs() <= 1e-8 * scale { 0.0 } else { im };
+                    Complex::from_floats((re, im))
+                }))
+            }
+        }
+
+        let value = |p: &Parameters| match Complex::from_parameter(p) {
+            Some(z) => z.to_floats(),
+            None => (f64::NAN, f64::NAN),
+        };
+        roots.sort_by(|a, b| {
+            let (a, b) = (value(a), value(b));
+            a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+        });
+        roots
+    }
+}
+
+impl Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_symbolic())
+    }
+}
+
+impl ops::Neg for Polynomial {
+    type Output = Polynomial;
+    fn neg(self) -> Self::Output {
+        let coefficients = self.coefficients.into_iter().map(|c| zero() - c).collect();
+        Polynomial::new(coefficients, &self.variable)
+    }
+}
+
+impl ops::Add for Polynomial {
+    type Output = Polynomial;
+    fn add(self, rhs: Self) -> Self::Output {
+        let n = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficients = (0..n)
+            .map(|i| {
+                let a = self.coefficients.get(i).cloned().unwrap_or_else(zero);
+                let b = rhs.coefficients.get(i).cloned().unwrap_or_else(zero);
+                a + b
+            })
+            .collect();
+        Polynomial::new(coefficients, &self.variable)
+    }
+}
+
+impl ops::Sub for Polynomial {
+    type Output = Polynomial;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl ops::Mul for Polynomial {
+    type Output = Polynomial;
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::new(Vec::new(), &self.variable);
+        }
+        let mut coefficients = vec![zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] = coefficients[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+        Polynomial::new(coefficients, &self.variable)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Polynomial;
+    use crate::exact_math::rationals::Rationals;
+    use crate::parsing::ast::Parameters;
+
+    /// A polynomial in x from its coefficients, highest degree first.
+    fn poly(coefficients: &[i64]) -> Polynomial {
+        let coefficients = coefficients
+            .iter()
+            .rev()
+            .map(|c| Rationals::new(1, *c))
+            .collect();
+        Polynomial::new(coefficients, "x")
+    }
+
+    #[test]
+    pub fn test_display() {
+        assert_eq!(poly(&[1, 0, -2]).to_string(), "x^2-2");
+        assert_eq!(poly(&[0, 0]).to_string(), "0");
+    }
+
+    #[test]
+    pub fn test_div_rem() {
+        let (q, r) = poly(&[1, 0, 0, -1]).div_rem(&poly(&[1, 1])).unwrap();
+        assert_eq!(q, poly(&[1, -1, 1]));
+        assert_eq!(r, poly(&[-2]));
+        assert!(poly(&[1]).div_rem(&poly(&[])).is_none());
+    }
+
+    #[test]
+    pub fn test_gcd() {
+        // (x-1)(x+2) and (x-1)(x-3)
+        let gcd = poly(&[1, 1, -2]).gcd(&poly(&[1, -4, 3]));
+        assert_eq!(gcd, poly(&[1, -1]));
+    }
+
+    #[test]
+    pub fn test_factor() {
+        let factored = |p: Polynomial| {
+            let (c, factors) = p.factor();
+            let factors: Vec<(String, usize)> =
+                factors.iter().map(|(f, m)| (f.to_string(), *m)).collect();
+            (c.to_string(), factors)
+        };
+        // 2x³ + 11x² + 12x - 9 = (2x-1)(x+3)²
+        let expected = vec![("x+3".to_string(), 2), ("2x-1".to_string(), 1)];
+        assert_eq!(
+            factored(poly(&[2, 11, 12, -9])),
+            ("1".to_string(), expected)
+        );
+        // -2x⁴ + 4x² = -2x²(x²-2)
+        let expected = vec![("x".to_string(), 2), ("x^2-2".to_string(), 1)];
+        assert_eq!(
+            factored(poly(&[-2, 0, 4, 0, 0])),
+            ("-2".to_string(), expected)
+        );
+        assert_eq!(factored(poly(&[3])), ("3".to_string(), vec![]));
+    }
+
+    #[test]
+    pub fn test_derivative() {
+        assert_eq!(poly(&[1, 3, 0, 5]).derivative(), poly(&[3, 6, 0]));
+    }
+
+    #[test]
+    pub fn test_rational_roots() {
+        // 2(x-1/2)(x+3)² has the roots -3, -3 and 1/2
+        let roots = poly(&[2, 11, 12, -9]).roots();
+        let expected = vec![
+            Parameters::Int(-3),
+            Parameters::Int(-3),
+            Parameters::Rational(Rationals::new(2, 1)),
+        ];
+        assert_eq!(roots, expected);
+    }
+
+    #[test]
+    pub fn test_quadratic_roots() {
+        let roots: Vec<String> = poly(&[1, 0, -2])
+            .roots()
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(roots, vec!["-√2", "√2"]);
+        let roots: Vec<String> = poly(&[2, 2, 3])
+            .roots()
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(roots, vec!["-1/2-√5/2*i", "-1/2+√5/2*i"]);
+    }
+
+    #[test]
+    pub fn test_numeric_roots() {
+        // x³ - 2 has a single real root and a complex pair, x⁴ - 5x² + 5 has
+        // four real roots
+        let value = |p: &Parameters| match p {
+            Parameters::Float(f) => *f,
+            p => panic!("expected a float, found {p}"),
+        };
+        let roots = poly(&[1, 0, 0, -2]).roots();
+        assert_eq!(roots.len(), 3);
+        assert!((value(&roots[2]) - 2f64.cbrt()).abs() < 1e-12);
+        match &roots[1] {
+            Parameters::Complex(z) => {
+                let (re, im) = z.to_floats();
+                assert!((re + 2f64.cbrt() / 2.0).abs() < 1e-12);
+                assert!((im - 2f64.cbrt() * 3f64.sqrt() / 2.0).abs() < 1e-12);
+            }
+            p => panic!("expected a complex number, found {p}"),
+        }
+        let roots = poly(&[1, 0, -5, 0, 5]).roots();
+        assert_eq!(roots.len(), 4);
+        let largest = ((5.0 + 5f64.sqrt()) / 2.0).sqrt();
+        assert!((value(&roots[3]) - largest).abs() < 1e-10);
+    }
+}
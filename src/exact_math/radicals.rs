@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::{fmt::Display, ops};
+
+use num_bigint::BigInt;
+use num_integer::{Integer, Roots};
+use num_traits::{One, Signed, ToPrimitive};
+
+use crate::exact_math::rationals::Rationals;
+
+/// A sum of terms `c√k` with rational coefficients `c` and distinct square-free
+/// radicands `k`, the rational part being stored under the radicand 1. Terms
+/// with a zero coefficient are never stored, so the zero value has no terms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Radicals {
+    pub terms: BTreeMap<BigInt, Rationals>,
+}
+
+/// Splits `n` into `(s, f)` with `n = s²f` and `f` square-free.
+fn square_free(mut n: u64) -> (u64, u64) {
+    let (mut square, mut free) = (1, 1);
+    let mut p = 2;
+    // once p³ > n what is left is 1, a prime, or a product of two primes
+    while (p as u128).pow(3) <= n as u128 {
+        let mut count = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            count += 1;
+        }
+        square *= p.pow(count / 2);
+        if count % 2 == 1 {
+            free *= p;
+        }
+        p += 1;
+    }
+    let root = n.sqrt();
+    if root * root == n {
+        (square * root, free)
+    } else {
+        (square, free * n)
+    }
+}
+
+impl Radicals {
+    pub fn from_rational(r: Rationals) -> Self {
+        let mut terms = BTreeMap::new();
+        if !r.clone().is_null() {
+            terms.insert(BigInt::one(), r.reduce());
+        }
+        Radicals { terms }
+    }
+
+    /// The exact square root of a non negative rational, `None` when the
+    /// radicand is negative or too large to be factored.
+    pub fn sqrt(r: &Rationals) -> Option<Self> {
+        let r = r.clone().reduce();
+        if r.over.is_negative() {
+            return None;
+        }
+        // √(p/q) = √(pq)/q
+        let (square, free) = square_free((&r.over * &r.under).to_u64()?);
+        let coefficient = Rationals::from_bigint(r.under, BigInt::from(square)).reduce();
+        Some(Radicals::term(coefficient, BigInt::from(free)))
+    }
+
+    fn term(coefficient: Rationals, radicand: BigInt) -> Self {
+        let mut terms = BTreeMap::new();
+        if !coefficient.clone().is_null() {
+            terms.insert(radicand, coefficient);
+        }
+        Radicals { terms }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// The value as a rational when no irrational term is left.
+    pub fn to_rational(&self) -> Option<Rationals> {
+        match self.terms.len() {
+            0 => Some(Rationals::new(1, 0)),
+            1 => self.terms.get(&BigInt::one()).cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn approx(&self) -> f64 {
+        self.terms
+            .iter()
+            .map(|(k, c)| c.clone().approx() * k.to_f64().unwrap_or(f64::NAN).sqrt())
+            .sum()
+    }
+
+    /// Raises to an integer power by repeated squaring, `None` when dividing
+    /// by zero.
+    pub fn pow(&self, exponent: i64) -> Option<Self> {
+        let mut result = Radicals::from_rational(Rationals::new(1, 1));
+        let mut base = self.clone();
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n % 2 == 1 {
+                result = result * base.clone();
+            }
+            base = base.clone() * base;
+            n /= 2;
+        }
+        if exponent < 0 {
+            Radicals::from_rational(Rationals::new(1, 1)).checked_div(&result)
+        } else {
+            Some(result)
+        }
+    }
+
+    /// Exact division, the denominator is made rational by multiplying both
+    /// sides with conjugates. `None` when dividing by zero.
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let mut numerator = self.clone();
+        let mut denominator = other.clone();
+        loop {
+            if let Some(r) = denominator.to_rational() {
+                let inverse = Rationals::new(1, 1) / r;
+                return Some(numerator * Radicals::from_rational(inverse));
+            }
+            let d = denominator.splitting_factor();
+            // with D = A + √d·B, D·(A - √d·B) = A² - d·B² no longer involves d
+            let conjugate = denominator.conjugate(&d);
+            numerator = numerator * conjugate.clone();
+            denominator = denominator * conjugate;
+        }
+    }
+
+    /// A factor `d > 1` of some radicand such that every radicand is either a
+    /// multiple of `d` or coprime to it.
+    fn splitting_factor(&self) -> BigInt {
+        let mut d = self
+            .terms
+            .keys()
+            .find(|k| !k.is_one())
+            .cloned()
+            .unwrap_or_else(BigInt::one);
+        loop {
+            let g = self
+                .terms
+                .keys()
+                .map(|k| k.gcd(&d))
+                .find(|g| !g.is_one() && g != &d);
+            match g {
+                Some(g) => d = g,
+                None => return d,
+            }
+        }
+    }
+
+    fn conjugate(&self, d: &BigInt) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .map(|(k, c)| {
+                if k.is_multiple_of(d) {
+                    (k.clone(), Rationals::new(1, 0) - c.clone())
+                } else {
+                    (k.clone(), c.clone())
+                }
+            })
+            .collect();
+        Radicals { terms }
+    }
+}
+
+impl Display for Radicals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        for (i, (k, c)) in self.terms.iter().enumerate() {
+            let c = c.clone().reduce();
+            let negative = c.over.is_negative();
+            if negative {
+                write!(f, "-")?;
+            } else if i > 0 {
+                write!(f, "+")?;
+            }
+            let c = c.abs();
+            if k.is_one() {
+                write!(f, "{c}")?;
+                continue;
+            }
+            if !c.over.is_one() {
+                write!(f, "{}", c.over)?;
+            }
+            write!(f, "√{k}")?;
+            if !c.under.is_one() {
+                write!(f, "/{}", c.under)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ops::Neg for Radicals {
+    type Output = Radicals;
+    fn neg(self) -> Self::Output {
+        let terms = self
+            .terms
+            .into_iter()
+            .map(|(k, c)| (k, Rationals::new(1, 0) - c))
+            .collect();
+        Radicals { terms }
+    }
+}
+
+impl ops::Add for Radicals {
+    type Output = Radicals;
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut terms = self.terms;
+        for (k, c) in rhs.terms {
+            let sum = match terms.remove(&k) {
+                Some(c2) => c2 + c,
+                None => c,
+            };
+            if !sum.clone().is_null() {
+                terms.insert(k, sum);
+            }
+        }
+        Radicals { terms }
+    }
+}
+
+impl ops::Sub for Radicals {
+    type Output = Radicals;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl ops::Mul for Radicals {
+    type Output = Radicals;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = Radicals::from_rational(Rationals::new(1, 0));
+        for (k1, c1) in self.terms.iter() {
+            for (k2, c2) in rhs.terms.iter() {
+                // √a·√b = g·√(a/g · b/g) with g = gcd(a, b), a/g and b/g being
+                // coprime and square-free
+                let g = k1.gcd(k2);
+                let radicand = (k1 / &g) * (k2 / &g);
+                let coefficient =
+                    c1.clone() * c2.clone() * Rationals::from_bigint(BigInt::one(), g);
+                result = result + Radicals::term(coefficient, radicand);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{square_free, Radicals};
+    use crate::exact_math::rationals::Rationals;
+
+    fn sqrt(i: i64) -> Radicals {
+        Radicals::sqrt(&Rationals::new(1, i)).unwrap()
+    }
+
+    #[test]
+    pub fn test_square_free() {
+        assert_eq!(square_free(8), (2, 2));
+        assert_eq!(square_free(72), (6, 2));
+        assert_eq!(square_free(49), (7, 1));
+        assert_eq!(square_free(1_000_000_007), (1, 1_000_000_007));
+    }
+
+    #[test]
+    pub fn test_display() {
+        assert_eq!(sqrt(8).to_string(), "2√2");
+        assert_eq!(
+            Radicals::sqrt(&Rationals::new(2, 1)).unwrap().to_string(),
+            "√2/2"
+        );
+        let sum = sqrt(2) - sqrt(3) + Radicals::from_rational(Rationals::new(1, 1));
+        assert_eq!(sum.to_string(), "1+√2-√3");
+    }
+
+    #[test]
+    pub fn test_mult() {
+        let expected = Radicals::from_rational(Rationals::new(1, 2));
+        assert_eq!(sqrt(2) * sqrt(2), expected);
+        assert_eq!((sqrt(6) * sqrt(10)).to_string(), "2√15");
+    }
+
+    #[test]
+    pub fn test_div() {
+        let one = Radicals::from_rational(Rationals::new(1, 1));
+        let value = one.clone().checked_div(&(one + sqrt(2))).unwrap();
+        assert_eq!(value.to_string(), "-1+√2");
+        let value = sqrt(2).checked_div(&(sqrt(3) + sqrt(6))).unwrap();
+        assert_eq!(value * (sqrt(3) + sqrt(6)), sqrt(2));
+    }
+
+    #[test]
+    pub fn test_pow() {
+        let expected = Radicals::from_rational(Rationals::new(4, 1));
+        assert_eq!(sqrt(2).pow(-4).unwrap(), expected);
+    }
+}
@@ -1,91 +1,241 @@
+use std::cell::Cell;
 use std::{fmt::Display, ops};
 
-use crate::utils::integer_utils::gcd;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Float, One, Signed, ToPrimitive, Zero};
+
+use crate::configuration::loader::{DEFAULT_MAX_DENOMINATOR, DEFAULT_TOLERANCE};
+use crate::utils::float_utils::format_decimal;
+use crate::utils::integer_utils::{format_integer, group_thousands, MAX_BITS};
+
+/// How non integral rational results are written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FractionDisplay {
+    /// `3/2`
+    Fraction,
+    /// `1.5000000000`
+    Decimal,
+    /// `1 1/2`
+    Mixed,
+}
+
+impl FractionDisplay {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fraction" => Some(FractionDisplay::Fraction),
+            "decimal" => Some(FractionDisplay::Decimal),
+            "mixed" => Some(FractionDisplay::Mixed),
+            _ => None,
+        }
+    }
+}
+
+thread_local! {
+    static MAX_DENOMINATOR: Cell<u64> = const { Cell::new(DEFAULT_MAX_DENOMINATOR) };
+    static TOLERANCE: Cell<f64> = const { Cell::new(DEFAULT_TOLERANCE) };
+    static FRACTION_DISPLAY: Cell<FractionDisplay> = const { Cell::new(FractionDisplay::Fraction) };
+}
+
+/// Chooses how rational results are printed.
+pub fn set_fraction_display(display: FractionDisplay) {
+    FRACTION_DISPLAY.with(|d| d.set(display));
+}
+
+/// A rational result in the configured display.
+pub fn format_rational(r: &Rationals) -> String {
+    let r = r.clone().reduce();
+    if r.under.is_one() {
+        return format_integer(&r.over);
+    }
+    let fraction = |over: &BigInt| {
+        format!(
+            "{}/{}",
+            group_thousands(&over.to_string()),
+            group_thousands(&r.under.to_string())
+        )
+    };
+    match FRACTION_DISPLAY.with(|d| d.get()) {
+        FractionDisplay::Decimal => format_decimal(&r),
+        FractionDisplay::Mixed if r.over.abs() > r.under => {
+            // the whole part carries the sign, -3/2 is -1 1/2
+            let (whole, rest) = r.over.abs().div_rem(&r.under);
+            let sign = if r.over.is_negative() { "-" } else { "" };
+            format!(
+                "{sign}{} {}",
+                group_thousands(&whole.to_string()),
+                fraction(&rest)
+            )
+        }
+        _ if r.over.is_negative() => format!("-{}", fraction(&-r.over.clone())),
+        _ => fraction(&r.over),
+    }
+}
+
+/// Sets the largest denominator and the relative tolerance used when float
+/// literals are turned into fractions.
+pub fn set_rationalize_limits(max_denominator: u64, tolerance: f64) {
+    MAX_DENOMINATOR.with(|m| m.set(max_denominator));
+    TOLERANCE.with(|t| t.set(tolerance));
+}
 
 #[derive(Debug, Clone)]
 pub struct Rationals {
-    pub under: i64,
-    pub over: i64,
+    pub under: BigInt,
+    pub over: BigInt,
 }
 
 impl Rationals {
-    pub fn put_to_denominator(&self, n: i64) -> Self {
+    pub fn put_to_denominator(&self, n: &BigInt) -> Self {
         Rationals {
-            under: self.under * n,
-            over: self.over * n,
+            under: &self.under * n,
+            over: &self.over * n,
         }
     }
     pub fn new(under: i64, over: i64) -> Self {
+        Rationals {
+            under: BigInt::from(under),
+            over: BigInt::from(over),
+        }
+    }
+
+    pub fn from_bigint(under: BigInt, over: BigInt) -> Self {
         Rationals { under, over }
     }
 
     pub fn approx(self) -> f64 {
-        return self.over as f64 / self.under as f64;
+        // keep both sides within the range of f64 before dividing
+        let bits = self.over.bits().max(self.under.bits());
+        let shift = bits.saturating_sub(1000);
+        let over = (&self.over >> shift).to_f64().unwrap_or(f64::NAN);
+        let under = (&self.under >> shift).to_f64().unwrap_or(f64::NAN);
+        over / under
+    }
+
+    /// The exact value of a finite float, `None` for infinities and NaN.
+    pub fn from_f64(f: f64) -> Option<Self> {
+        if !f.is_finite() {
+            return None;
+        }
+        let (mantissa, exponent, sign) = f.integer_decode();
+        let over = BigInt::from(mantissa) * sign;
+        let shift = exponent.unsigned_abs() as usize;
+        let r = if exponent >= 0 {
+            Rationals::from_bigint(BigInt::one(), over << shift)
+        } else {
+            Rationals::from_bigint(BigInt::one() << shift, over)
+        };
+        Some(r.reduce())
+    }
+
+    /// Simplest fraction within the configured tolerance of `f`, `None` when no
+    /// fraction with a small enough denominator is close enough.
+    pub fn rationalize(f: f64) -> Option<Self> {
+        let exact = Rationals::from_f64(f)?;
+        let r = exact.simplify();
+        if (r.clone() - exact).abs().approx() <= TOLERANCE.with(|t| t.get()) * f.abs() {
+            Some(r)
+        } else {
+            None
+        }
     }
 
-    pub fn rationalize(f: f64) -> Self {
-        let r = (f * (10.0_f64.powf(10.0))).round() as i64;
-        Rationals::new(10_i64.pow(10), r).reduce()
+    /// `best_approximation` with the configured maximum denominator and tolerance.
+    pub fn simplify(&self) -> Self {
+        let max_denominator = BigInt::from(MAX_DENOMINATOR.with(|m| m.get()));
+        self.best_approximation(&max_denominator, TOLERANCE.with(|t| t.get()))
+    }
+
+    /// Walks the continued fraction expansion and returns the first convergent
+    /// within `tolerance` (relative) of the value. When the denominators grow
+    /// past `max_denominator` first, the closest convergent or semiconvergent
+    /// with an allowed denominator is returned instead.
+    pub fn best_approximation(&self, max_denominator: &BigInt, tolerance: f64) -> Self {
+        let value = self.clone().reduce();
+        let max_denominator = max_denominator.max(&BigInt::one()).clone();
+        let target = value.clone().approx().abs() * tolerance;
+        let (mut h0, mut h1) = (BigInt::zero(), BigInt::one());
+        let (mut k0, mut k1) = (BigInt::one(), BigInt::zero());
+        let (mut n, mut d) = (value.over.clone(), value.under.clone());
+        loop {
+            let (a, rem) = n.div_mod_floor(&d);
+            let h2 = &a * &h1 + &h0;
+            let k2 = &a * &k1 + &k0;
+            if k2 > max_denominator {
+                let t = (&max_denominator - &k0) / &k1;
+                let semi = Rationals::from_bigint(&t * &k1 + &k0, &t * &h1 + &h0);
+                let last = Rationals::from_bigint(k1, h1);
+                let distance = |r: &Rationals| (r.clone() - value.clone()).abs();
+                return if distance(&semi) < distance(&last) {
+                    semi.reduce()
+                } else {
+                    last
+                };
+            }
+            (h0, h1) = (h1, h2);
+            (k0, k1) = (k1, k2);
+            let current = Rationals::from_bigint(k1.clone(), h1.clone());
+            if rem.is_zero() || (current.clone() - value.clone()).abs().approx() <= target {
+                return current;
+            }
+            (n, d) = (d, rem);
+        }
     }
 
     pub fn is_null(self) -> bool {
-        return self.over == 0;
+        self.over.is_zero()
     }
 
-    pub fn reduce(self) -> Self {
-        let minus;
-        let i1;
-        let i2;
-        if self.over < 0 && self.under > 0 {
-            minus = true;
-            i1 = -self.over;
-            i2 = self.under;
-        } else if self.over > 0 && self.under < 0 {
-            minus = true;
-            i1 = self.over;
-            i2 = -self.under;
-        } else if self.over < 0 && self.under < 0 {
-            minus = false;
-            i1 = -self.over;
-            i2 = -self.under;
+    pub fn is_integer(&self) -> bool {
+        let r = self.clone().reduce();
+        r.under == BigInt::from(1)
+    }
+
+    /// Raises to an integer power, `None` if the result would exceed `MAX_BITS`
+    /// or if zero is raised to a negative power.
+    pub fn checked_pow(&self, exponent: i64) -> Option<Self> {
+        let r = self.clone().reduce();
+        let n = exponent.unsigned_abs();
+        let bits = r.over.bits().max(r.under.bits());
+        if bits.saturating_mul(n) > MAX_BITS {
+            return None;
+        }
+        let n = u32::try_from(n).ok()?;
+        let (over, under) = (r.over.pow(n), r.under.pow(n));
+        if exponent >= 0 {
+            Some(Rationals::from_bigint(under, over))
+        } else if over.is_zero() {
+            None
         } else {
-            minus = false;
-            i1 = self.over;
-            i2 = self.under;
+            Some(Rationals::from_bigint(over, under).reduce())
         }
+    }
 
-        if i1 == 0 && i2 == 0 {
-            return Rationals { under: 0, over: 0 };
-        } else if i1 == 0 {
-            return Rationals { under: 1, over: 0 };
-        } else if i2 == 0 {
-            return Rationals {
-                under: 1,
-                over: i64::MAX,
-            };
+    pub fn reduce(self) -> Self {
+        // a zero denominator has no canonical form, divisions check for it
+        if self.under.is_zero() {
+            return self;
+        }
+        let gcd = self.over.gcd(&self.under);
+        let (under, over) = if self.under.is_negative() {
+            (-self.under, -self.over)
         } else {
-            let gcd = gcd(i1, i2);
-            let new_under = self.under.abs() / gcd;
-            let new_over = if minus {
-                -self.over.abs() / gcd
-            } else {
-                self.over.abs() / gcd
-            };
-            return Rationals {
-                under: new_under,
-                over: new_over,
-            };
+            (self.under, self.over)
+        };
+        Rationals {
+            under: under / &gcd,
+            over: over / &gcd,
         }
     }
     pub fn abs(self) -> Self {
-        Rationals::new(self.under.abs(), self.over.abs())
+        Rationals::from_bigint(self.under.abs(), self.over.abs())
     }
 }
 
 impl Display for Rationals {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let fs = self.clone().reduce();
-        if fs.under == 1 {
+        if fs.under == BigInt::from(1) {
             write!(f, "{}", fs.over)
         } else {
             write!(f, "{}/{}", fs.over, fs.under)
@@ -95,53 +245,14 @@ impl Display for Rationals {
 
 impl PartialEq for Rationals {
     fn eq(&self, other: &Self) -> bool {
-        if self.under == other.under {
-            return self.over == other.over;
-        } else {
-            let i1 = self.put_to_denominator(other.under);
-            let i2 = other.put_to_denominator(self.under);
-            return i1.over == i2.over;
-        }
-    }
-    fn ne(&self, other: &Self) -> bool {
-        return !self.eq(other);
+        &self.over * &other.under == &other.over * &self.under
     }
 }
 
 impl PartialOrd for Rationals {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if !(self.under == other.under) {
-            if self.over == other.over {
-                return Some(std::cmp::Ordering::Equal);
-            }
-            if self.over > other.over {
-                return Some(std::cmp::Ordering::Greater);
-            }
-            if self.over < other.over {
-                return Some(std::cmp::Ordering::Less);
-            }
-            None
-        } else {
-            let i1 = self.put_to_denominator(other.under);
-            let i2 = other.put_to_denominator(self.under);
-            if i1.over == i2.over {
-                return Some(std::cmp::Ordering::Equal);
-            }
-            if i1.over > i2.over {
-                return Some(std::cmp::Ordering::Greater);
-            }
-            if i1.over < i2.over {
-                return Some(std::cmp::Ordering::Less);
-            }
-            None
-        }
-    }
-
-    fn le(&self, other: &Self) -> bool {
-        return self < other || self == other;
-    }
-    fn ge(&self, other: &Self) -> bool {
-        return self > other || self == other;
+        let (s, o) = (self.clone().reduce(), other.clone().reduce());
+        (&s.over * &o.under).partial_cmp(&(&o.over * &s.under))
     }
 }
 
@@ -149,11 +260,11 @@ impl ops::Add for Rationals {
     type Output = Rationals;
     fn add(self, rhs: Self) -> Self::Output {
         if self.under == rhs.under {
-            Rationals::new(self.under, self.over + rhs.over).reduce()
+            Rationals::from_bigint(self.under, self.over + rhs.over).reduce()
         } else {
-            let f1 = self.put_to_denominator(rhs.under);
-            let f2 = rhs.put_to_denominator(self.under);
-            Rationals::new(f1.under, f1.over + f2.over).reduce()
+            let f1 = self.put_to_denominator(&rhs.under);
+            let f2 = rhs.put_to_denominator(&self.under);
+            Rationals::from_bigint(f1.under, f1.over + f2.over).reduce()
         }
     }
 }
@@ -161,14 +272,14 @@ impl ops::Add for Rationals {
 impl ops::Sub for Rationals {
     type Output = Rationals;
     fn sub(self, rhs: Self) -> Self::Output {
-        return self + Rationals::new(rhs.under, -rhs.over);
+        self + Rationals::from_bigint(rhs.under, -rhs.over)
     }
 }
 
 impl ops::Mul for Rationals {
     type Output = Rationals;
     fn mul(self, rhs: Self) -> Self::Output {
-        return Rationals::new(self.under * rhs.under, self.over * rhs.over).reduce();
+        Rationals::from_bigint(self.under * rhs.under, self.over * rhs.over).reduce()
     }
 }
 
@@ -177,19 +288,22 @@ impl ops::Div for Rationals {
     fn div(self, rhs: Self) -> Self::Output {
         let l = self.under * rhs.over;
         let rs = self.over * rhs.under;
-        let r = Rationals::new(l, rs);
+        let r = Rationals::from_bigint(l, rs);
         return r.reduce();
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Rationals;
+    use num_bigint::BigInt;
+
+    use super::{format_rational, set_fraction_display, FractionDisplay, Rationals};
+    use std::f64::consts::PI;
 
     #[test]
     pub fn test_denominator() {
         let expected = Rationals::new(10, 30);
-        let result = Rationals::new(2, 6).put_to_denominator(5);
+        let result = Rationals::new(2, 6).put_to_denominator(&BigInt::from(5));
         assert_eq!(expected, result);
     }
 
@@ -281,4 +395,71 @@ mod test {
         let value = Rationals::new(25, 32) / Rationals::new(76, 42);
         assert_eq!(value, expected);
     }
+
+    #[test]
+    pub fn test_no_overflow() {
+        let big = Rationals::new(1, i64::MAX);
+        let expected = "85070591730234615847396907784232501249";
+        assert_eq!((big.clone() * big).to_string(), expected);
+    }
+
+    #[test]
+    pub fn test_compare_different_denominators() {
+        let f1 = Rationals::new(3, 1);
+        let f2 = Rationals::new(2, 1);
+        assert!(f1 < f2);
+    }
+
+    #[test]
+    pub fn test_pow() {
+        let expected = Rationals::new(8, 1);
+        let value = Rationals::new(1, 2).checked_pow(-3).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    pub fn test_approx_big() {
+        let value = Rationals::new(1, 10).checked_pow(400).unwrap();
+        assert_eq!(value.approx(), f64::INFINITY);
+    }
+
+    #[test]
+    pub fn test_rationalize_literal() {
+        let expected = Rationals::new(10, 1);
+        let value = Rationals::rationalize(0.1).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    pub fn test_rationalize_keeps_small_values() {
+        let value = Rationals::rationalize(1e-15);
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    pub fn test_best_approximation_pi() {
+        let expected = "355/113";
+        let pi = Rationals::from_f64(PI).unwrap();
+        let value = pi.best_approximation(&BigInt::from(1000), 0.0);
+        assert_eq!(value.to_string(), expected);
+    }
+
+    #[test]
+    pub fn test_best_approximation_semiconvergent() {
+        let expected = "311/99";
+        let pi = Rationals::from_f64(PI).unwrap();
+        let value = pi.best_approximation(&BigInt::from(100), 0.0);
+        assert_eq!(value.to_string(), expected);
+    }
+
+    #[test]
+    pub fn test_format_rational() {
+        assert_eq!(format_rational(&Rationals::new(2, -3)), "-3/2");
+        set_fraction_display(FractionDisplay::Mixed);
+        assert_eq!(format_rational(&Rationals::new(2, -3)), "-1 1/2");
+        assert_eq!(format_rational(&Rationals::new(4, 1)), "1/4");
+        set_fraction_display(FractionDisplay::Decimal);
+        assert_eq!(format_rational(&Rationals::new(4, 1)), "0.2500000000");
+        set_fraction_display(FractionDisplay::Fraction);
+    }
 }
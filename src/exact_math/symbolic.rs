@@ -0,0 +1,669 @@
+use std::fmt::Display;
+
+use crate::errors::calc_error::CalcError;
+use crate::exact_math::rationals::Rationals;
+use crate::interpreting::function::{add, expo, mult, to_float};
+use crate::interpreting::stdlib::exec;
+use crate::parsing::ast::{Ast, Parameters};
+
+/// An expression over unbound identifiers. Subtraction is stored as a sum with
+/// a negated term and division as a product with a negative power, so that the
+/// simplifier only has to deal with sums, products and powers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbolic {
+    Number(Parameters),
+    Symbol(String),
+    Sum(Vec<Symbolic>),
+    Product(Vec<Symbolic>),
+    Power(Box<Symbolic>, Box<Symbolic>),
+    Function(String, Vec<Symbolic>),
+}
+
+fn is_exact(p: &Parameters) -> bool {
+    matches!(
+        p,
+        Parameters::Int(_) | Parameters::Rational(_) | Parameters::Radical(_)
+    )
+}
+
+/// Integer valued rationals become integers so that equal numbers compare equal.
+fn normalize(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Rational(r) if r.is_integer() => match r.clone().reduce().over.try_into() {
+            Ok(i) => Parameters::Int(i),
+            Err(_) => Parameters::Rational(r),
+        },
+        p => p,
+    }
+}
+
+fn number(i: i64) -> Symbolic {
+    Symbolic::Number(Parameters::Int(i))
+}
+
+impl Symbolic {
+    fn is_number(&self, value: f64) -> bool {
+        match self {
+            Symbolic::Number(p) => to_float(p) == Some(value),
+            _ => false,
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        match self {
+            Symbolic::Number(p) => to_float(p).is_some_and(|f| f < 0.0),
+            Symbolic::Product(factors) => factors.first().is_some_and(|f| f.is_negative()),
+            _ => false,
+        }
+    }
+
+    pub fn neg(self) -> Symbolic {
+        Symbolic::Product(vec![number(-1), self]).simplify()
+    }
+
+    pub fn add(self, other: Symbolic) -> Symbolic {
+        Symbolic::Sum(vec![self, other]).simplify()
+    }
+
+    pub fn sub(self, other: Symbolic) -> Symbolic {
+        Symbolic::Sum(vec![self, other.neg()]).simplify()
+    }
+
+    pub fn mul(self, other: Symbolic) -> Symbolic {
+        Symbolic::Product(vec![self, other]).simplify()
+    }
+
+    pub fn div(self, other: Symbolic) -> Symbolic {
+        let inverse = Symbolic::Power(Box::from(other), Box::from(number(-1)));
+        Symbolic::Product(vec![self, inverse]).simplify()
+    }
+
+    pub fn pow(self, other: Symbolic) -> Symbolic {
+        Symbolic::Power(Box::from(self), Box::from(other)).simplify()
+    }
+
+    /// Rewrites the expression in canonical form: nested sums and products are
+    /// flattened, constants are folded, like terms and equal bases are
+    /// collected and the operands are sorted.
+    pub fn simplify(self) -> Symbolic {
+        match self {
+            Symbolic::Number(p) => Symbolic::Number(normalize(p)),
+            Symbolic::Symbol(s) => Symbolic::Symbol(s),
+            Symbolic::Sum(terms) => simplify_sum(terms),
+            Symbolic::Product(factors) => simplify_product(factors),
+            Symbolic::Power(base, exponent) => simplify_power(base.simplify(), exponent.simplify()),
+            Symbolic::Function(name, args) => {
+                let args: Vec<Symbolic> = args.into_iter().map(|a| a.simplify()).collect();
+                let numbers: Option<Vec<Parameters>> = args
+                    .iter()
+                    .map(|a| match a {
+                        Symbolic::Number(p) => Some(p.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                // like powers, exact arguments are not traded for a float value
+                let exact = numbers.as_ref().is_some_and(|n| n.iter().all(is_exact));
+                match numbers.map(|n| exec(name.clone(), n, None, None)) {
+                    Some(Ok(Parameters::Float(_))) if exact => Symbolic::Function(name, args),
+                    Some(Ok(p)) if to_float(&p).is_some() => Symbolic::Number(normalize(p)),
+                    _ => Symbolic::Function(name, args),
+                }
+            }
+        }
+    }
+
+    /// Splits a term into its numeric coefficient and the rest.
+    fn coefficient(self) -> (Parameters, Symbolic) {
+        match self {
+            Symbolic::Number(p) => (p, number(1)),
+            Symbolic::Product(mut factors) => match factors.first() {
+                Some(Symbolic::Number(p)) => {
+                    let p = p.clone();
+                    factors.remove(0);
+                    let rest = match factors.len() {
+                        1 => factors.remove(0),
+                        _ => Symbolic::Product(factors),
+                    };
+                    (p, rest)
+                }
+                _ => (Parameters::Int(1), Symbolic::Product(factors)),
+            },
+            s => (Parameters::Int(1), s),
+        }
+    }
+
+    /// Splits a factor into its base and exponent.
+    fn base(self) -> (Symbolic, Symbolic) {
+        match self {
+            Symbolic::Power(base, exponent) => (*base, *exponent),
+            s => (s, number(1)),
+        }
+    }
+
+    /// Rank used to order the factors of a product.
+    fn rank(&self) -> u8 {
+        match self {
+            Symbolic::Symbol(_) => 0,
+            Symbolic::Number(_) | Symbolic::Function(_, _) => 1,
+            Symbolic::Power(base, _) => base.rank(),
+            Symbolic::Sum(_) | Symbolic::Product(_) => 2,
+        }
+    }
+
+    /// Degree used to order the terms of a sum, highest first.
+    fn degree(&self) -> f64 {
+        match self {
+            Symbolic::Number(_) => 0.0,
+            Symbolic::Symbol(_) | Symbolic::Function(_, _) => 1.0,
+            Symbolic::Sum(terms) => terms.iter().map(|t| t.degree()).fold(0.0, f64::max),
+            Symbolic::Product(factors) => factors.iter().map(|f| f.degree()).sum(),
+            Symbolic::Power(base, exponent) => match exponent.as_ref() {
+                Symbolic::Number(p) => base.degree() * to_float(p).unwrap_or(1.0),
+                _ => base.degree(),
+            },
+        }
+    }
+}
+
+fn simplify_sum(terms: Vec<Symbolic>) -> Symbolic {
+    let mut flat = Vec::new();
+    for term in terms {
+        match term.simplify() {
+            Symbolic::Sum(inner) => flat.extend(inner),
+            t => flat.push(t),
+        }
+    }
+
+    let mut constant = Parameters::Int(0);
+    let mut collected: Vec<(Symbolic, Parameters)> = Vec::new();
+    for term in flat {
+        match term {
+            Symbolic::Number(p) => {
+                constant = add(constant.clone(), p.clone(), None).unwrap_or(constant);
+            }
+            t => {
+                let (c, rest) = t.coefficient();
+                match collected.iter_mut().find(|(r, _)| *r == rest) {
+                    Some((_, total)) => *total = add(total.clone(), c.clone(), None).unwrap_or(c),
+                    None => collected.push((rest, c)),
+                }
+            }
+        }
+    }
+
+    let mut result: Vec<Symbolic> = collected
+        .into_iter()
+        .filter(|(_, c)| to_float(c) != Some(0.0))
+        .map(|(rest, c)| Symbolic::Product(vec![Symbolic::Number(c), rest]).simplify())
+        .collect();
+    // highest degree first, terms of the same degree by name
+    let name = |t: &Symbolic| t.clone().coefficient().1.to_string();
+    result.sort_by(|a, b| {
+        b.degree()
+            .total_cmp(&a.degree())
+            .then_with(|| name(a).cmp(&name(b)))
+    });
+    if to_float(&constant) != Some(0.0) {
+        result.push(Symbolic::Number(normalize(constant)));
+    }
+
+    match result.len() {
+        0 => number(0),
+        1 => result.remove(0),
+        _ => Symbolic::Sum(result),
+    }
+}
+
+fn simplify_product(factors: Vec<Symbolic>) -> Symbolic {
+    let mut flat = Vec::new();
+    for factor in factors {
+        match factor.simplify() {
+            Symbolic::Product(inner) => flat.extend(inner),
+            f => flat.push(f),
+        }
+    }
+
+    let mut coefficient = Parameters::Int(1);
+    let mut collected: Vec<(Symbolic, Symbolic)> = Vec::new();
+    for factor in flat {
+        match factor {
+            Symbolic::Number(p) => {
+                coefficient = mult(coefficient.clone(), p.clone(), None).unwrap_or(coefficient);
+            }
+            f => {
+                let (base, exponent) = f.base();
+                match collected.iter_mut().find(|(b, _)| *b == base) {
+                    Some((_, total)) => *total = total.clone().add(exponent),
+                    None => collected.push((base, exponent)),
+                }
+            }
+        }
+    }
+    let mut result = Vec::new();
+    for (base, exponent) in collected {
+        match simplify_power(base, exponent) {
+            Symbolic::Number(p) => {
+                coefficient = mult(coefficient.clone(), p, None).unwrap_or(coefficient);
+            }
+            f => result.push(f),
+        }
+    }
+    // symbols first, then calls and sums, x^2*sin(x) rather than sin(x)*x^2
+    result.sort_by_cached_key(|f| (f.rank(), f.to_string()));
+    let coefficient = normalize(coefficient);
+
+    // a numeric factor is distributed over a single sum, 2(x+1) is 2x+2
+    if result.len() == 1 && !Symbolic::Number(coefficient.clone()).is_number(1.0) {
+        if let Symbolic::Sum(terms) = &result[0] {
+            let terms = terms
+                .iter()
+                .map(|t| Symbolic::Product(vec![Symbolic::Number(coefficient.clone()), t.clone()]))
+                .collect();
+            return simplify_sum(terms);
+        }
+    }
+
+    if to_float(&coefficient) == Some(0.0) {
+        return number(0);
+    }
+    if to_float(&coefficient) != Some(1.0) || result.is_empty() {
+        result.insert(0, Symbolic::Number(coefficient));
+    }
+    match result.len() {
+        1 => result.remove(0),
+        _ => Symbolic::Product(result),
+    }
+}
+
+fn simplify_power(base: Symbolic, exponent: Symbolic) -> Symbolic {
+    if exponent.is_number(0.0) || base.is_number(1.0) {
+        return number(1);
+    }
+    if exponent.is_number(1.0) {
+        return base;
+    }
+    match (base, exponent) {
+        (Symbolic::Number(b), Symbolic::Number(e)) => {
+            let exact = is_exact(&b) && is_exact(&e);
+            match expo(b.clone(), e.clone(), None) {
+                Ok(p) if !(exact && matches!(p, Parameters::Float(_))) => {
+                    Symbolic::Number(normalize(p))
+                }
+                _ => Symbolic::Power(
+                    Box::from(Symbolic::Number(b)),
+                    Box::from(Symbolic::Number(e)),
+                ),
+            }
+        }
+        // (b^e)^n = b^(e*n) and (ab)^n = a^n b^n for integer n
+        (Symbolic::Power(b, e), Symbolic::Number(Parameters::Int(n))) => {
+            simplify_power(*b, e.mul(number(n)))
+        }
+        (Symbolic::Product(factors), Symbolic::Number(Parameters::Int(n))) => simplify_product(
+            factors
+                .into_iter()
+                .map(|f| Symbolic::Power(Box::from(f), Box::from(number(n))))
+                .collect(),
+        ),
+        (base, exponent) => Symbolic::Power(Box::from(base), Box::from(exponent)),
+    }
+}
+
+fn fmt_number(p: &Parameters) -> String {
+    match p {
+        Parameters::Float(f) => format!("{f}"),
+        p => p.to_string(),
+    }
+}
+
+/// Formats a factor inside a product or as the base of a power, adding
+/// parentheses where the factor would otherwise bind the wrong way.
+fn fmt_factor(s: &Symbolic) -> String {
+    match s {
+        Symbolic::Sum(_) | Symbolic::Product(_) => format!("({s})"),
+        Symbolic::Number(p) => match p {
+            Parameters::Int(i) if *i >= 0 => s.to_string(),
+            _ => format!("({s})"),
+        },
+        _ => s.to_string(),
+    }
+}
+
+fn fmt_exponent(s: &Symbolic) -> String {
+    match s {
+        Symbolic::Symbol(_) => s.to_string(),
+        Symbolic::Number(Parameters::Int(i)) if *i >= 0 => s.to_string(),
+        _ => format!("({s})"),
+    }
+}
+
+/// Formats a coefficient followed by factors, writing `2x` rather than `2*x`
+/// when that can not be misread.
+fn fmt_monomial(coefficient: &Parameters, factors: &[Symbolic]) -> String {
+    let factors: Vec<String> = factors.iter().map(fmt_factor).collect();
+    let factors = factors.join("*");
+    match coefficient {
+        _ if factors.is_empty() => fmt_number(coefficient),
+        Parameters::Int(1) => factors,
+        Parameters::Int(-1) => format!("-{factors}"),
+        Parameters::Int(i) if factors.starts_with(|c: char| c.is_alphabetic() || c == '(') => {
+            format!("{i}{factors}")
+        }
+        p => format!("{}*{factors}", fmt_number(p)),
+    }
+}
+
+fn fmt_product(factors: &[Symbolic]) -> String {
+    let (coefficient, rest) = match factors.first() {
+        Some(Symbolic::Number(p)) => (p.clone(), &factors[1..]),
+        _ => (Parameters::Int(1), factors),
+    };
+
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    for f in rest {
+        match f {
+            Symbolic::Power(base, exponent) if exponent.is_negative() => {
+                denominator.push(simplify_power(
+                    base.as_ref().clone(),
+                    exponent.as_ref().clone().neg(),
+                ));
+            }
+            f => numerator.push(f.clone()),
+        }
+    }
+
+    // a rational coefficient is split so that x/2 is written rather than 1/2*x
+    let (top, bottom) = match &coefficient {
+        Parameters::Rational(r) => {
+            let r = r.clone().reduce();
+            (
+                normalize(Parameters::Rational(Rationals::from_bigint(
+                    1.into(),
+                    r.over,
+                ))),
+                normalize(Parameters::Rational(Rationals::from_bigint(
+                    1.into(),
+                    r.under,
+                ))),
+            )
+        }
+        p => (p.clone(), Parameters::Int(1)),
+    };
+
+    let result = fmt_monomial(&top, &numerator);
+    let one = Parameters::Int(1);
+    let parts = denominator.len() + usize::from(bottom != one);
+    let denominator = fmt_monomial(&bottom, &denominator);
+    match parts {
+        0 => result,
+        1 => format!("{result}/{denominator}"),
+        _ => format!("{result}/({denominator})"),
+    }
+}
+
+/// The derivative of the builtin `name` at `u`.
+fn outer_derivative(name: &str, u: &Symbolic) -> Result<Symbolic, CalcError> {
+    let f = |name: &str, u: Symbolic| Symbolic::Function(name.to_string(), vec![u]);
+    let square = u.clone().pow(number(2));
+    Ok(match name {
+        "cos" => f("sin", u.clone()).neg(),
+        "sin" => f("cos", u.clone()),
+        "tan" => number(1).add(f("tan", u.clone()).pow(number(2))),
+        "cosh" => f("sinh", u.clone()),
+        "sinh" => f("cosh", u.clone()),
+        "tanh" => number(1).sub(f("tanh", u.clone()).pow(number(2))),
+        "exp" => f("exp", u.clone()),
+        "acos" => f("sqrt", number(1).sub(square)).pow(number(-1)).neg(),
+        "asin" => f("sqrt", number(1).sub(square)).pow(number(-1)),
+        "atan" => number(1).add(square).pow(number(-1)),
+        "ln" | "log" => u.clone().pow(number(-1)),
+        "sqrt" => number(2).mul(f("sqrt", u.clone())).pow(number(-1)),
+        "abs" => u.clone().div(f("abs", u.clone())),
+        // piecewise constant
+        "ceil" | "floor" | "round" => number(0),
+        _ => return Err(CalcError::NotDifferentiable(name.to_string())),
+    })
+}
+
+fn node(value: Parameters, left: Ast, right: Ast) -> Ast {
+    Ast::Node {
+        value,
+        left: Box::from(left),
+        right: Box::from(right),
+    }
+}
+
+impl Symbolic {
+    fn depends_on(&self, x: &str) -> bool {
+        match self {
+            Symbolic::Number(_) => false,
+            Symbolic::Symbol(s) => s == x,
+            Symbolic::Sum(v) | Symbolic::Product(v) | Symbolic::Function(_, v) => {
+                v.iter().any(|s| s.depends_on(x))
+            }
+            Symbolic::Power(base, exponent) => base.depends_on(x) || exponent.depends_on(x),
+        }
+    }
+
+    /// The names of the symbols of the expression, sorted and without duplicates.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut names = match self {
+            Symbolic::Number(_) => Vec::new(),
+            Symbolic::Symbol(s) => vec![s.clone()],
+            Symbolic::Sum(v) | Symbolic::Product(v) | Symbolic::Function(_, v) => {
+                v.iter().flat_map(|s| s.symbols()).collect()
+            }
+            Symbolic::Power(base, exponent) => {
+                let mut names = base.symbols();
+                names.extend(exponent.symbols());
+                names
+            }
+        };
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// The float value with the symbol `x` set to `value`, `None` when another
+    /// symbol is left or a function can not be evaluated.
+    pub fn evaluate(&self, x: &str, value: f64) -> Option<f64> {
+        match self {
+            Symbolic::Number(p) => to_float(p),
+            Symbolic::Symbol(s) if s == x => Some(value),
+            Symbolic::Symbol(_) => None,
+            Symbolic::Sum(v) => v.iter().map(|s| s.evaluate(x, value)).sum(),
+            Symbolic::Product(v) => v.iter().map(|s| s.evaluate(x, value)).product(),
+            Symbolic::Power(base, exponent) => {
+                Some(base.evaluate(x, value)?.powf(exponent.evaluate(x, value)?))
+            }
+            Symbolic::Function(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|a| a.evaluate(x, value).map(Parameters::Float))
+                    .collect::<Option<Vec<Parameters>>>()?;
+                to_float(&exec(name.clone(), args, None, None).ok()?)
+            }
+        }
+    }
+
+    /// The simplified derivative with respect to `x`. Quotients are products
+    /// with a negative power, so the quotient rule follows from the product
+    /// and power rules.
+    pub fn derivative(&self, x: &str) -> Result<Symbolic, CalcError> {
+        if !self.depends_on(x) {
+            return Ok(number(0));
+        }
+        Ok(match self {
+            Symbolic::Number(_) => number(0),
+            Symbolic::Symbol(_) => number(1),
+            Symbolic::Sum(terms) => Symbolic::Sum(
+                terms
+                    .iter()
+                    .map(|t| t.derivative(x))
+                    .collect::<Result<Vec<Symbolic>, CalcError>>()?,
+            )
+            .simplify(),
+            // (uv)' = u'v + uv'
+            Symbolic::Product(factors) => {
+                let mut terms = Vec::new();
+                for i in 0..factors.len() {
+                    let mut term = factors.clone();
+                    term[i] = factors[i].derivative(x)?;
+                    terms.push(Symbolic::Product(term));
+                }
+                Symbolic::Sum(terms).simplify()
+            }
+            // (u^n)' = n u^(n-1) u'
+            Symbolic::Power(base, exponent) if !exponent.depends_on(x) => Symbolic::Product(vec![
+                *exponent.clone(),
+                base.as_ref()
+                    .clone()
+                    .pow(exponent.as_ref().clone().sub(number(1))),
+                base.derivative(x)?,
+            ])
+            .simplify(),
+            // (u^v)' = u^v (v' ln(u) + v u'/u)
+            Symbolic::Power(base, exponent) => {
+                let ln = Symbolic::Function("ln".to_string(), vec![*base.clone()]);
+                let inner = exponent.derivative(x)?.mul(ln).add(
+                    exponent
+                        .as_ref()
+                        .clone()
+                        .mul(base.derivative(x)?)
+                        .div(*base.clone()),
+                );
+                self.clone().mul(inner)
+            }
+            // (f(u))' = f'(u) u'
+            Symbolic::Function(name, args) => match args.as_slice() {
+                [u] => outer_derivative(name, u)?.mul(u.derivative(x)?),
+                _ => return Err(CalcError::NotDifferentiable(name.clone())),
+            },
+        })
+    }
+
+    /// The expression as a syntax tree, so that it can be stored as the body
+    /// of a user function.
+    pub fn to_ast(&self) -> Ast {
+        match self {
+            Symbolic::Number(p) => Ast::new(p.clone()),
+            Symbolic::Symbol(s) => Ast::new(Parameters::Identifier(s.clone())),
+            Symbolic::Sum(terms) => terms
+                .iter()
+                .map(|t| t.to_ast())
+                .reduce(|a, b| node(Parameters::PlusOperation, a, b))
+                .unwrap_or(Ast::new(Parameters::Int(0))),
+            Symbolic::Product(factors) => factors
+                .iter()
+                .map(|f| f.to_ast())
+                .reduce(|a, b| node(Parameters::MultiplicationOperation, a, b))
+                .unwrap_or(Ast::new(Parameters::Int(1))),
+            Symbolic::Power(base, exponent) => {
+                node(Parameters::ExpoOperation, base.to_ast(), exponent.to_ast())
+            }
+            Symbolic::Function(name, args) => Ast::Call {
+                name: name.clone(),
+                lst: args.iter().map(|a| a.to_ast()).collect(),
+            },
+        }
+    }
+}
+
+impl Display for Symbolic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Symbolic::Number(p) => write!(f, "{}", fmt_number(p)),
+            Symbolic::Symbol(s) => write!(f, "{s}"),
+            Symbolic::Sum(terms) => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i == 0 {
+                        write!(f, "{term}")?;
+                    } else if term.is_negative() {
+                        write!(f, "-{}", term.clone().neg())?;
+                    } else {
+                        write!(f, "+{term}")?;
+                    }
+                }
+                Ok(())
+            }
+            Symbolic::Product(factors) => write!(f, "{}", fmt_product(factors)),
+            Symbolic::Power(_, exponent) if exponent.is_negative() => {
+                write!(f, "{}", fmt_product(std::slice::from_ref(self)))
+            }
+            Symbolic::Power(base, exponent) => {
+                write!(f, "{}^{}", fmt_factor(base), fmt_exponent(exponent))
+            }
+            Symbolic::Function(name, args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{name}({})", args.join(","))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Symbolic;
+    use crate::parsing::ast::Parameters;
+
+    fn x() -> Symbolic {
+        Symbolic::Symbol("x".to_string())
+    }
+
+    fn n(i: i64) -> Symbolic {
+        Symbolic::Number(Parameters::Int(i))
+    }
+
+    #[test]
+    pub fn test_collect_like_terms() {
+        let value = n(2).mul(x()).add(n(3).mul(x())).sub(n(1)).add(n(1));
+        assert_eq!(value.to_string(), "5x");
+    }
+
+    #[test]
+    pub fn test_collect_powers() {
+        let value = x().mul(x()).mul(n(3)).div(x().pow(n(3)));
+        assert_eq!(value.to_string(), "3/x");
+    }
+
+    #[test]
+    pub fn test_cancel() {
+        let value = x().add(n(1)).sub(x());
+        assert_eq!(value, n(1));
+    }
+
+    #[test]
+    pub fn test_order() {
+        let value = n(1).add(x()).add(x().pow(n(2)).mul(n(2)));
+        assert_eq!(value.to_string(), "2x^2+x+1");
+    }
+
+    #[test]
+    pub fn test_derivative_product() {
+        let sin = Symbolic::Function("sin".to_string(), vec![x()]);
+        let value = x().pow(n(2)).mul(sin).derivative("x").unwrap();
+        assert_eq!(value.to_string(), "x^2*cos(x)+2x*sin(x)");
+    }
+
+    #[test]
+    pub fn test_derivative_quotient() {
+        let value = n(1).div(x().add(n(1))).derivative("x").unwrap();
+        assert_eq!(value.to_string(), "-1/(x+1)^2");
+    }
+
+    #[test]
+    pub fn test_derivative_chain() {
+        let inner = x().pow(n(2)).add(n(1));
+        let value = Symbolic::Function("ln".to_string(), vec![inner])
+            .derivative("x")
+            .unwrap();
+        assert_eq!(value.to_string(), "2x/(x^2+1)");
+    }
+
+    #[test]
+    pub fn test_distribute() {
+        let value = n(2).mul(x().add(n(1))).sub(n(2));
+        assert_eq!(value.to_string(), "2x");
+    }
+}
@@ -0,0 +1,531 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+use crate::errors::calc_error::CalcError;
+use crate::exact_math::polynomial::rational_parameter;
+use crate::exact_math::symbolic::Symbolic;
+use crate::interpreting::function::{add, divide, expo, minus, mult};
+use crate::interpreting::interpreter::interpret;
+use crate::interpreting::stdlib::{constants, sqrt};
+use crate::lexing::lexer::lex;
+use crate::parsing::ast::Parameters;
+use crate::parsing::parser::init_calc_parser;
+
+/// The SI base units, a dimension counts the power of each of them.
+pub const BASE_UNITS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+pub type Dimension = [i64; 7];
+
+const DIMENSIONLESS: Dimension = [0; 7];
+
+/// Units defined in terms of other units, with whether SI prefixes apply to
+/// them. Names that are often used as variables (t, l, ...) are left out.
+const DERIVED_UNITS: &[(&str, &str, bool)] = &[
+    ("g", "0.001 kg", true),
+    ("min", "60 s", false),
+    ("h", "60 min", false),
+    ("day", "24 h", false),
+    ("week", "7 day", false),
+    ("yr", "365.25 day", false),
+    ("Hz", "1/s", true),
+    ("N", "1 kg*m/s^2", true),
+    ("Pa", "1 N/m^2", true),
+    ("J", "1 N*m", true),
+    ("W", "1 J/s", true),
+    ("C", "1 A*s", true),
+    ("V", "1 W/A", true),
+    ("ohm", "1 V/A", true),
+    ("Ω", "1 ohm", true),
+    ("S", "1 A/V", true),
+    ("F", "1 C/V", true),
+    ("Wb", "1 V*s", true),
+    ("T", "1 Wb/m^2", true),
+    ("H", "1 Wb/A", true),
+    ("L", "0.001 m^3", true),
+    ("tonne", "1000 kg", false),
+    ("bar", "100000 Pa", true),
+    ("atm", "101325 Pa", false),
+    ("eV", "1.602176634e-19 J", true),
+    ("cal", "4.184 J", true),
+    ("Wh", "3600 J", true),
+    ("inch", "2.54 cm", false),
+    ("ft", "12 inch", false),
+    ("yd", "3 ft", false),
+    ("mi", "5280 ft", false),
+    ("nmi", "1852 m", false),
+    ("mph", "1 mi/h", false),
+    ("knot", "1 nmi/h", false),
+    ("lb", "0.45359237 kg", false),
+    ("oz", "0.0625 lb", false),
+    ("lbf", "9.80665 lb*m/s^2", false),
+    ("psi", "1 lbf/inch^2", false),
+    ("gal", "3.785411784 L", false),
+    ("rad", "1", false),
+    ("deg", "pi/180", false),
+    ("percent", "1/100", false),
+];
+
+/// SI prefixes, `da` first so that it is not read as deci.
+const PREFIXES: &[(&str, i64)] = &[
+    ("da", 1),
+    ("Q", 30),
+    ("R", 27),
+    ("Y", 24),
+    ("Z", 21),
+    ("E", 18),
+    ("P", 15),
+    ("T", 12),
+    ("G", 9),
+    ("M", 6),
+    ("k", 3),
+    ("h", 2),
+    ("d", -1),
+    ("c", -2),
+    ("m", -3),
+    ("µ", -6),
+    ("u", -6),
+    ("n", -9),
+    ("p", -12),
+    ("f", -15),
+    ("a", -18),
+    ("z", -21),
+    ("y", -24),
+    ("r", -27),
+    ("q", -30),
+];
+
+thread_local! {
+    static USER_UNITS: RefCell<BTreeMap<String, String>> = const { RefCell::new(BTreeMap::new()) };
+    static CACHE: RefCell<HashMap<String, Option<Unit>>> = RefCell::new(HashMap::new());
+    static RESOLVING: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Replaces the units read from the units file, which take precedence over
+/// the builtin ones and all accept prefixes.
+pub fn set_user_units(units: BTreeMap<String, String>) {
+    USER_UNITS.with(|u| *u.borrow_mut() = units);
+    CACHE.with(|c| c.borrow_mut().clear());
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    /// The value of one unit in SI base units.
+    pub factor: Parameters,
+    pub dimension: Dimension,
+}
+
+/// A number times a product of powers of units, `3/2 m/s` is stored as 3/2
+/// with the units m and s^-1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub value: Parameters,
+    pub units: Vec<(Unit, i64)>,
+}
+
+fn definition(name: &str) -> Option<(String, bool)> {
+    if let Some(d) = USER_UNITS.with(|u| u.borrow().get(name).cloned()) {
+        return Some((d, true));
+    }
+    DERIVED_UNITS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, d, prefixable)| (d.to_string(), *prefixable))
+}
+
+fn is_prefixable(name: &str) -> bool {
+    match definition(name) {
+        Some((_, prefixable)) => prefixable,
+        None => BASE_UNITS.contains(&name) && name != "kg",
+    }
+}
+
+/// The unit with this name, possibly prefixed (`km`, `MPa`, `µs`).
+pub fn lookup(name: &str) -> Option<Unit> {
+    if let Some(unit) = CACHE.with(|c| c.borrow().get(name).cloned()) {
+        return unit;
+    }
+    let unit = resolve(name);
+    CACHE.with(|c| c.borrow_mut().insert(name.to_string(), unit.clone()));
+    unit
+}
+
+fn resolve(name: &str) -> Option<Unit> {
+    if let Some(i) = BASE_UNITS.iter().position(|b| *b == name) {
+        let mut dimension = DIMENSIONLESS;
+        dimension[i] = 1;
+        return Some(Unit {
+            name: name.to_string(),
+            factor: Parameters::Int(1),
+            dimension,
+        });
+    }
+    if let Some((d, _)) = definition(name) {
+        return define(name, &d);
+    }
+    for (prefix, power) in PREFIXES {
+        let rest = match name.strip_prefix(prefix) {
+            Some(rest) if !rest.is_empty() && is_prefixable(rest) => rest,
+            _ => continue,
+        };
+        let unit = lookup(rest)?;
+        let scale = expo(Parameters::Int(10), Parameters::Int(*power), None).ok()?;
+        return Some(Unit {
+            name: name.to_string(),
+            factor: normalize(mult(unit.factor, scale, None).ok()?),
+            dimension: unit.dimension,
+        });
+    }
+    None
+}
+
+/// Evaluates the definition of a unit, a unit defined in terms of itself has
+/// no value.
+fn define(name: &str, definition: &str) -> Option<Unit> {
+    if !RESOLVING.with(|r| r.borrow_mut().insert(name.to_string())) {
+        return None;
+    }
+    let tokens = lex(definition.to_string());
+    let value = init_calc_parser(&tokens).parse().ok().and_then(|ast| {
+        let mut ram = constants();
+        interpret(&ast, &mut ram, &mut HashMap::new()).ok()
+    });
+    RESOLVING.with(|r| r.borrow_mut().remove(name));
+
+    let value = value?;
+    let quantity = Quantity::from_parameter(&unit_quantity(&value).unwrap_or(value))?;
+    Some(Unit {
+        name: name.to_string(),
+        factor: quantity.si_value().ok()?,
+        dimension: quantity.dimension(),
+    })
+}
+
+/// The quantity named by an unbound identifier, or by an expression whose
+/// symbols are all units such as `m/s^2`.
+pub fn unit_quantity(p: &Parameters) -> Option<Parameters> {
+    match p {
+        Parameters::Identifier(s) if !s.starts_with('@') => {
+            lookup(s).map(|u| Parameters::Quantity(Box::from(Quantity::unit(u))))
+        }
+        Parameters::Symbolic(s) => symbolic_quantity(s),
+        _ => None,
+    }
+}
+
+fn symbolic_quantity(s: &Symbolic) -> Option<Parameters> {
+    match s {
+        Symbolic::Number(p) => Some(p.clone()),
+        Symbolic::Symbol(name) => {
+            lookup(name).map(|u| Parameters::Quantity(Box::from(Quantity::unit(u))))
+        }
+        Symbolic::Sum(terms) => terms.iter().try_fold(Parameters::Null, |acc, t| {
+            add(acc, symbolic_quantity(t)?, None).ok()
+        }),
+        Symbolic::Product(factors) => factors.iter().try_fold(Parameters::Int(1), |acc, f| {
+            mult(acc, symbolic_quantity(f)?, None).ok()
+        }),
+        Symbolic::Power(base, exponent) => {
+            expo(symbolic_quantity(base)?, symbolic_quantity(exponent)?, None).ok()
+        }
+        Symbolic::Function(_, _) => None,
+    }
+}
+
+/// Integer valued rationals become integers so that equal values compare equal.
+fn normalize(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Rational(r) => rational_parameter(r),
+        p => p,
+    }
+}
+
+fn is_real(p: &Parameters) -> bool {
+    matches!(
+        p,
+        Parameters::Int(_)
+            | Parameters::Float(_)
+            | Parameters::Rational(_)
+            | Parameters::Radical(_)
+    )
+}
+
+impl Quantity {
+    pub fn new(value: Parameters, units: Vec<(Unit, i64)>) -> Self {
+        let units = units.into_iter().filter(|(_, e)| *e != 0).collect();
+        Quantity {
+            value: normalize(value),
+            units,
+        }
+    }
+
+    pub fn unit(unit: Unit) -> Self {
+        Quantity::new(Parameters::Int(1), vec![(unit, 1)])
+    }
+
+    /// A quantity, or a real number as a quantity without units.
+    pub fn from_parameter(p: &Parameters) -> Option<Self> {
+        match p {
+            Parameters::Quantity(q) => Some(*q.clone()),
+            p if is_real(p) => Some(Quantity::new(p.clone(), Vec::new())),
+            _ => None,
+        }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        let mut dimension = DIMENSIONLESS;
+        for (unit, e) in &self.units {
+            for (d, u) in dimension.iter_mut().zip(unit.dimension) {
+                *d += u * e;
+            }
+        }
+        dimension
+    }
+
+    /// The value of the units of the quantity in SI base units.
+    fn factor(&self) -> Result<Parameters, CalcError> {
+        self.units
+            .iter()
+            .try_fold(Parameters::Int(1), |acc, (u, e)| {
+                mult(
+                    acc,
+                    expo(u.factor.clone(), Parameters::Int(*e), None)?,
+                    None,
+                )
+            })
+    }
+
+    /// The value of the quantity in SI base units.
+    pub fn si_value(&self) -> Result<Parameters, CalcError> {
+        mult(self.value.clone(), self.factor()?, None).map(normalize)
+    }
+
+    /// The units of the quantity, `kg*m/s^2`.
+    pub fn unit_string(&self) -> String {
+        let power = |(u, e): &(Unit, i64)| match e.abs() {
+            1 => u.name.clone(),
+            e => format!("{}^{e}", u.name),
+        };
+        let numerator: Vec<String> = self.units.iter().filter(|u| u.1 > 0).map(power).collect();
+        let denominator: Vec<String> = self.units.iter().filter(|u| u.1 < 0).map(power).collect();
+        match (numerator.len(), denominator.len()) {
+            (_, 0) => numerator.join("*"),
+            (0, _) => self
+                .units
+                .iter()
+                .map(|(u, e)| format!("{}^{e}", u.name))
+                .collect::<Vec<String>>()
+                .join("*"),
+            (_, 1) => format!("{}/{}", numerator.join("*"), denominator[0]),
+            _ => format!("{}/({})", numerator.join("*"), denominator.join("*")),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.units.len() {
+            0 => "a number".to_string(),
+            _ => self.unit_string(),
+        }
+    }
+
+    fn into_parameter(self) -> Parameters {
+        match self.units.len() {
+            0 => self.value,
+            _ => Parameters::Quantity(Box::from(self)),
+        }
+    }
+
+    /// Products whose units cancel out are numbers.
+    fn simplify(self) -> Result<Parameters, CalcError> {
+        if self.dimension() == DIMENSIONLESS {
+            return self.si_value();
+        }
+        Ok(self.into_parameter())
+    }
+
+    fn check_dimension(&self, other: &Quantity, action: &str) -> Result<(), CalcError> {
+        if self.dimension() != other.dimension() {
+            return Err(CalcError::DimensionMismatch(format!(
+                "cannot {action} {} and {}",
+                self.describe(),
+                other.describe()
+            )));
+        }
+        Ok(())
+    }
+
+    /// The value of `other` in the units of `self`.
+    fn value_of(&self, other: &Quantity) -> Result<Parameters, CalcError> {
+        divide(other.si_value()?, self.factor()?, None)
+    }
+
+    /// The sum in the units of the left operand.
+    pub fn add(&self, other: &Quantity) -> Result<Parameters, CalcError> {
+        self.check_dimension(other, "add")?;
+        let value = add(self.value.clone(), self.value_of(other)?, None)?;
+        Ok(Quantity::new(value, self.units.clone()).into_parameter())
+    }
+
+    pub fn sub(&self, other: &Quantity) -> Result<Parameters, CalcError> {
+        self.check_dimension(other, "subtract")?;
+        let value = minus(self.value.clone(), self.value_of(other)?, None)?;
+        Ok(Quantity::new(value, self.units.clone()).into_parameter())
+    }
+
+    pub fn neg(&self) -> Result<Parameters, CalcError> {
+        let value = minus(Parameters::Null, self.value.clone(), None)?;
+        Ok(Quantity::new(value, self.units.clone()).into_parameter())
+    }
+
+    fn merge(&self, other: &Quantity, sign: i64) -> Vec<(Unit, i64)> {
+        let mut units = self.units.clone();
+        for (unit, e) in &other.units {
+            match units.iter_mut().find(|(u, _)| u.name == unit.name) {
+                Some((_, e2)) => *e2 += sign * e,
+                None => units.push((unit.clone(), sign * e)),
+            }
+        }
+        units
+    }
+
+    pub fn mul(&self, other: &Quantity) -> Result<Parameters, CalcError> {
+        let value = mult(self.value.clone(), other.value.clone(), None)?;
+        Quantity::new(value, self.merge(other, 1)).simplify()
+    }
+
+    pub fn div(&self, other: &Quantity) -> Result<Parameters, CalcError> {
+        let value = divide(self.value.clone(), other.value.clone(), None)?;
+        Quantity::new(value, self.merge(other, -1)).simplify()
+    }
+
+    /// Integer powers, and rational ones when the powers of the units stay
+    /// integers, sqrt(4 m^2) is 2 m.
+    pub fn pow(&self, exponent: &Parameters) -> Result<Parameters, CalcError> {
+        let (numerator, denominator) = match exponent {
+            Parameters::Int(i) => (*i, 1),
+            Parameters::Rational(r) => {
+                let r = r.clone().reduce();
+                match (i64::try_from(r.over), i64::try_from(r.under)) {
+                    (Ok(n), Ok(d)) => (n, d),
+                    _ => return Err(CalcError::Overflow("^".to_string())),
+                }
+            }
+            p => return Err(CalcError::incompatible("^", "a quantity", p.type_name())),
+        };
+        let mut units = Vec::new();
+        for (unit, e) in &self.units {
+            if (e * numerator) % denominator != 0 {
+                return Err(CalcError::DimensionMismatch(format!(
+                    "cannot raise {} to the power {exponent}",
+                    self.unit_string()
+                )));
+            }
+            units.push((unit.clone(), e * numerator / denominator));
+        }
+        let value = match denominator {
+            2 => sqrt(
+                &vec![expo(self.value.clone(), Parameters::Int(numerator), None)?],
+                &None,
+            )?,
+            _ => expo(self.value.clone(), exponent.clone(), None)?,
+        };
+        Quantity::new(value, units).simplify()
+    }
+
+    /// The quantity in the units of `target`, whose value is ignored.
+    pub fn convert(&self, target: &Quantity) -> Result<Parameters, CalcError> {
+        if self.dimension() != target.dimension() {
+            return Err(CalcError::DimensionMismatch(format!(
+                "cannot convert {} to {}",
+                self.describe(),
+                target.describe()
+            )));
+        }
+        Ok(Quantity::new(target.value_of(self)?, target.units.clone()).into_parameter())
+    }
+
+    /// Both quantities in SI base units, to compare them.
+    pub fn compare(&self, other: &Quantity) -> Result<(Parameters, Parameters), CalcError> {
+        self.check_dimension(other, "compare")?;
+        Ok((self.si_value()?, other.si_value()?))
+    }
+}
+
+impl Display for Quantity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.value, self.unit_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lookup, unit_quantity, Quantity, DERIVED_UNITS};
+    use crate::errors::calc_error::CalcError;
+    use crate::exact_math::rationals::Rationals;
+    use crate::parsing::ast::Parameters;
+
+    fn quantity(value: i64, unit: &str) -> Quantity {
+        Quantity::new(Parameters::Int(value), vec![(lookup(unit).unwrap(), 1)])
+    }
+
+    #[test]
+    pub fn test_prefixes() {
+        let km = lookup("km").unwrap();
+        assert_eq!(km.factor, Parameters::Int(1000));
+        assert_eq!(km.dimension, [1, 0, 0, 0, 0, 0, 0]);
+        let mg = lookup("mg").unwrap();
+        assert_eq!(mg.factor, Parameters::Rational(Rationals::new(1000000, 1)));
+        assert_eq!(lookup("dam").unwrap().factor, Parameters::Int(10));
+        assert_eq!(lookup("min").unwrap().factor, Parameters::Int(60));
+        assert!(lookup("kmin").is_none());
+        assert!(lookup("x").is_none());
+    }
+
+    #[test]
+    pub fn test_derived() {
+        let newton = lookup("kN").unwrap();
+        assert_eq!(newton.factor, Parameters::Int(1000));
+        assert_eq!(newton.dimension, [1, 1, -2, 0, 0, 0, 0]);
+        let mph = lookup("mph").unwrap();
+        assert_eq!(mph.factor, Parameters::Rational(Rationals::new(3125, 1397)));
+    }
+
+    #[test]
+    pub fn test_every_derived_unit_resolves() {
+        for (name, definition, _) in DERIVED_UNITS {
+            assert!(
+                lookup(name).is_some(),
+                "{name} = {definition} does not resolve"
+            );
+        }
+        assert_eq!(lookup("keV").unwrap().dimension, [2, 1, -2, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    pub fn test_arithmetic() {
+        let sum = quantity(1, "km").add(&quantity(500, "m")).unwrap();
+        assert_eq!(sum.to_string(), "3/2 km");
+        let speed = quantity(3, "m").div(&quantity(2, "s")).unwrap();
+        assert_eq!(speed.to_string(), "3/2 m/s");
+        let ratio = quantity(1, "km").div(&quantity(1, "m")).unwrap();
+        assert_eq!(ratio, Parameters::Int(1000));
+        let error = quantity(1, "m").add(&quantity(1, "s"));
+        assert_eq!(
+            error,
+            Err(CalcError::DimensionMismatch(
+                "cannot add m and s".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_convert() {
+        let target = match unit_quantity(&Parameters::Identifier("cm".to_string())) {
+            Some(Parameters::Quantity(q)) => *q,
+            _ => unreachable!(),
+        };
+        let converted = quantity(2, "inch").convert(&target).unwrap();
+        assert_eq!(converted.to_string(), "127/25 cm");
+    }
+}
@@ -1,32 +1,43 @@
 use std::collections::HashMap;
 
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::errors::calc_error::CalcError;
+use crate::exact_math::complex::Complex;
+use crate::exact_math::polynomial::{rational_parameter, Polynomial};
+use crate::exact_math::radicals::Radicals;
 use crate::exact_math::rationals::Rationals;
+use crate::exact_math::symbolic::Symbolic;
+use crate::exact_math::units::Quantity;
 use crate::parsing::ast::Parameters;
 use crate::parsing::ast::Parameters::Bool;
+use crate::utils::integer_utils::{int_or_big, MAX_BITS};
 use crate::utils::matrix_utils::mult_matrix;
 
+type BinaryOperator = fn(
+    Parameters,
+    Parameters,
+    Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError>;
+
 pub fn apply_operator(
     value: Parameters,
     value2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-    f: fn(Parameters, Parameters, Option<&HashMap<String, Parameters>>) -> Parameters,
-) -> Parameters {
+    f: BinaryOperator,
+) -> Result<Parameters, CalcError> {
     let s = match value {
         Parameters::Identifier(s) => s,
         _ => "".to_string(),
     };
     if s == "".to_string() {
-        return Parameters::Null;
+        return Ok(Parameters::Null);
     }
-    match ram {
-        None => value2,
-        Some(i_ram) => {
-            let value = i_ram.get(&s);
-            match value {
-                None => value2,
-                Some(val) => f(val.clone(), value2.clone(), ram),
-            }
-        }
+    match ram.and_then(|i_ram| i_ram.get(&s)) {
+        None => Err(CalcError::UndefinedVariable(s)),
+        Some(val) => f(val.clone(), value2.clone(), ram),
     }
 }
 
@@ -34,29 +45,115 @@ pub fn apply_operator_reverse(
     value: Parameters,
     value2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-    f: fn(Parameters, Parameters, Option<&HashMap<String, Parameters>>) -> Parameters,
-) -> Parameters {
+    f: BinaryOperator,
+) -> Result<Parameters, CalcError> {
     let s = match value2 {
         Parameters::Identifier(s) => s,
         _ => "".to_string(),
     };
     if s == "".to_string() {
-        return Parameters::Null;
+        return Ok(Parameters::Null);
     }
-    match ram {
-        None => value,
-        Some(i_ram) => {
-            let val3 = i_ram.get(&s);
-            match val3 {
-                None => value,
-                Some(val) => f(value.clone(), val.clone(), ram),
-            }
-        }
+    match ram.and_then(|i_ram| i_ram.get(&s)) {
+        None => Err(CalcError::UndefinedVariable(s)),
+        Some(val) => f(value.clone(), val.clone(), ram),
+    }
+}
+
+/// The exact value of an integer, a rational or a radical.
+pub fn to_radical(p: &Parameters) -> Option<Radicals> {
+    match p {
+        Parameters::Int(i) => Some(Radicals::from_rational(Rationals::new(1, *i))),
+        Parameters::Rational(r) => Some(Radicals::from_rational(r.clone())),
+        Parameters::Radical(r) => Some(r.clone()),
+        _ => None,
+    }
+}
+
+/// Turns a radical without irrational terms back into a rational.
+pub fn from_radical(r: Radicals) -> Parameters {
+    match r.to_rational() {
+        Some(s) => Parameters::Rational(s),
+        None => Parameters::Radical(r),
+    }
+}
+
+/// The value as a symbolic expression, unbound names being symbols.
+pub fn to_symbolic(p: &Parameters) -> Option<Symbolic> {
+    match p {
+        Parameters::Int(_)
+        | Parameters::Float(_)
+        | Parameters::Rational(_)
+        | Parameters::Radical(_) => Some(Symbolic::Number(p.clone())),
+        Parameters::Identifier(s) => Some(Symbolic::Symbol(s.clone())),
+        Parameters::Symbolic(s) => Some(*s.clone()),
+        Parameters::Polynomial(p) => Some(p.to_symbolic()),
+        _ => None,
+    }
+}
+
+/// Turns a symbolic expression that simplified to a number back into it.
+pub fn from_symbolic(s: Symbolic) -> Parameters {
+    match s {
+        Symbolic::Number(p) => p,
+        s => Parameters::Symbolic(Box::from(s)),
+    }
+}
+
+/// The value as a polynomial in `variable`, for rationals and expressions
+/// polynomial in it.
+pub fn to_polynomial(p: &Parameters, variable: &str) -> Option<Polynomial> {
+    match p {
+        Parameters::Int(i) => Some(Polynomial::constant(Rationals::new(1, *i), variable)),
+        Parameters::Rational(r) => Some(Polynomial::constant(r.clone(), variable)),
+        Parameters::Identifier(s) if s == variable => Some(Polynomial::variable(variable)),
+        Parameters::Polynomial(p) if p.variable == variable => Some(p.clone()),
+        Parameters::Symbolic(s) => Polynomial::from_symbolic(s, variable),
+        _ => None,
+    }
+}
+
+/// Turns a constant polynomial back into a number.
+pub fn from_polynomial(p: Polynomial) -> Parameters {
+    match p.degree() {
+        0 => rational_parameter(p.evaluate(&Rationals::new(1, 0))),
+        _ => Parameters::Polynomial(p),
     }
 }
 
-pub fn add(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameters>>) -> Parameters {
-    match (i, i2) {
+/// Both operands as polynomials in the variable of the polynomial one.
+fn polynomial_pair(a: &Parameters, b: &Parameters) -> Option<(Polynomial, Polynomial)> {
+    let variable = match (a, b) {
+        (Parameters::Polynomial(p), _) | (_, Parameters::Polynomial(p)) => p.variable.clone(),
+        _ => return None,
+    };
+    Some((to_polynomial(a, &variable)?, to_polynomial(b, &variable)?))
+}
+
+/// A polynomial as a symbolic expression, for the operations that leave them.
+fn polynomial_to_symbolic(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Polynomial(p) => Parameters::Symbolic(Box::from(p.to_symbolic())),
+        p => p,
+    }
+}
+
+pub fn to_float(p: &Parameters) -> Option<f64> {
+    match p {
+        Parameters::Int(i) => Some(*i as f64),
+        Parameters::Float(f) => Some(*f),
+        Parameters::Rational(r) => Some(r.clone().approx()),
+        Parameters::Radical(r) => Some(r.approx()),
+        _ => None,
+    }
+}
+
+pub fn add(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Null, Parameters::InterpreterVector(vec)) => {
@@ -78,16 +175,19 @@ pub fn add(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameter
         }
         (Parameters::Rational(s), Parameters::Float(f)) => Parameters::Float(s.approx() + f),
         (Parameters::Float(f), Parameters::Rational(s)) => Parameters::Float(f + s.approx()),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Int(v + v2),
+        (Parameters::Int(v), Parameters::Int(v2)) => match v.checked_add(v2) {
+            Some(r) => Parameters::Int(r),
+            None => int_or_big(BigInt::from(v) + v2),
+        },
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64) + f),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v + f),
         (Parameters::Float(v), Parameters::Int(i1)) => Parameters::Float(v + (i1 as f64)),
         (Parameters::InterpreterVector(vec), Parameters::InterpreterVector(vec2)) => {
-            let mut res = Vec::new();
-            vec.into_iter()
+            let res = vec
+                .into_iter()
                 .zip(vec2.into_iter())
                 .map(|(x, y)| add(x.clone(), y.clone(), ram))
-                .for_each(|s| res.push(s));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(res))
         }
         (Bool(_), Parameters::Int(i)) => Parameters::Int(i),
@@ -102,72 +202,117 @@ pub fn add(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameter
             Parameters::Identifier(s2),
             ram,
             add,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, add)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, add)?
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, add)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, add)?
         }
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
             Parameters::Rational(s.clone()),
             Parameters::Identifier(ss.clone()),
             ram,
             add,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             add,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, add)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, add)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, add)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, add)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, add)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, add)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, add)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, add)?
         }
         (Parameters::Identifier(s), Parameters::InterpreterVector(vec)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::InterpreterVector(vec.clone()),
             ram,
             add,
-        ),
+        )?,
         (Parameters::InterpreterVector(vec), Parameters::Identifier(s)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::InterpreterVector(vec.clone()),
             ram,
             add,
-        ),
+        )?,
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, add)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, add)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, add)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, add)?
+        }
+        (Parameters::Quantity(q), Parameters::Null)
+        | (Parameters::Null, Parameters::Quantity(q)) => Parameters::Quantity(q),
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => q.add(&q2)?,
+                _ => return Err(CalcError::incompatible("+", a.type_name(), b.type_name())),
+            }
         }
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the + operator".to_string(),
-        ),
-    }
+        (Parameters::Polynomial(p), Parameters::Null)
+        | (Parameters::Null, Parameters::Polynomial(p)) => Parameters::Polynomial(p),
+        (a @ Parameters::Polynomial(_), b) | (a, b @ Parameters::Polynomial(_)) => {
+            match polynomial_pair(&a, &b) {
+                Some((p, q)) => from_polynomial(p + q),
+                None => add(polynomial_to_symbolic(a), polynomial_to_symbolic(b), ram)?,
+            }
+        }
+        (Parameters::Symbolic(s), Parameters::Null)
+        | (Parameters::Null, Parameters::Symbolic(s)) => Parameters::Symbolic(s),
+        (a @ Parameters::Symbolic(_), b) | (a, b @ Parameters::Symbolic(_)) => {
+            match (to_symbolic(&a), to_symbolic(&b)) {
+                (Some(s), Some(s2)) => from_symbolic(s.add(s2)),
+                _ => return Err(CalcError::incompatible("+", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Complex(z), Parameters::Null) | (Parameters::Null, Parameters::Complex(z)) => {
+            Parameters::Complex(z)
+        }
+        (a @ Parameters::Complex(_), b) | (a, b @ Parameters::Complex(_)) => {
+            match (Complex::from_parameter(&a), Complex::from_parameter(&b)) {
+                (Some(z), Some(w)) => z.add(&w)?,
+                _ => return Err(CalcError::incompatible("+", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Radical(r), Parameters::Null) | (Parameters::Null, Parameters::Radical(r)) => {
+            Parameters::Radical(r)
+        }
+        (Parameters::Radical(r), Parameters::Float(f))
+        | (Parameters::Float(f), Parameters::Radical(r)) => Parameters::Float(r.approx() + f),
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_radical(&a), to_radical(&b)) {
+                (Some(r), Some(r2)) => from_radical(r + r2),
+                _ => return Err(CalcError::incompatible("+", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("+", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn minus(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
-        (Parameters::Null, Parameters::Int(v)) => Parameters::Int(-v),
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
+        (Parameters::Null, Parameters::Int(v)) => int_or_big(-BigInt::from(v)),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(-f),
-        (Parameters::Int(v), Parameters::Null) => Parameters::Int(-v),
+        (Parameters::Int(v), Parameters::Null) => int_or_big(-BigInt::from(v)),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(-f),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Int(v - v2),
+        (Parameters::Int(v), Parameters::Int(v2)) => match v.checked_sub(v2) {
+            Some(r) => Parameters::Int(r),
+            None => int_or_big(BigInt::from(v) - v2),
+        },
 
         (Parameters::Rational(s), Parameters::Null) => {
             Parameters::Rational(Rationals::new(1, 0) - s)
@@ -186,27 +331,27 @@ pub fn minus(
         (Parameters::Rational(s), Parameters::Float(f)) => Parameters::Float(s.approx() - f),
         (Parameters::Float(f), Parameters::Rational(s)) => Parameters::Float(f - s.approx()),
         (Parameters::InterpreterVector(vec), Parameters::Null) => {
-            let mut res = Vec::new();
-            vec.into_iter()
+            let res = vec
+                .into_iter()
                 .map(|x| minus(Parameters::Null, x.clone(), ram))
-                .for_each(|z| res.push(z));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(res))
         }
 
         (Parameters::Null, Parameters::InterpreterVector(vec)) => {
-            let mut res = Vec::new();
-            vec.into_iter()
+            let res = vec
+                .into_iter()
                 .map(|x| minus(Parameters::Null, x.clone(), ram))
-                .for_each(|z| res.push(z));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(res))
         }
 
         (Parameters::InterpreterVector(vec), Parameters::InterpreterVector(vec2)) => {
-            let mut res = Vec::new();
-            vec.into_iter()
+            let res = vec
+                .into_iter()
                 .zip(vec2.into_iter())
                 .map(|(x, y)| minus(x.clone(), y.clone(), ram))
-                .for_each(|z| res.push(z));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(res))
         }
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64) - f),
@@ -225,12 +370,12 @@ pub fn minus(
             Parameters::Identifier(s2),
             ram,
             minus,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, minus)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, minus)?
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, minus)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, minus)?
         }
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
@@ -238,28 +383,28 @@ pub fn minus(
             Parameters::Identifier(ss.clone()),
             ram,
             minus,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             minus,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, minus)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, minus)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, minus);
+            let v = apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, minus)?;
             match v {
-                Parameters::Int(i) => Parameters::Int(-i),
+                Parameters::Int(i) => int_or_big(-BigInt::from(i)),
                 _ => Parameters::Null,
             }
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, minus)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, minus)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, minus);
+            let v = apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, minus)?;
             match v {
                 Parameters::Float(i) => Parameters::Float(-i),
                 _ => Parameters::Null,
@@ -271,36 +416,83 @@ pub fn minus(
             Parameters::Identifier(s),
             ram,
             minus,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::InterpreterVector(vec)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::InterpreterVector(vec.clone()),
             ram,
             minus,
-        ),
+        )?,
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, minus)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, minus)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, minus)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, minus)?
+        }
+        (Parameters::Quantity(q), Parameters::Null)
+        | (Parameters::Null, Parameters::Quantity(q)) => q.neg()?,
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => q.sub(&q2)?,
+                _ => return Err(CalcError::incompatible("-", a.type_name(), b.type_name())),
+            }
         }
-        _ => Parameters::Identifier(
-            "Those two values are incompatible with the - operator".to_string(),
-        ),
-    }
+        (Parameters::Polynomial(p), Parameters::Null)
+        | (Parameters::Null, Parameters::Polynomial(p)) => Parameters::Polynomial(-p),
+        (a @ Parameters::Polynomial(_), b) | (a, b @ Parameters::Polynomial(_)) => {
+            match polynomial_pair(&a, &b) {
+                Some((p, q)) => from_polynomial(p - q),
+                None => minus(polynomial_to_symbolic(a), polynomial_to_symbolic(b), ram)?,
+            }
+        }
+        (Parameters::Symbolic(s), Parameters::Null)
+        | (Parameters::Null, Parameters::Symbolic(s)) => from_symbolic((*s).neg()),
+        (a @ Parameters::Symbolic(_), b) | (a, b @ Parameters::Symbolic(_)) => {
+            match (to_symbolic(&a), to_symbolic(&b)) {
+                (Some(s), Some(s2)) => from_symbolic(s.sub(s2)),
+                _ => return Err(CalcError::incompatible("-", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Complex(z), Parameters::Null) | (Parameters::Null, Parameters::Complex(z)) => {
+            Complex::from_parameter(&Parameters::Int(0))
+                .unwrap()
+                .sub(&z)?
+        }
+        (a @ Parameters::Complex(_), b) | (a, b @ Parameters::Complex(_)) => {
+            match (Complex::from_parameter(&a), Complex::from_parameter(&b)) {
+                (Some(z), Some(w)) => z.sub(&w)?,
+                _ => return Err(CalcError::incompatible("-", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Radical(r), Parameters::Null) | (Parameters::Null, Parameters::Radical(r)) => {
+            Parameters::Radical(-r)
+        }
+        (Parameters::Radical(r), Parameters::Float(f)) => Parameters::Float(r.approx() - f),
+        (Parameters::Float(f), Parameters::Radical(r)) => Parameters::Float(f - r.approx()),
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_radical(&a), to_radical(&b)) {
+                (Some(r), Some(r2)) => from_radical(r - r2),
+                _ => return Err(CalcError::incompatible("-", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("-", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn mult(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(v),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(f),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Int(v * v2),
+        (Parameters::Int(v), Parameters::Int(v2)) => match v.checked_mul(v2) {
+            Some(r) => Parameters::Int(r),
+            None => int_or_big(BigInt::from(v) * v2),
+        },
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64) * f),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v * f),
         (Parameters::Float(v), Parameters::Int(i1)) => Parameters::Float(v * (i1 as f64)),
@@ -323,31 +515,31 @@ pub fn mult(
             Parameters::InterpreterVector(vec.clone())
         }
         (Parameters::InterpreterVector(vec), Parameters::Int(v)) => {
-            let mut result = Vec::new();
-            vec.into_iter()
+            let result = vec
+                .into_iter()
                 .map(|x| mult(x.clone(), Parameters::Int(v), ram))
-                .for_each(|x| result.push(x));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(result))
         }
         (Parameters::Int(v), Parameters::InterpreterVector(vec)) => {
-            let mut result = Vec::new();
-            vec.into_iter()
+            let result = vec
+                .into_iter()
                 .map(|x| mult(x.clone(), Parameters::Int(v), ram))
-                .for_each(|x| result.push(x));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(result))
         }
         (Parameters::InterpreterVector(vec), Parameters::Float(v)) => {
-            let mut result = Vec::new();
-            vec.into_iter()
+            let result = vec
+                .into_iter()
                 .map(|x| mult(x.clone(), Parameters::Float(v), ram))
-                .for_each(|x| result.push(x));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(result))
         }
         (Parameters::Float(v), Parameters::InterpreterVector(vec)) => {
-            let mut result = Vec::new();
-            vec.into_iter()
+            let result = vec
+                .into_iter()
                 .map(|x| mult(x.clone(), Parameters::Float(v), ram))
-                .for_each(|x| result.push(x));
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             Parameters::InterpreterVector(Box::from(result))
         }
 
@@ -372,27 +564,30 @@ pub fn mult(
             });
 
             if !is_matrix {
+                if vec.len() != vec2.len() {
+                    return Err(CalcError::DimensionMismatch(format!(
+                        "cannot multiply a vector of size {} with a vector of size {}",
+                        vec.len(),
+                        vec2.len()
+                    )));
+                }
                 let mut sum = Parameters::Null;
-                (*vec)
-                    .into_iter()
-                    .zip(vec2.into_iter())
-                    .map(|(a, b)| mult(a.clone(), b.clone(), ram))
-                    .for_each(|x| sum = add(sum.clone(), x, ram));
+                for (a, b) in (*vec).into_iter().zip(vec2.into_iter()) {
+                    sum = add(sum.clone(), mult(a.clone(), b.clone(), ram)?, ram)?;
+                }
 
                 match sum {
                     Parameters::Int(i) => Parameters::Int(i),
                     Parameters::Float(f) => Parameters::Float(f),
+                    Parameters::Rational(s) => Parameters::Rational(s),
+                    Parameters::Radical(s) => Parameters::Radical(s),
                     _ => Parameters::Float(f64::NAN),
                 }
             } else {
-                let matrix_result = mult_matrix(res, res2, ram);
+                let matrix_result = mult_matrix(res, res2, ram)?;
 
                 let mut res = Vec::new();
 
-                if matrix_result.len() == 0 {
-                    return Parameters::Null;
-                }
-
                 matrix_result
                     .into_iter()
                     .for_each(|x| res.push(Parameters::InterpreterVector(Box::from(x))));
@@ -413,9 +608,9 @@ pub fn mult(
             Parameters::Identifier(s2),
             ram,
             mult,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, mult)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, mult)?
         }
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
@@ -423,62 +618,129 @@ pub fn mult(
             Parameters::Identifier(ss.clone()),
             ram,
             mult,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             mult,
-        ),
+        )?,
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, mult)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, mult)?
         }
         (Parameters::Identifier(s), Parameters::InterpreterVector(vec)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::InterpreterVector(vec.clone()),
             ram,
             mult,
-        ),
+        )?,
         (Parameters::InterpreterVector(vec), Parameters::Identifier(s)) => apply_operator_reverse(
             Parameters::InterpreterVector(vec.clone()),
             Parameters::Identifier(s),
             ram,
             mult,
-        ),
+        )?,
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, mult)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, mult)?
         }
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, mult)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, mult)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, mult)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, mult)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, mult)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, mult)?
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, mult)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, mult)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, mult)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, mult)?
+        }
+        (Parameters::Quantity(q), Parameters::Null)
+        | (Parameters::Null, Parameters::Quantity(q)) => Parameters::Quantity(q),
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => q.mul(&q2)?,
+                _ => return Err(CalcError::incompatible("*", a.type_name(), b.type_name())),
+            }
         }
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the * operator".to_string(),
-        ),
-    }
+        (Parameters::Polynomial(p), Parameters::Null)
+        | (Parameters::Null, Parameters::Polynomial(p)) => Parameters::Polynomial(p),
+        (a @ Parameters::Polynomial(_), b) | (a, b @ Parameters::Polynomial(_)) => {
+            match polynomial_pair(&a, &b) {
+                Some((p, q)) => from_polynomial(p * q),
+                None => mult(polynomial_to_symbolic(a), polynomial_to_symbolic(b), ram)?,
+            }
+        }
+        (Parameters::Symbolic(s), Parameters::Null)
+        | (Parameters::Null, Parameters::Symbolic(s)) => Parameters::Symbolic(s),
+        (Parameters::InterpreterVector(vec), Parameters::Symbolic(s))
+        | (Parameters::Symbolic(s), Parameters::InterpreterVector(vec)) => {
+            let result = vec
+                .into_iter()
+                .map(|x| mult(x.clone(), Parameters::Symbolic(s.clone()), ram))
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
+            Parameters::InterpreterVector(Box::from(result))
+        }
+        (a @ Parameters::Symbolic(_), b) | (a, b @ Parameters::Symbolic(_)) => {
+            match (to_symbolic(&a), to_symbolic(&b)) {
+                (Some(s), Some(s2)) => from_symbolic(s.mul(s2)),
+                _ => return Err(CalcError::incompatible("*", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Complex(z), Parameters::Null) | (Parameters::Null, Parameters::Complex(z)) => {
+            Parameters::Complex(z)
+        }
+        (Parameters::InterpreterVector(vec), Parameters::Complex(z))
+        | (Parameters::Complex(z), Parameters::InterpreterVector(vec)) => {
+            let result = vec
+                .into_iter()
+                .map(|x| mult(x.clone(), Parameters::Complex(z.clone()), ram))
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
+            Parameters::InterpreterVector(Box::from(result))
+        }
+        (a @ Parameters::Complex(_), b) | (a, b @ Parameters::Complex(_)) => {
+            match (Complex::from_parameter(&a), Complex::from_parameter(&b)) {
+                (Some(z), Some(w)) => z.mul(&w)?,
+                _ => return Err(CalcError::incompatible("*", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Radical(r), Parameters::Null) | (Parameters::Null, Parameters::Radical(r)) => {
+            Parameters::Radical(r)
+        }
+        (Parameters::Radical(r), Parameters::Float(f))
+        | (Parameters::Float(f), Parameters::Radical(r)) => Parameters::Float(r.approx() * f),
+        (Parameters::InterpreterVector(vec), Parameters::Radical(r))
+        | (Parameters::Radical(r), Parameters::InterpreterVector(vec)) => {
+            let result = vec
+                .into_iter()
+                .map(|x| mult(x.clone(), Parameters::Radical(r.clone()), ram))
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
+            Parameters::InterpreterVector(Box::from(result))
+        }
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_radical(&a), to_radical(&b)) {
+                (Some(r), Some(r2)) => from_radical(r * r2),
+                _ => return Err(CalcError::incompatible("*", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("*", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn divide(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(v),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(f),
+        (Parameters::Int(_), Parameters::Int(0)) => return Err(CalcError::DivisionByZero),
         (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Rational(Rationals::new(v2, v)),
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64) / f),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v / f),
@@ -490,12 +752,22 @@ pub fn divide(
             Parameters::InterpreterVector(vec.clone())
         }
 
+        (Parameters::Rational(s), Parameters::Null)
+        | (Parameters::Null, Parameters::Rational(s))
+            if s.clone().is_null() =>
+        {
+            return Err(CalcError::DivisionByZero)
+        }
         (Parameters::Rational(s), Parameters::Null) => {
             Parameters::Rational(Rationals::new(1, 1) / s)
         }
         (Parameters::Null, Parameters::Rational(s)) => {
             Parameters::Rational(Rationals::new(1, 1) / s)
         }
+        (_, Parameters::Rational(s2)) if s2.clone().is_null() => {
+            return Err(CalcError::DivisionByZero)
+        }
+        (Parameters::Rational(_), Parameters::Int(0)) => return Err(CalcError::DivisionByZero),
         (Parameters::Rational(s), Parameters::Rational(s2)) => Parameters::Rational(s / s2),
 
         (Parameters::Rational(s), Parameters::Int(i)) => {
@@ -518,56 +790,112 @@ pub fn divide(
             Parameters::Identifier(s2),
             ram,
             divide,
-        ),
+        )?,
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
             Parameters::Rational(s.clone()),
             Parameters::Identifier(ss.clone()),
             ram,
             divide,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             divide,
-        ),
+        )?,
 
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, divide)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, divide)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, divide);
+            let v = apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, divide)?;
             match v {
                 Parameters::Float(i) => Parameters::Float(1.0 / i),
                 _ => Parameters::Null,
             }
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, divide)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, divide)?
         }
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, divide)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, divide)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, divide)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, divide)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, divide);
+            let v = apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, divide)?;
             match v {
                 Parameters::Float(i) => Parameters::Float(1.0 / i),
                 _ => Parameters::Null,
             }
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, divide)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, divide)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, divide)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, divide)?
+        }
+        (Parameters::Quantity(q), Parameters::Null)
+        | (Parameters::Null, Parameters::Quantity(q)) => Parameters::Quantity(q),
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => q.div(&q2)?,
+                _ => return Err(CalcError::incompatible("/", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Polynomial(p), Parameters::Null)
+        | (Parameters::Null, Parameters::Polynomial(p)) => Parameters::Polynomial(p),
+        (a @ Parameters::Polynomial(_), b) | (a, b @ Parameters::Polynomial(_)) => {
+            match polynomial_pair(&a, &b).map(|(p, q)| p.div_rem(&q)) {
+                Some(None) => return Err(CalcError::DivisionByZero),
+                // an exact quotient stays a polynomial, otherwise a fraction
+                Some(Some((q, r))) if r.is_zero() => from_polynomial(q),
+                _ => divide(polynomial_to_symbolic(a), polynomial_to_symbolic(b), ram)?,
+            }
+        }
+        (Parameters::Symbolic(s), Parameters::Null)
+        | (Parameters::Null, Parameters::Symbolic(s)) => Parameters::Symbolic(s),
+        (a @ Parameters::Symbolic(_), b) | (a, b @ Parameters::Symbolic(_)) => {
+            match (to_symbolic(&a), to_symbolic(&b)) {
+                (Some(s), Some(s2)) => from_symbolic(s.div(s2)),
+                _ => return Err(CalcError::incompatible("/", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Complex(z), Parameters::Null) | (Parameters::Null, Parameters::Complex(z)) => {
+            Parameters::Complex(z)
         }
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the / operator".to_string(),
-        ),
+        (a @ Parameters::Complex(_), b) | (a, b @ Parameters::Complex(_)) => {
+            match (Complex::from_parameter(&a), Complex::from_parameter(&b)) {
+                (Some(z), Some(w)) => z.div(&w)?,
+                _ => return Err(CalcError::incompatible("/", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Radical(r), Parameters::Null) | (Parameters::Null, Parameters::Radical(r)) => {
+            Parameters::Radical(r)
+        }
+        (Parameters::Radical(r), Parameters::Float(f)) => Parameters::Float(r.approx() / f),
+        (Parameters::Float(f), Parameters::Radical(r)) => Parameters::Float(f / r.approx()),
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_radical(&a), to_radical(&b)) {
+                (Some(r), Some(r2)) => match r.checked_div(&r2) {
+                    Some(r) => from_radical(r),
+                    None => return Err(CalcError::DivisionByZero),
+                },
+                _ => return Err(CalcError::incompatible("/", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("/", a.type_name(), b.type_name())),
+    })
+}
+
+/// Whether `a ^ b` raises a negative real to a real power which is not an
+/// integer, the result being complex.
+fn is_complex_power(a: &Parameters, b: &Parameters) -> bool {
+    match (to_float(a), to_float(b)) {
+        (Some(x), Some(y)) => x < 0.0 && y.fract() != 0.0,
+        _ => false,
     }
 }
 
@@ -575,13 +903,23 @@ pub fn expo(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    // the principal value, (-1)^0.5 = i
+    if is_complex_power(&i, &i2) {
+        let (z, w) = (Complex::from_parameter(&i), Complex::from_parameter(&i2));
+        return z.unwrap().pow(&w.unwrap());
+    }
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(v),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(f),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Float((v as f64).powf(v2 as f64)),
+        (Parameters::Int(v), Parameters::Int(v2)) => match Rationals::new(1, v).checked_pow(v2) {
+            Some(r) if r.is_integer() => int_or_big(r.over),
+            Some(r) => Parameters::Rational(r),
+            None if v == 0 => return Err(CalcError::DivisionByZero),
+            None => return Err(CalcError::Overflow("^".to_string())),
+        },
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64).powf(f)),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v.powf(f)),
         (Parameters::Float(v), Parameters::Int(i1)) => Parameters::Float(v.powf(i1 as f64)),
@@ -591,9 +929,11 @@ pub fn expo(
         (Parameters::Rational(s), Parameters::Rational(s2)) => {
             Parameters::Float(s.approx().powf(s2.approx()))
         }
-        (Parameters::Rational(s), Parameters::Int(i)) => {
-            Parameters::Float(s.approx().powf(i as f64))
-        }
+        (Parameters::Rational(s), Parameters::Int(i)) => match s.checked_pow(i) {
+            Some(r) => Parameters::Rational(r),
+            None if s.clone().is_null() => return Err(CalcError::DivisionByZero),
+            None => return Err(CalcError::Overflow("^".to_string())),
+        },
         (Parameters::Int(i), Parameters::Rational(s)) => {
             Parameters::Float((i as f64).powf(s.approx()))
         }
@@ -611,15 +951,15 @@ pub fn expo(
             Parameters::Identifier(s2),
             ram,
             expo,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, expo)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, expo)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, expo)
+            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, expo)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, expo)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, expo)?
         }
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
@@ -627,32 +967,89 @@ pub fn expo(
             Parameters::Identifier(ss.clone()),
             ram,
             expo,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             expo,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, expo)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, expo)?
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, expo)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, expo)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, expo)
+            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, expo)?
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, expo)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, expo)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, expo)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, expo)?
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the ^ operator".to_string(),
-        ),
+        (Parameters::Quantity(q), Parameters::Null)
+        | (Parameters::Null, Parameters::Quantity(q)) => Parameters::Quantity(q),
+        (Parameters::Quantity(q), b) => q.pow(&b)?,
+        (a, b @ Parameters::Quantity(_)) => {
+            return Err(CalcError::incompatible("^", a.type_name(), b.type_name()))
+        }
+        (Parameters::Polynomial(p), Parameters::Null)
+        | (Parameters::Null, Parameters::Polynomial(p)) => Parameters::Polynomial(p),
+        (Parameters::Polynomial(p), Parameters::Int(i)) if (0..=MAX_BITS as i64).contains(&i) => {
+            from_polynomial(p.pow(i as u32))
+        }
+        (a @ Parameters::Polynomial(_), b) | (a, b @ Parameters::Polynomial(_)) => {
+            expo(polynomial_to_symbolic(a), polynomial_to_symbolic(b), ram)?
+        }
+        (Parameters::Symbolic(s), Parameters::Null)
+        | (Parameters::Null, Parameters::Symbolic(s)) => Parameters::Symbolic(s),
+        (a @ Parameters::Symbolic(_), b) | (a, b @ Parameters::Symbolic(_)) => {
+            match (to_symbolic(&a), to_symbolic(&b)) {
+                (Some(s), Some(s2)) => from_symbolic(s.pow(s2)),
+                _ => return Err(CalcError::incompatible("^", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Complex(z), Parameters::Null) | (Parameters::Null, Parameters::Complex(z)) => {
+            Parameters::Complex(z)
+        }
+        (a @ Parameters::Complex(_), b) | (a, b @ Parameters::Complex(_)) => {
+            match (Complex::from_parameter(&a), Complex::from_parameter(&b)) {
+                (Some(z), Some(w)) => z.pow(&w)?,
+                _ => return Err(CalcError::incompatible("^", a.type_name(), b.type_name())),
+            }
+        }
+        (Parameters::Radical(r), Parameters::Null) | (Parameters::Null, Parameters::Radical(r)) => {
+            Parameters::Radical(r)
+        }
+        (Parameters::Radical(_), Parameters::Int(i)) if i.unsigned_abs() > MAX_BITS => {
+            return Err(CalcError::Overflow("^".to_string()))
+        }
+        (Parameters::Radical(r), Parameters::Int(i)) => match r.pow(i) {
+            Some(r) => from_radical(r),
+            None => return Err(CalcError::DivisionByZero),
+        },
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_float(&a), to_float(&b)) {
+                (Some(f), Some(f2)) => Parameters::Float(f.powf(f2)),
+                _ => return Err(CalcError::incompatible("^", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("^", a.type_name(), b.type_name())),
+    })
+}
+
+/// Expresses a quantity in the units of another one, `60 mph to km/h`.
+pub fn convert(
+    i: Parameters,
+    i2: Parameters,
+    _ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    match (Quantity::from_parameter(&i), &i2) {
+        (Some(q), Parameters::Quantity(target)) => q.convert(target),
+        _ => Err(CalcError::incompatible("to", i.type_name(), i2.type_name())),
     }
 }
 
@@ -667,8 +1064,8 @@ pub fn greater(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(_)) => Bool(true),
         (Parameters::Null, Parameters::Float(_)) => Bool(true),
         (Parameters::Int(_), Parameters::Null) => Bool(true),
@@ -696,63 +1093,76 @@ pub fn greater(
             Parameters::Identifier(s2),
             ram,
             greater,
-        ),
+        )?,
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
             Parameters::Rational(s.clone()),
             Parameters::Identifier(ss.clone()),
             ram,
             greater,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             greater,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, greater)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, greater)?
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, greater)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, greater)?
         }
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, greater)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, greater)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, greater)
+            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, greater)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Float(i),
             ram,
             greater,
-        ),
+        )?,
         (Parameters::Float(i), Parameters::Identifier(s)) => apply_operator_reverse(
             Parameters::Float(i),
             Parameters::Identifier(s),
             ram,
             greater,
-        ),
+        )?,
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, greater)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, greater)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, greater)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, greater)?
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the > operator".to_string(),
-        ),
-    }
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => {
+                    let (x, y) = q.compare(&q2)?;
+                    greater(x, y, None)?
+                }
+                _ => return Err(CalcError::incompatible(">", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_float(&a), to_float(&b)) {
+                (Some(f), Some(f2)) => Bool(f > f2),
+                _ => return Err(CalcError::incompatible(">", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible(">", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn lesser(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(_)) => Bool(false),
         (Parameters::Null, Parameters::Float(_)) => Bool(false),
         (Parameters::Int(_), Parameters::Null) => Bool(false),
@@ -780,56 +1190,69 @@ pub fn lesser(
             Parameters::Identifier(s2),
             ram,
             lesser,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, lesser)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, lesser)?
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, lesser)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, lesser)?
         }
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
             Parameters::Rational(s.clone()),
             Parameters::Identifier(ss.clone()),
             ram,
             lesser,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             lesser,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, lesser)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, lesser)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, lesser)
+            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, lesser)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, lesser)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, lesser)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, lesser)
+            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, lesser)?
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, lesser)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, lesser)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, lesser)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, lesser)?
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the < operator".to_string(),
-        ),
-    }
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => {
+                    let (x, y) = q.compare(&q2)?;
+                    lesser(x, y, None)?
+                }
+                _ => return Err(CalcError::incompatible("<", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_float(&a), to_float(&b)) {
+                (Some(f), Some(f2)) => Bool(f < f2),
+                _ => return Err(CalcError::incompatible("<", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("<", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn greater_or_equal(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(_)) => Bool(true),
         (Parameters::Null, Parameters::Float(_)) => Bool(true),
         (Parameters::Int(_), Parameters::Null) => Bool(true),
@@ -858,75 +1281,88 @@ pub fn greater_or_equal(
             Parameters::Identifier(s2),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Int(i),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Null, Parameters::Identifier(s)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Null,
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Null) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Null,
             ram,
             greater_or_equal,
-        ),
+        )?,
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
             Parameters::Rational(s.clone()),
             Parameters::Identifier(ss.clone()),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Int(i), Parameters::Identifier(s)) => apply_operator_reverse(
             Parameters::Int(i),
             Parameters::Identifier(s),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Float(i)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Float(i),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Parameters::Float(i), Parameters::Identifier(s)) => apply_operator_reverse(
             Parameters::Float(i),
             Parameters::Identifier(s),
             ram,
             greater_or_equal,
-        ),
+        )?,
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, greater_or_equal)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, greater_or_equal)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, greater_or_equal)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, greater_or_equal)?
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the >= operator".to_string(),
-        ),
-    }
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => {
+                    let (x, y) = q.compare(&q2)?;
+                    greater_or_equal(x, y, None)?
+                }
+                _ => return Err(CalcError::incompatible(">=", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_float(&a), to_float(&b)) {
+                (Some(f), Some(f2)) => Bool(f >= f2),
+                _ => return Err(CalcError::incompatible(">=", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible(">=", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn lesser_or_equal(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(_)) => Bool(false),
         (Parameters::Null, Parameters::Float(_)) => Bool(false),
         (Parameters::Int(_), Parameters::Null) => Bool(false),
@@ -955,75 +1391,88 @@ pub fn lesser_or_equal(
             Parameters::Identifier(s2),
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Int(i),
             ram,
             lesser_or_equal,
-        ),
+        )?,
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
             Parameters::Rational(s.clone()),
             Parameters::Identifier(ss.clone()),
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Null, Parameters::Identifier(s)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Null,
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Null) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Null,
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Int(i), Parameters::Identifier(s)) => apply_operator_reverse(
             Parameters::Int(i),
             Parameters::Identifier(s),
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Float(i)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Float(i),
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Parameters::Float(i), Parameters::Identifier(s)) => apply_operator_reverse(
             Parameters::Float(i),
             Parameters::Identifier(s),
             ram,
             lesser_or_equal,
-        ),
+        )?,
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, lesser_or_equal)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, lesser_or_equal)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, lesser_or_equal)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, lesser_or_equal)?
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the <= operator".to_string(),
-        ),
-    }
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => {
+                    let (x, y) = q.compare(&q2)?;
+                    lesser_or_equal(x, y, None)?
+                }
+                _ => return Err(CalcError::incompatible("<=", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_float(&a), to_float(&b)) {
+                (Some(f), Some(f2)) => Bool(f <= f2),
+                _ => return Err(CalcError::incompatible("<=", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("<=", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn equal(
     i: Parameters,
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match (i, i2) {
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Parameters::Null, Parameters::Int(_)) => Bool(true),
         (Parameters::Null, Parameters::Float(_)) => Bool(true),
         (Parameters::Int(_), Parameters::Null) => Bool(true),
@@ -1052,30 +1501,30 @@ pub fn equal(
             Parameters::Identifier(s2),
             ram,
             equal,
-        ),
+        )?,
         (Parameters::Identifier(s), Parameters::Int(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, equal)
+            apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, equal)?
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, equal)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, equal)?
         }
         (Parameters::Identifier(s), Parameters::Null) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, equal)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, equal)?
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, equal)
+            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, equal)?
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, equal)
+            apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, equal)?
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, equal)
+            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, equal)?
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, equal)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, equal)?
         }
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, equal)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, equal)?
         }
 
         (Parameters::Rational(s), Parameters::Identifier(ss)) => apply_operator_reverse(
@@ -1083,226 +1532,495 @@ pub fn equal(
             Parameters::Identifier(ss.clone()),
             ram,
             equal,
-        ),
+        )?,
         (Parameters::Identifier(ss), Parameters::Rational(s)) => apply_operator(
             Parameters::Identifier(ss),
             Parameters::Rational(s),
             ram,
             equal,
-        ),
+        )?,
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the == operator".to_string(),
-        ),
-    }
+        (a @ Parameters::Quantity(_), b) | (a, b @ Parameters::Quantity(_)) => {
+            match (Quantity::from_parameter(&a), Quantity::from_parameter(&b)) {
+                (Some(q), Some(q2)) => {
+                    let (x, y) = q.compare(&q2)?;
+                    equal(x, y, None)?
+                }
+                _ => return Err(CalcError::incompatible("==", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Complex(_), b) | (a, b @ Parameters::Complex(_)) => {
+            match (Complex::from_parameter(&a), Complex::from_parameter(&b)) {
+                (Some(z), Some(w)) => Bool(
+                    equal(z.re, w.re, None)? == Bool(true)
+                        && equal(z.im, w.im, None)? == Bool(true),
+                ),
+                _ => return Err(CalcError::incompatible("==", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Polynomial(_), b) | (a, b @ Parameters::Polynomial(_)) => {
+            match polynomial_pair(&a, &b) {
+                Some((p, q)) => Bool(p == q),
+                None => return Err(CalcError::incompatible("==", a.type_name(), b.type_name())),
+            }
+        }
+        (a @ Parameters::Radical(_), b) | (a, b @ Parameters::Radical(_)) => {
+            match (to_radical(&a), to_radical(&b), to_float(&a), to_float(&b)) {
+                (Some(r), Some(r2), _, _) => Bool(r == r2),
+                (_, _, Some(f), Some(f2)) => Bool(f == f2),
+                _ => return Err(CalcError::incompatible("==", a.type_name(), b.type_name())),
+            }
+        }
+        (a, b) => return Err(CalcError::incompatible("==", a.type_name(), b.type_name())),
+    })
 }
 
 pub fn not(
     i: Parameters,
     _i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
-    match i {
+) -> Result<Parameters, CalcError> {
+    Ok(match i {
         Bool(b) => Bool(!b),
         Parameters::Identifier(s) => {
-            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, not)
+            apply_operator(Parameters::Identifier(s), Parameters::Null, ram, not)?
         }
-        _ => Bool(false),
-    }
+        a => return Err(CalcError::wrong_type("!", a.type_name())),
+    })
 }
 
-pub fn and(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameters>>) -> Parameters {
-    match (i, i2) {
+pub fn and(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Bool(b), Bool(b2)) => Bool(b && b2),
         (Bool(b), Parameters::Null) => Bool(b),
         (Parameters::Null, Bool(b)) => Bool(b),
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, and)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, and)?
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, and)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, and)?
         }
         (Parameters::Identifier(s), Parameters::Identifier(s2)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Identifier(s2),
             ram,
             and,
-        ),
-        _ => Bool(false),
-    }
+        )?,
+        (a, b) => return Err(CalcError::incompatible("&&", a.type_name(), b.type_name())),
+    })
 }
 
-pub fn or(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameters>>) -> Parameters {
-    match (i, i2) {
+pub fn or(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    Ok(match (i, i2) {
         (Bool(b), Bool(b2)) => Bool(b || b2),
         (Bool(b), Parameters::Null) => Bool(b),
         (Parameters::Null, Bool(b)) => Bool(b),
         (Parameters::Identifier(s), Bool(b)) => {
-            apply_operator(Parameters::Identifier(s), Bool(b), ram, or)
+            apply_operator(Parameters::Identifier(s), Bool(b), ram, or)?
         }
         (Bool(b), Parameters::Identifier(s)) => {
-            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, or)
+            apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, or)?
         }
         (Parameters::Identifier(s), Parameters::Identifier(s2)) => apply_operator(
             Parameters::Identifier(s),
             Parameters::Identifier(s2),
             ram,
             or,
-        ),
-        _ => Bool(false),
+        )?,
+        (a, b) => return Err(CalcError::incompatible("||", a.type_name(), b.type_name())),
+    })
+}
+
+/// The value of an operand of the integer operators, big integers are integral
+/// rationals.
+fn integer_operand(p: &Parameters) -> Option<BigInt> {
+    match p {
+        Parameters::Int(i) => Some(BigInt::from(*i)),
+        Parameters::Rational(r) if r.is_integer() => Some(r.clone().reduce().over),
+        _ => None,
+    }
+}
+
+fn resolve(
+    p: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    match p {
+        Parameters::Identifier(s) => match ram.and_then(|i_ram| i_ram.get(&s)) {
+            Some(val) => Ok(val.clone()),
+            None => Err(CalcError::UndefinedVariable(s)),
+        },
+        p => Ok(p),
+    }
+}
+
+fn integer_operation(
+    operator: &str,
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+    f: fn(BigInt, BigInt) -> Result<BigInt, CalcError>,
+) -> Result<Parameters, CalcError> {
+    let (i, i2) = (resolve(i, ram)?, resolve(i2, ram)?);
+    match (integer_operand(&i), integer_operand(&i2)) {
+        (Some(a), Some(b)) => Ok(int_or_big(f(a, b)?)),
+        _ => Err(CalcError::incompatible(
+            operator,
+            i.type_name(),
+            i2.type_name(),
+        )),
+    }
+}
+
+/// The shift count of `<<` and `>>`, negative counts are an error.
+fn shift_count(operator: &str, b: &BigInt) -> Result<u64, CalcError> {
+    if b.is_negative() {
+        return Err(CalcError::TypeMismatch {
+            context: format!("The {operator} operator"),
+            found: "a negative shift count".to_string(),
+        });
+    }
+    Ok(b.to_u64().unwrap_or(u64::MAX))
+}
+
+pub fn modulo(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    let (i, i2) = (resolve(i, ram)?, resolve(i2, ram)?);
+    // on polynomials and expressions in one variable, the remainder of the
+    // euclidean division given by polydiv
+    let variable = [&i, &i2].into_iter().find_map(|p| match p {
+        Parameters::Polynomial(p) => Some(p.variable.clone()),
+        Parameters::Symbolic(s) => match s.symbols().as_slice() {
+            [x] => Some(x.clone()),
+            _ => None,
+        },
+        _ => None,
+    });
+    if let Some(variable) = variable {
+        return match (to_polynomial(&i, &variable), to_polynomial(&i2, &variable)) {
+            (Some(p), Some(q)) => match p.div_rem(&q) {
+                Some((_, r)) => Ok(from_polynomial(r)),
+                None => Err(CalcError::DivisionByZero),
+            },
+            _ => Err(CalcError::incompatible("%", i.type_name(), i2.type_name())),
+        };
+    }
+    integer_operation("%", i, i2, ram, |a, b| {
+        if b.is_zero() {
+            return Err(CalcError::DivisionByZero);
+        }
+        Ok(a.mod_floor(&b))
+    })
+}
+
+pub fn integer_division(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    integer_operation("//", i, i2, ram, |a, b| {
+        if b.is_zero() {
+            return Err(CalcError::DivisionByZero);
+        }
+        Ok(a.div_floor(&b))
+    })
+}
+
+pub fn left_shift(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    integer_operation("<<", i, i2, ram, |a, b| {
+        let n = shift_count("<<", &b)?;
+        if a.is_zero() {
+            return Ok(a);
+        }
+        if a.bits().saturating_add(n) > MAX_BITS {
+            return Err(CalcError::Overflow("<<".to_string()));
+        }
+        Ok(a << n)
+    })
+}
+
+pub fn right_shift(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    integer_operation(">>", i, i2, ram, |a, b| {
+        // shifting past the last bit leaves 0 or -1, rounding towards -inf
+        let n = shift_count(">>", &b)?.min(a.bits() + 1);
+        Ok(a >> n)
+    })
+}
+
+pub fn bit_and(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    match (resolve(i, ram)?, resolve(i2, ram)?) {
+        (Bool(b), Bool(b2)) => Ok(Bool(b && b2)),
+        (a, b) => integer_operation("&", a, b, ram, |a, b| Ok(a & b)),
+    }
+}
+
+pub fn bit_or(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    match (resolve(i, ram)?, resolve(i2, ram)?) {
+        (Bool(b), Bool(b2)) => Ok(Bool(b || b2)),
+        (a, b) => integer_operation("|", a, b, ram, |a, b| Ok(a | b)),
+    }
+}
+
+pub fn xor(
+    i: Parameters,
+    i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    match (resolve(i, ram)?, resolve(i2, ram)?) {
+        (Bool(b), Bool(b2)) => Ok(Bool(b != b2)),
+        (a, b) => integer_operation("xor", a, b, ram, |a, b| Ok(a ^ b)),
+    }
+}
+
+pub fn bit_not(
+    i: Parameters,
+    _i2: Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    let i = resolve(i, ram)?;
+    match integer_operand(&i) {
+        Some(a) => Ok(int_or_big(!a)),
+        None => Err(CalcError::wrong_type("~", i.type_name())),
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::interpreting::function::{add, divide, minus, mult};
+    use crate::errors::calc_error::CalcError;
+    use crate::exact_math::rationals::Rationals;
+    use crate::interpreting::function::{add, bit_not, divide, expo, minus, modulo, mult};
     use crate::parsing::ast::Parameters;
 
     #[test]
     pub fn test_add_null() {
         let expected = Parameters::Int(1);
-        let result = add(Parameters::Int(1), Parameters::Null, None);
+        let result = add(Parameters::Int(1), Parameters::Null, None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_add_simple() {
         let expected = Parameters::Int(2);
-        let result = add(Parameters::Int(1), Parameters::Int(1), None);
+        let result = add(Parameters::Int(1), Parameters::Int(1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_add_float() {
         let expected = Parameters::Float(2.1);
-        let result = add(Parameters::Float(0.1), Parameters::Float(2.0), None);
+        let result = add(Parameters::Float(0.1), Parameters::Float(2.0), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_add_int_float() {
         let expected = Parameters::Float(2.1);
-        let result = add(Parameters::Int(2), Parameters::Float(0.1), None);
+        let result = add(Parameters::Int(2), Parameters::Float(0.1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_add_float_int() {
         let expected = Parameters::Float(2.1);
-        let result = add(Parameters::Float(0.1), Parameters::Int(2), None);
+        let result = add(Parameters::Float(0.1), Parameters::Int(2), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_minus_null() {
         let expected = Parameters::Int(-1);
-        let result = minus(Parameters::Int(1), Parameters::Null, None);
+        let result = minus(Parameters::Int(1), Parameters::Null, None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_minus_null_rev() {
         let expected = Parameters::Int(-1);
-        let result = minus(Parameters::Null, Parameters::Int(1), None);
+        let result = minus(Parameters::Null, Parameters::Int(1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_minus_simple() {
         let expected = Parameters::Int(0);
-        let result = minus(Parameters::Int(1), Parameters::Int(1), None);
+        let result = minus(Parameters::Int(1), Parameters::Int(1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_minus_float() {
         let expected = Parameters::Float(1.9);
-        let result = minus(Parameters::Float(2.0), Parameters::Float(0.1), None);
+        let result = minus(Parameters::Float(2.0), Parameters::Float(0.1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_minus_int_float() {
         let expected = Parameters::Float(1.9);
-        let result = minus(Parameters::Int(2), Parameters::Float(0.1), None);
+        let result = minus(Parameters::Int(2), Parameters::Float(0.1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_minus_float_int() {
         let expected = Parameters::Float(-1.9);
-        let result = minus(Parameters::Float(0.1), Parameters::Int(2), None);
+        let result = minus(Parameters::Float(0.1), Parameters::Int(2), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_mult_null() {
         let expected = Parameters::Int(1);
-        let result = mult(Parameters::Int(1), Parameters::Null, None);
+        let result = mult(Parameters::Int(1), Parameters::Null, None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_mult_simple() {
         let expected = Parameters::Int(2);
-        let result = mult(Parameters::Int(1), Parameters::Int(2), None);
+        let result = mult(Parameters::Int(1), Parameters::Int(2), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_mult_float() {
         let expected = Parameters::Float(0.2);
-        let result = mult(Parameters::Float(0.1), Parameters::Float(2.0), None);
+        let result = mult(Parameters::Float(0.1), Parameters::Float(2.0), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_mult_int_float() {
         let expected = Parameters::Float(0.2);
-        let result = mult(Parameters::Int(2), Parameters::Float(0.1), None);
+        let result = mult(Parameters::Int(2), Parameters::Float(0.1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_mult_float_int() {
         let expected = Parameters::Float(0.2);
-        let result = mult(Parameters::Float(0.1), Parameters::Int(2), None);
+        let result = mult(Parameters::Float(0.1), Parameters::Int(2), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_divide_null() {
         let expected = Parameters::Int(1);
-        let result = divide(Parameters::Int(1), Parameters::Null, None);
+        let result = divide(Parameters::Int(1), Parameters::Null, None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_divide_simple() {
-        let expected =
-            Parameters::Rational(crate::exact_math::rationals::Rationals { under: 1, over: 1 });
-        let result = divide(Parameters::Int(1), Parameters::Int(1), None);
+        let expected = Parameters::Rational(crate::exact_math::rationals::Rationals::new(1, 1));
+        let result = divide(Parameters::Int(1), Parameters::Int(1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_divide_float() {
         let expected = Parameters::Float(0.05);
-        let result = divide(Parameters::Float(0.1), Parameters::Float(2.0), None);
+        let result = divide(Parameters::Float(0.1), Parameters::Float(2.0), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_divide_int_float() {
         let expected = Parameters::Float(20.0);
-        let result = divide(Parameters::Int(2), Parameters::Float(0.1), None);
+        let result = divide(Parameters::Int(2), Parameters::Float(0.1), None).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     pub fn test_divide_float_int() {
         let expected = Parameters::Float(0.05);
-        let result = divide(Parameters::Float(0.1), Parameters::Int(2), None);
+        let result = divide(Parameters::Float(0.1), Parameters::Int(2), None).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn test_divide_by_zero() {
+        let expected = Err(CalcError::DivisionByZero);
+        let result = divide(Parameters::Int(1), Parameters::Int(0), None);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn test_add_overflow() {
+        let expected = "9223372036854775808";
+        let result = add(Parameters::Int(i64::MAX), Parameters::Int(1), None);
+        assert_eq!(result.unwrap().to_string(), expected);
+    }
+
+    #[test]
+    pub fn test_mult_overflow() {
+        let expected = "85070591730234615847396907784232501249";
+        let result = mult(Parameters::Int(i64::MAX), Parameters::Int(i64::MAX), None);
+        assert_eq!(result.unwrap().to_string(), expected);
+    }
+
+    #[test]
+    pub fn test_expo_exact() {
+        let expected = "1267650600228229401496703205376";
+        let result = expo(Parameters::Int(2), Parameters::Int(100), None);
+        assert_eq!(result.unwrap().to_string(), expected);
+    }
+
+    #[test]
+    pub fn test_expo_negative() {
+        let expected = Parameters::Rational(Rationals::new(8, 1));
+        let result = expo(Parameters::Int(2), Parameters::Int(-3), None);
+        assert_eq!(result, Ok(expected));
+    }
+
+    #[test]
+    pub fn test_add_type_mismatch() {
+        let result = add(Parameters::Int(1), Parameters::Str("a".to_string()), None);
+        assert!(matches!(result, Err(CalcError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    pub fn test_modulo_rational() {
+        let big = Parameters::Rational(Rationals::new(1, 10_i64.pow(18)));
+        let result = modulo(big, Parameters::Int(7), None).unwrap();
+        assert_eq!(result, Parameters::Int(1));
+        let half = Parameters::Rational(Rationals::new(2, 1));
+        assert_eq!(
+            modulo(half, Parameters::Int(7), None),
+            Err(CalcError::incompatible("%", "a rational", "an integer"))
+        );
+    }
+
+    #[test]
+    pub fn test_bit_not() {
+        let result = bit_not(Parameters::Int(-1), Parameters::Null, None).unwrap();
+        assert_eq!(result, Parameters::Int(0));
+    }
 }
@@ -1,99 +1,427 @@
 use std::collections::HashMap;
 
+use crate::errors::calc_error::CalcError;
 use crate::exact_math::rationals::Rationals;
+use crate::exact_math::symbolic::Symbolic;
+use crate::exact_math::units::{lookup, unit_quantity, Quantity};
 use crate::interpreting::function::{
-    add, and, assign, divide, equal, expo, greater, greater_or_equal, lesser, lesser_or_equal,
-    minus, mult, not, or,
+    add, and, assign, bit_and, bit_not, bit_or, convert, divide, equal, expo, from_polynomial,
+    from_symbolic, greater, greater_or_equal, integer_division, left_shift, lesser,
+    lesser_or_equal, minus, modulo, mult, not, or, right_shift, to_symbolic, xor,
+};
+use crate::interpreting::stdlib::{
+    constants, exec, is_protected, save_all, save_function, save_variable, BUILTINS,
 };
-use crate::interpreting::stdlib::exec;
 use crate::parsing::ast::{Ast, Parameters};
 
+/// Unbound names of units next to a number or a quantity are units, 3 m is
+/// three metres while 3 x stays symbolic.
+fn attach_units(a: Parameters, b: Parameters) -> (Parameters, Parameters) {
+    let is_measure = |p: &Parameters| Quantity::from_parameter(p).is_some();
+    if is_measure(&a) && !is_measure(&b) {
+        let b = unit_quantity(&b).unwrap_or(b);
+        (a, b)
+    } else if is_measure(&b) && !is_measure(&a) {
+        (unit_quantity(&a).unwrap_or(a), b)
+    } else {
+        (a, b)
+    }
+}
+
+fn symbolize(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Identifier(s) if !s.starts_with('@') => {
+            Parameters::Symbolic(Box::from(Symbolic::Symbol(s)))
+        }
+        p => p,
+    }
+}
+
+/// An operand which must have a value, a lone symbol being an unbound name.
+fn bound(p: Parameters) -> Result<Parameters, CalcError> {
+    match p {
+        Parameters::Symbolic(s) => match *s {
+            Symbolic::Symbol(name) => Err(CalcError::UndefinedVariable(name)),
+            s => Ok(Parameters::Symbolic(Box::from(s))),
+        },
+        p => Ok(p),
+    }
+}
+
+/// An argument of a call, where an unbound name is passed as is for the
+/// function to read it as a variable, a function or a unit.
+fn interpret_argument(
+    ast: &Ast,
+    ram: &mut HashMap<String, Parameters>,
+    function: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Result<Parameters, CalcError> {
+    match ast {
+        Ast::Node {
+            value: Parameters::Identifier(s),
+            ..
+        } if !ram.contains_key(s) && !s.starts_with('$') => Ok(Parameters::Identifier(s.clone())),
+        _ => interpret(ast, ram, function),
+    }
+}
+
 pub fn interpret(
     ast: &Ast,
     mut ram: &mut HashMap<String, Parameters>,
     mut function: &mut HashMap<String, (Vec<Ast>, Ast)>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     match ast {
-        Ast::Nil => Parameters::Null,
+        Ast::Nil => Ok(Parameters::Null),
         Ast::Node {
             value: v,
             left: l,
             right: r,
         } => {
-            let param1 = interpret(l, &mut ram, &mut function);
-            let param2 = interpret(r, &mut ram, &mut function);
+            let (param1, param2) = match (v, l.as_ref()) {
+                // neither the prototype nor the body of a function definition is evaluated
+                (Parameters::Assign, Ast::Call { .. }) => (Parameters::Null, Parameters::Null),
+                // the target of an assignment is a name, not its current value
+                (
+                    Parameters::Assign,
+                    Ast::Node {
+                        value: Parameters::Identifier(s),
+                        ..
+                    },
+                ) => (
+                    Parameters::Identifier(s.clone()),
+                    interpret(r, ram, function)?,
+                ),
+                _ => {
+                    let param1 = interpret(l, ram, function)?;
+                    // `&&` and `||` only evaluate their right side when it decides the result
+                    match (v, &param1) {
+                        (Parameters::AndOperation, Parameters::Bool(false))
+                        | (Parameters::OrOperation, Parameters::Bool(true)) => return Ok(param1),
+                        _ => (),
+                    }
+                    (param1, interpret(r, ram, function)?)
+                }
+            };
+            let (param1, param2) = match v {
+                Parameters::MultiplicationOperation | Parameters::DivideOperation => {
+                    attach_units(param1, param2)
+                }
+                // both sides of a conversion are read as units
+                Parameters::ConversionOperation => (
+                    unit_quantity(&param1).unwrap_or(param1),
+                    unit_quantity(&param2).unwrap_or(param2),
+                ),
+                _ => (param1, param2),
+            };
+            // unbound names taking part in arithmetic are symbols
+            let (param1, param2) = match v {
+                Parameters::PlusOperation
+                | Parameters::MinusOperation
+                | Parameters::MultiplicationOperation
+                | Parameters::DivideOperation
+                | Parameters::ExpoOperation => (symbolize(param1), symbolize(param2)),
+                Parameters::Assign | Parameters::ConversionOperation => (param1, param2),
+                // other operators need values
+                _ => (bound(param1)?, bound(param2)?),
+            };
             let last = match v {
-                Parameters::PlusOperation => add(param1, param2, Some(&ram)),
-                Parameters::MinusOperation => minus(param1, param2, Some(&ram)),
-                Parameters::MultiplicationOperation => mult(param1, param2, Some(&ram)),
-                Parameters::DivideOperation => divide(param1, param2, Some(&ram)),
-                Parameters::ExpoOperation => expo(param1, param2, Some(&ram)),
-                Parameters::Equal => equal(param1, param2, Some(&ram)),
-                Parameters::Not => not(param1, param2, Some(&ram)),
-                Parameters::GreaterOperation => greater(param1, param2, Some(&ram)),
-                Parameters::GreaterOrEqualOperation => greater_or_equal(param1, param2, Some(&ram)),
-                Parameters::LesserOperation => lesser(param1, param2, Some(&ram)),
-                Parameters::LesserOrEqualOperation => lesser_or_equal(param1, param2, Some(&ram)),
-                Parameters::AndOperation => and(param1, param2, Some(&ram)),
-                Parameters::OrOperation => or(param1, param2, Some(&ram)),
+                Parameters::PlusOperation => add(param1, param2, Some(ram))?,
+                Parameters::MinusOperation => minus(param1, param2, Some(ram))?,
+                Parameters::MultiplicationOperation => mult(param1, param2, Some(ram))?,
+                Parameters::DivideOperation => divide(param1, param2, Some(ram))?,
+                Parameters::ExpoOperation => expo(param1, param2, Some(ram))?,
+                Parameters::ConversionOperation => convert(param1, param2, Some(ram))?,
+                Parameters::ModuloOperation => modulo(param1, param2, Some(ram))?,
+                Parameters::IntegerDivisionOperation => {
+                    integer_division(param1, param2, Some(ram))?
+                }
+                Parameters::LeftShiftOperation => left_shift(param1, param2, Some(ram))?,
+                Parameters::RightShiftOperation => right_shift(param1, param2, Some(ram))?,
+                Parameters::BitAndOperation => bit_and(param1, param2, Some(ram))?,
+                Parameters::BitOrOperation => bit_or(param1, param2, Some(ram))?,
+                Parameters::XorOperation => xor(param1, param2, Some(ram))?,
+                Parameters::BitNotOperation => bit_not(param1, param2, Some(ram))?,
+                Parameters::Equal => equal(param1, param2, Some(ram))?,
+                Parameters::Not => not(param1, param2, Some(ram))?,
+                Parameters::GreaterOperation => greater(param1, param2, Some(ram))?,
+                Parameters::GreaterOrEqualOperation => greater_or_equal(param1, param2, Some(ram))?,
+                Parameters::LesserOperation => lesser(param1, param2, Some(ram))?,
+                Parameters::LesserOrEqualOperation => lesser_or_equal(param1, param2, Some(ram))?,
+                Parameters::AndOperation => and(param1, param2, Some(ram))?,
+                Parameters::OrOperation => or(param1, param2, Some(ram))?,
                 Parameters::Rational(s) => Parameters::Rational(s.clone()),
+                Parameters::Radical(s) => Parameters::Radical(s.clone()),
+                Parameters::Symbolic(s) => Parameters::Symbolic(s.clone()),
+                Parameters::Polynomial(p) => Parameters::Polynomial(p.clone()),
+                Parameters::Complex(z) => Parameters::Complex(z.clone()),
+                Parameters::Quantity(q) => Parameters::Quantity(q.clone()),
                 Parameters::Str(s) => Parameters::Str(s.to_string()),
                 Parameters::Assign => match *(l.clone()) {
                     Ast::Call { name: n, lst: list } => {
-                        if function.contains_key(&n) {
-                            Parameters::Str("This function has already been set".to_string())
+                        if is_protected(&n) {
+                            return Err(CalcError::ProtectedName(n));
+                        }
+                        let message = if function.contains_key(&n) {
+                            format!("@The function {} has been redefined", n)
                         } else {
-                            if n.as_str() != "" {
-                                (function).insert(n.to_string(), (list, *r.clone()));
-                            }
-                            Parameters::Identifier(format!(
-                                "@The function {} has been set",
-                                n.clone()
-                            ))
+                            format!("@The function {} has been set", n)
+                        };
+                        if n.as_str() != "" {
+                            save_function(function, &n);
+                            (function).insert(n.to_string(), (list, *r.clone()));
                         }
+                        Parameters::Identifier(message)
                     }
                     _ => {
                         let (a, b) = assign(param1.clone(), param2.clone());
                         if a != "".to_string() {
+                            if is_protected(&a) {
+                                return Err(CalcError::ProtectedName(a));
+                            }
+                            save_variable(ram, &a);
                             if ram.contains_key(&a) {
                                 ram.remove(&a);
                             }
                             (ram).insert(a.clone(), b.clone());
 
-                            return Parameters::Identifier(format!(
+                            return Ok(Parameters::Identifier(format!(
                                 "@ {} = {}",
                                 a.clone(),
                                 b.clone().pretty_print(Some(ram), Some(function))
-                            ));
+                            )));
                         }
                         Parameters::Null
                     }
                 },
-                Parameters::Float(f) => Parameters::Rational(Rationals::rationalize(*f)),
+                Parameters::Float(f) => match Rationals::rationalize(*f) {
+                    Some(r) => Parameters::Rational(r),
+                    None => Parameters::Float(*f),
+                },
                 Parameters::Int(i) => Parameters::Int(*i),
-                Parameters::Identifier(s) => {
-                    if ram.contains_key(s) {
-                        ram.get(s).unwrap().clone()
-                    } else {
+                Parameters::Identifier(s) => match ram.get(s) {
+                    Some(value) => value.clone(),
+                    // a reference to a result which does not exist, `$9` or `out[9]`
+                    None if s.starts_with('$') => {
+                        return Err(CalcError::UndefinedVariable(s.clone()))
+                    }
+                    // units and functions are read by the operators and calls around them
+                    None if lookup(s).is_some()
+                        || function.contains_key(s)
+                        || BUILTINS.contains(&s.as_str()) =>
+                    {
                         Parameters::Identifier(s.clone())
                     }
-                }
+                    // any other unbound name is a symbol
+                    None => symbolize(Parameters::Identifier(s.clone())),
+                },
                 Parameters::Bool(b) => Parameters::Bool(*b),
                 Parameters::Null => Parameters::Null,
                 Parameters::Vector(a) => {
-                    let mut vec = Vec::new();
-                    (*a).clone()
+                    let vec = (*a)
+                        .clone()
                         .into_iter()
-                        .map(|a| interpret(&a, ram, function))
-                        .for_each(|s| vec.push(s));
+                        .map(|a| interpret(&a, ram, function).map(symbolize))
+                        .collect::<Result<Vec<Parameters>, CalcError>>()?;
                     Parameters::InterpreterVector(Box::from(vec))
                 }
                 Parameters::InterpreterVector(a) => Parameters::InterpreterVector(a.clone()),
             };
-            last.clone()
+            Ok(last.clone())
+        }
+        Ast::Call { name: n, lst: list } if n == "if" => {
+            if list.len() != 3 {
+                return Err(CalcError::ArityMismatch {
+                    function: "if".to_string(),
+                    expected: 3,
+                    found: list.len(),
+                });
+            }
+            // only the branch selected by the condition is evaluated
+            match interpret(&list[0], ram, function)? {
+                Parameters::Bool(true) => interpret(&list[1], ram, function),
+                Parameters::Bool(false) => interpret(&list[2], ram, function),
+                p => Err(CalcError::wrong_type("if", p.type_name())),
+            }
+        }
+        Ast::Call { name: n, lst: list } if n == "undef" => {
+            if list.is_empty() {
+                return Err(CalcError::ArityMismatch {
+                    function: "undef".to_string(),
+                    expected: 1,
+                    found: 0,
+                });
+            }
+            let mut removed = Vec::new();
+            for arg in list {
+                let name = match arg {
+                    Ast::Node {
+                        value: Parameters::Identifier(s),
+                        ..
+                    } => s.clone(),
+                    _ => return Err(CalcError::wrong_type("undef", "an expression")),
+                };
+                if is_protected(&name) {
+                    return Err(CalcError::ProtectedName(name));
+                }
+                save_variable(ram, &name);
+                save_function(function, &name);
+                let variable = ram.remove(&name).is_some();
+                let func = function.remove(&name).is_some();
+                if !variable && !func {
+                    return Err(CalcError::UndefinedVariable(name));
+                }
+                removed.push(name);
+            }
+            Ok(Parameters::Identifier(format!(
+                "@{} has been removed",
+                removed.join(", ")
+            )))
+        }
+        Ast::Call { name: n, lst: list } if n == "diff" => {
+            if list.len() != 2 {
+                return Err(CalcError::ArityMismatch {
+                    function: "diff".to_string(),
+                    expected: 2,
+                    found: list.len(),
+                });
+            }
+            let x = match &list[1] {
+                Ast::Node {
+                    value: Parameters::Identifier(s),
+                    ..
+                } => s.clone(),
+                _ => return Err(CalcError::wrong_type("diff", "an expression")),
+            };
+            let user_function = match &list[0] {
+                Ast::Node {
+                    value: Parameters::Identifier(s),
+                    ..
+                } if function.contains_key(s) => Some(s.clone()),
+                _ => None,
+            };
+
+            // the variable, and the parameters of a user function, are evaluated
+            // as symbols even when they are bound
+            let mut frame = ram.clone();
+            frame.insert(
+                x.clone(),
+                Parameters::Symbolic(Box::from(Symbolic::Symbol(x.clone()))),
+            );
+            let (value, parameters) = match &user_function {
+                Some(f) => {
+                    let (parameters, body) = function.get(f).unwrap().clone();
+                    for p in &parameters {
+                        if let Ast::Node {
+                            value: Parameters::Identifier(s),
+                            ..
+                        } = p
+                        {
+                            let symbol = Symbolic::Symbol(s.clone());
+                            frame.insert(s.clone(), Parameters::Symbolic(Box::from(symbol)));
+                        }
+                    }
+                    (interpret(&body, &mut frame, function)?, parameters)
+                }
+                None => (interpret(&list[0], &mut frame, function)?, Vec::new()),
+            };
+
+            // a polynomial in x stays one
+            if let (Parameters::Polynomial(p), None) = (&value, &user_function) {
+                if p.variable == x {
+                    return Ok(from_polynomial(p.derivative()));
+                }
+            }
+            let derivative = match to_symbolic(&value) {
+                Some(s) => s.derivative(&x)?,
+                None => return Err(CalcError::wrong_type("diff", value.type_name())),
+            };
+            // the derivative of a user function f is registered as f'
+            if let Some(f) = user_function {
+                save_function(function, &format!("{f}'"));
+                function.insert(format!("{f}'"), (parameters, derivative.to_ast()));
+            }
+            Ok(from_symbolic(derivative))
+        }
+        // solve(lhs == rhs, x) solves lhs - rhs = 0 for x
+        Ast::Call { name: n, lst: list }
+            if n == "solve"
+                && matches!(
+                    list.first(),
+                    Some(Ast::Node {
+                        value: Parameters::Equal,
+                        ..
+                    })
+                ) =>
+        {
+            let (left, right) = match &list[0] {
+                Ast::Node { left, right, .. } => (left, right),
+                _ => unreachable!(),
+            };
+            let x = match list.get(1) {
+                Some(Ast::Node {
+                    value: Parameters::Identifier(s),
+                    ..
+                }) => s.clone(),
+                Some(_) => return Err(CalcError::wrong_type("solve", "an expression")),
+                None => {
+                    return Err(CalcError::ArityMismatch {
+                        function: "solve".to_string(),
+                        expected: 2,
+                        found: 1,
+                    })
+                }
+            };
+
+            // the unknown is a symbol even when it is bound
+            let mut frame = ram.clone();
+            frame.insert(
+                x.clone(),
+                Parameters::Symbolic(Box::from(Symbolic::Symbol(x.clone()))),
+            );
+            let left = symbolize(interpret(left, &mut frame, function)?);
+            let right = symbolize(interpret(right, &mut frame, function)?);
+            let difference = minus(left, right, None)?;
+            if let Some(s) = to_symbolic(&difference) {
+                if let Some(other) = s.symbols().into_iter().find(|s| s != &x) {
+                    return Err(CalcError::UndefinedVariable(other));
+                }
+            }
+
+            let mut args = vec![difference];
+            for a in &list[2..] {
+                args.push(interpret(a, ram, function)?);
+            }
+            // Newton starts from 1 when no starting point is given
+            if args.len() == 1 {
+                args.push(Parameters::Int(1));
+            }
+            exec(n.to_string(), args, Some(&mut ram), Some(&mut function))
+        }
+        Ast::Call { name: n, lst: list } if n == "clear" => {
+            if !list.is_empty() {
+                return Err(CalcError::ArityMismatch {
+                    function: "clear".to_string(),
+                    expected: 0,
+                    found: list.len(),
+                });
+            }
+            save_all(ram, function);
+            // the results of the session stay, the REPL keeps numbering them
+            let outputs: Vec<(String, Parameters)> = ram
+                .drain()
+                .filter(|(name, _)| name.starts_with('$') || name == "ans" || name == "_")
+                .collect();
+            *ram = constants();
+            ram.extend(outputs);
+            function.clear();
+            Ok(Parameters::Identifier(
+                "@All variables and functions have been cleared".to_string(),
+            ))
         }
         Ast::Call { name: n, lst: list } => {
-            let v: Vec<Parameters> = list.iter().map(|x| interpret(x, ram, function)).collect();
+            let v: Vec<Parameters> = list
+                .iter()
+                .map(|x| interpret_argument(x, ram, function))
+                .collect::<Result<Vec<Parameters>, CalcError>>()?;
             exec(n.to_string(), v, Some(&mut ram), Some(&mut function))
         }
     }
@@ -102,10 +430,27 @@ pub fn interpret(
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
+    use std::f64::consts::PI;
 
+    use crate::errors::calc_error::CalcError;
+    use crate::exact_math::complex::Complex;
     use crate::exact_math::rationals::Rationals;
+    use crate::exact_math::symbolic::Symbolic;
     use crate::interpreting::interpreter::interpret;
+    use crate::interpreting::stdlib::set_recursion_limit;
+    use crate::lexing::lexer::lex;
     use crate::parsing::ast::{Ast, Parameters};
+    use crate::parsing::parser::init_calc_parser;
+
+    fn run(
+        input: &str,
+        ram: &mut HashMap<String, Parameters>,
+        function: &mut HashMap<String, (Vec<Ast>, Ast)>,
+    ) -> Result<Parameters, CalcError> {
+        let tokens = lex(input.to_string());
+        let ast = init_calc_parser(&tokens).parse().unwrap();
+        interpret(&ast, ram, function)
+    }
 
     #[test]
     fn test_interpreter_int() {
@@ -117,7 +462,7 @@ mod test {
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
         };
-        let result = interpret(&ast, &mut ram, &mut function);
+        let result = interpret(&ast, &mut ram, &mut function).unwrap();
         assert_eq!(result, expected)
     }
 
@@ -131,7 +476,7 @@ mod test {
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
         };
-        let result = interpret(&ast, &mut ram, &mut function);
+        let result = interpret(&ast, &mut ram, &mut function).unwrap();
         assert_eq!(result, expected)
     }
 
@@ -145,7 +490,7 @@ mod test {
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
         };
-        let result = interpret(&ast, &mut ram, &mut function);
+        let result = interpret(&ast, &mut ram, &mut function).unwrap();
         assert_eq!(result, expected)
     }
 
@@ -159,7 +504,7 @@ mod test {
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
         };
-        let result = interpret(&ast, &mut ram, &mut function);
+        let result = interpret(&ast, &mut ram, &mut function).unwrap();
         assert_eq!(result, expected)
     }
 
@@ -173,7 +518,7 @@ mod test {
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
         };
-        let result = interpret(&ast, &mut ram, &mut function);
+        let result = interpret(&ast, &mut ram, &mut function).unwrap();
         assert_eq!(result, expected)
     }
 
@@ -181,14 +526,542 @@ mod test {
     fn test_interpreter_divide_operation() {
         let mut ram: HashMap<String, Parameters> = HashMap::new();
         let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
-        let expected =
-            Parameters::Rational(crate::exact_math::rationals::Rationals { under: 1, over: 1 });
+        let expected = Parameters::Rational(crate::exact_math::rationals::Rationals::new(1, 1));
         let ast = Ast::Node {
             value: Parameters::DivideOperation,
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
         };
+        let result = interpret(&ast, &mut ram, &mut function).unwrap();
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_interpreter_unknown_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let expected = Err(CalcError::UnknownFunction("foo".to_string()));
+        let ast = Ast::Call {
+            name: "foo".to_string(),
+            lst: vec![Ast::new(Parameters::Int(1))],
+        };
+        let result = interpret(&ast, &mut ram, &mut function);
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_interpreter_undefined_variable() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let expected = Err(CalcError::UndefinedVariable("x".to_string()));
+        let ast = Ast::Node {
+            value: Parameters::GreaterOperation,
+            left: Box::from(Ast::new(Parameters::Identifier("x".to_string()))),
+            right: Box::from(Ast::new(Parameters::Int(1))),
+        };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn test_interpreter_symbolic() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let result = run("2x + 3x - 1 + 1", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "5x");
+        run("y = x + 1", &mut ram, &mut function).unwrap();
+        let result = run("2y - x", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "x+2");
+        let result = run("sin(x)^2 / sin(x)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "sin(x)");
+        // a lone unbound name is a symbol as well
+        let result = run("z", &mut ram, &mut function).unwrap();
+        assert_eq!(
+            result,
+            Parameters::Symbolic(Box::from(Symbolic::Symbol("z".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_diff() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(t) = t^3 - 2t", &mut ram, &mut function).unwrap();
+        let result = run("diff(f, t)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "3t^2-2");
+        let result = run("f'(2)", &mut ram, &mut function);
+        assert_eq!(result, Ok(Parameters::Int(10)));
+        // the variable is a symbol even when it is bound
+        run("x = 3", &mut ram, &mut function).unwrap();
+        let result = run("diff(x^2, x)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "2x");
+    }
+
+    #[test]
+    fn test_interpreter_integrate() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        ram.insert("pi".to_string(), Parameters::Float(PI));
+        ram.insert("inf".to_string(), Parameters::Float(f64::INFINITY));
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let value = |p: Parameters| match p {
+            Parameters::Float(f) => f,
+            p => panic!("expected a float, found {p}"),
+        };
+        let result = run("integrate(sin, 0, pi)", &mut ram, &mut function).unwrap();
+        assert!((value(result) - 2.0).abs() < 1e-12);
+        run("f(t) = t^2", &mut ram, &mut function).unwrap();
+        let result = run("integrate(f, 0, 3)", &mut ram, &mut function).unwrap();
+        assert!((value(result) - 9.0).abs() < 1e-12);
+        let result = run("integrate(1/(1+x^2), -inf, inf)", &mut ram, &mut function).unwrap();
+        assert!((value(result) - PI).abs() < 1e-10);
+        let result = run("integrate(foo, 0, 1)", &mut ram, &mut function);
+        assert_eq!(result, Err(CalcError::UnknownFunction("foo".to_string())));
+    }
+
+    #[test]
+    fn test_interpreter_solve() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let value = |p: Parameters| match p {
+            Parameters::Float(f) => f,
+            p => panic!("expected a float, found {p}"),
+        };
+        run("f(t) = t^3 - t - 1", &mut ram, &mut function).unwrap();
+        let result = run("solve(f, 1)", &mut ram, &mut function).unwrap();
+        assert!((value(result) - 1.324717957244746).abs() < 1e-12);
+        let result = run("solve(cos, 0, 3)", &mut ram, &mut function).unwrap();
+        assert!((value(result) - PI / 2.0).abs() < 1e-12);
+        // the unknown of an equation is a symbol even when it is bound
+        run("x = 5", &mut ram, &mut function).unwrap();
+        let result = run("solve(x^2 - 2 == 0, x)", &mut ram, &mut function).unwrap();
+        assert!((value(result) - 2f64.sqrt()).abs() < 1e-12);
+        let result = run("solve(x^2 == y, x)", &mut ram, &mut function);
+        assert_eq!(result, Err(CalcError::UndefinedVariable("y".to_string())));
+        let result = run("solve(x == 0, x)", &mut ram, &mut function).unwrap();
+        assert_eq!(value(result), 0.0);
+        let result = run("solve(foo, 1)", &mut ram, &mut function);
+        assert_eq!(result, Err(CalcError::UnknownFunction("foo".to_string())));
+    }
+
+    #[test]
+    fn test_interpreter_polynomial() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let mut eval = |s: &str| run(s, &mut ram, &mut function).unwrap().to_string();
+        assert_eq!(eval("p = poly([1,0,-2])"), "@ p = x^2-2");
+        assert_eq!(eval("p*(x+1)"), "x^3+x^2-2x-2");
+        assert_eq!(eval("(x^2-1)/poly([1,-1])"), "x+1");
+        assert_eq!(eval("p(3)"), "7");
+        assert_eq!(eval("diff(p, x)"), "2x");
+        assert_eq!(eval("gcd(x^2+x-2, x^2-4x+3)"), "x-1");
+        assert_eq!(eval("p % poly([1,-1])"), "-1");
+        assert_eq!(eval("(x^3-1) % (x-1)"), "0");
+        assert_eq!(eval("factor(2x^3+11x^2+12x-9)"), "(x+3)^2*(2x-1)");
+        assert_eq!(eval("factor(x^4/2-x^2)"), "x^2*(x^2-2)/2");
+        let roots = run("roots(x^3-6x^2+11x-6)", &mut ram, &mut function);
+        let expected = (1..=3).map(Parameters::Int).collect::<Vec<Parameters>>();
+        assert_eq!(
+            roots,
+            Ok(Parameters::InterpreterVector(Box::from(expected)))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_complex() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        ram.insert(
+            "i".to_string(),
+            Parameters::Complex(Box::from(Complex::i())),
+        );
+        ram.insert("pi".to_string(), Parameters::Float(PI));
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let mut eval = |s: &str| run(s, &mut ram, &mut function).unwrap().to_string();
+        assert_eq!(eval("sqrt(-4)"), "2i");
+        assert_eq!(eval("(1+2i)*(3-i)"), "5+5i");
+        assert_eq!(eval("(5+5i)/(3-i)"), "1+2i");
+        assert_eq!(eval("i^2"), "-1");
+        assert_eq!(eval("conj(3+4i)"), "3-4i");
+        assert_eq!(eval("abs(3+4i)"), "5");
+        assert_eq!(eval("re(3+4i) + im(3+4i)"), "7");
+        assert_eq!(eval("exp(i*pi)"), "-1.0000000000");
+        assert_eq!(eval("ln(-1)"), "3.1415926536i");
+        // negative bases to fractional powers
+        assert_eq!(eval("(-1)^0.5"), "1.0000000000i");
+        assert_eq!(eval("(-1)^(1/2)"), "1.0000000000i");
+        assert_eq!(eval("(-8)^(1/3)"), "1.0000000000+1.7320508076i");
+        assert_eq!(eval("(-2)^3"), "-8");
+        assert_eq!(eval("log(-8, 2)"), "3.0000000000+4.5323601418i");
+        assert_eq!(eval("log(8, 2)"), "3.0000000000");
+    }
+
+    #[test]
+    fn test_interpreter_bases() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let mut eval = |s: &str| run(s, &mut ram, &mut function).map(|p| p.to_string());
+        assert_eq!(eval("0x1f + 0b11"), Ok("34".to_string()));
+        assert_eq!(eval("hex(255)"), Ok("0xff".to_string()));
+        assert_eq!(eval("bin(-6)"), Ok("-0b110".to_string()));
+        assert_eq!(eval("to_base(35, 36)"), Ok("z".to_string()));
+        assert_eq!(eval("hex(2^64)"), Ok("0x10000000000000000".to_string()));
+        assert_eq!(
+            eval("hex(1.5)"),
+            Err(CalcError::wrong_type("hex", "a rational"))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_integer_operators() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let mut eval = |s: &str| run(s, &mut ram, &mut function).map(|p| p.to_string());
+        assert_eq!(eval("-7 % 3"), Ok("2".to_string()));
+        assert_eq!(eval("-7 // 2"), Ok("-4".to_string()));
+        assert_eq!(eval("1 + 2 << 3"), Ok("24".to_string()));
+        assert_eq!(eval("-5 >> 1"), Ok("-3".to_string()));
+        assert_eq!(eval("6 & 3 | 8"), Ok("10".to_string()));
+        assert_eq!(eval("6 xor 3"), Ok("5".to_string()));
+        assert_eq!(eval("~0x0f"), Ok("-16".to_string()));
+        assert_eq!(
+            eval("x & 1 == 0"),
+            Err(CalcError::UndefinedVariable("x".to_string()))
+        );
+        assert_eq!(eval("2^70 % 1000"), Ok("424".to_string()));
+        assert_eq!(eval("5 // 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(
+            eval("7.5 % 2"),
+            Err(CalcError::incompatible("%", "a rational", "an integer"))
+        );
+        assert_eq!(
+            eval("1 << 2000000"),
+            Err(CalcError::Overflow("<<".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_previous_results() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        ram.insert("$1".to_string(), Parameters::Int(3));
+        ram.insert("ans".to_string(), Parameters::Int(5));
+        let mut eval = |s: &str| run(s, &mut ram, &mut function).map(|p| p.to_string());
+        assert_eq!(eval("$1 + out[1] + ans"), Ok("11".to_string()));
+        assert_eq!(
+            eval("f(x) = x * ans"),
+            Ok("@The function f has been set".to_string())
+        );
+        assert_eq!(eval("f(2)"), Ok("10".to_string()));
+        assert_eq!(
+            eval("$9 + 1"),
+            Err(CalcError::UndefinedVariable("$9".to_string()))
+        );
+        assert_eq!(
+            eval("out[9]"),
+            Err(CalcError::UndefinedVariable("$9".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_units() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let mut eval = |s: &str| run(s, &mut ram, &mut function).map(|p| p.to_string());
+        assert_eq!(eval("3 m / 2 s"), Ok("3/2 m/s".to_string()));
+        assert_eq!(eval("5 kg * 2 m/s^2"), Ok("10 kg*m/s^2".to_string()));
+        assert_eq!(eval("36 km/h to m/s"), Ok("10 m/s".to_string()));
+        assert_eq!(eval("1 km/h in m/s"), Ok("5/18 m/s".to_string()));
+        assert_eq!(eval("1 N to kg*m/s^2"), Ok("1 kg*m/s^2".to_string()));
+        assert_eq!(eval("2 km / 4 m"), Ok("500".to_string()));
+        assert_eq!(eval("sqrt(9 m^2)"), Ok("3 m".to_string()));
+        assert_eq!(eval("2 x"), Ok("2x".to_string()));
+        assert_eq!(
+            eval("1 m + 1 s"),
+            Err(CalcError::DimensionMismatch(
+                "cannot add m and s".to_string()
+            ))
+        );
+        assert_eq!(
+            eval("1 kg to m"),
+            Err(CalcError::DimensionMismatch(
+                "cannot convert kg to m".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_function_definition() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let ast = Ast::Node {
+            value: Parameters::Assign,
+            left: Box::from(Ast::Call {
+                name: "f".to_string(),
+                lst: vec![Ast::new(Parameters::Identifier("x".to_string()))],
+            }),
+            right: Box::from(Ast::Node {
+                value: Parameters::PlusOperation,
+                left: Box::from(Ast::new(Parameters::Identifier("x".to_string()))),
+                right: Box::from(Ast::new(Parameters::Int(1))),
+            }),
+        };
+        let _ = interpret(&ast, &mut ram, &mut function).unwrap();
+        let call = Ast::Call {
+            name: "f".to_string(),
+            lst: vec![Ast::new(Parameters::Int(2))],
+        };
+        let result = interpret(&call, &mut ram, &mut function).unwrap();
+        assert_eq!(result, Parameters::Int(3))
+    }
+
+    #[test]
+    fn test_interpreter_function_reads_globals() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x * k", &mut ram, &mut function).unwrap();
+        run("k = 3", &mut ram, &mut function).unwrap();
+        let result = run("f(2)", &mut ram, &mut function);
+        assert_eq!(result, Ok(Parameters::Int(6)))
+    }
+
+    #[test]
+    fn test_interpreter_function_calls_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x + 1", &mut ram, &mut function).unwrap();
+        run("g(x) = f(x) * 2", &mut ram, &mut function).unwrap();
+        let result = run("g(2)", &mut ram, &mut function);
+        assert_eq!(result, Ok(Parameters::Int(6)))
+    }
+
+    #[test]
+    fn test_interpreter_function_does_not_leak_arguments() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("x = 10", &mut ram, &mut function).unwrap();
+        run("f(x) = x + 1", &mut ram, &mut function).unwrap();
+        run("f(2)", &mut ram, &mut function).unwrap();
+        assert_eq!(ram.get("x"), Some(&Parameters::Int(10)))
+    }
+
+    #[test]
+    fn test_interpreter_function_body_does_not_leak() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("y = 1", &mut ram, &mut function).unwrap();
+        run("f(x) = (y = x)", &mut ram, &mut function).unwrap();
+        run("g(x) = (h(z) = z)", &mut ram, &mut function).unwrap();
+        run("f(5)", &mut ram, &mut function).unwrap();
+        run("g(1)", &mut ram, &mut function).unwrap();
+        assert_eq!(ram.get("y"), Some(&Parameters::Int(1)));
+        assert!(!function.contains_key("h"))
+    }
+
+    #[test]
+    fn test_interpreter_recursion_limit() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        set_recursion_limit(10);
+        run("f(x) = f(x + 1)", &mut ram, &mut function).unwrap();
+        let expected = Err(CalcError::RecursionLimit {
+            function: "f".to_string(),
+            limit: 10,
+        });
+        let result = run("f(1)", &mut ram, &mut function);
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_interpreter_if_is_lazy() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let result = run("if(1 < 2, 1, 1/0)", &mut ram, &mut function);
+        assert_eq!(result, Ok(Parameters::Int(1)))
+    }
+
+    #[test]
+    fn test_interpreter_if_condition_must_be_bool() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let result = run("if(1, 2, 3)", &mut ram, &mut function);
+        assert_eq!(result, Err(CalcError::wrong_type("if", "an integer")))
+    }
+
+    #[test]
+    fn test_interpreter_recursive_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run(
+            "fib(n) = if(n<2, n, fib(n-1)+fib(n-2))",
+            &mut ram,
+            &mut function,
+        )
+        .unwrap();
+        let result = run("fib(10)", &mut ram, &mut function);
+        assert_eq!(result, Ok(Parameters::Int(55)))
+    }
+
+    #[test]
+    fn test_interpreter_short_circuit() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let and = run("false && (1/0 == 1)", &mut ram, &mut function);
+        let or = run("true || (1/0 == 1)", &mut ram, &mut function);
+        assert_eq!(and, Ok(Parameters::Bool(false)));
+        assert_eq!(or, Ok(Parameters::Bool(true)))
+    }
+
+    #[test]
+    fn test_interpreter_logic_below_comparisons() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("n = 0", &mut ram, &mut function).unwrap();
+        let or = run("n == 0 || 1/n > 1", &mut ram, &mut function);
+        let between = run("if(n > 0 && n < 5, 1, 0)", &mut ram, &mut function);
+        assert_eq!(or, Ok(Parameters::Bool(true)));
+        assert_eq!(between, Ok(Parameters::Int(0)))
+    }
+
+    #[test]
+    fn test_interpreter_reassign_variable() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("x = 1", &mut ram, &mut function).unwrap();
+        run("x = 2", &mut ram, &mut function).unwrap();
+        assert_eq!(ram.get("x"), Some(&Parameters::Int(2)))
+    }
+
+    #[test]
+    fn test_interpreter_redefine_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x", &mut ram, &mut function).unwrap();
+        let expected = Parameters::Identifier("@The function f has been redefined".to_string());
+        let result = run("f(x) = x + 1", &mut ram, &mut function);
+        assert_eq!(result, Ok(expected));
+        assert_eq!(run("f(1)", &mut ram, &mut function), Ok(Parameters::Int(2)))
+    }
+
+    #[test]
+    fn test_interpreter_protected_names() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let cos = run("cos(x) = x", &mut ram, &mut function);
+        let pi = run("pi = 3", &mut ram, &mut function);
+        assert_eq!(cos, Err(CalcError::ProtectedName("cos".to_string())));
+        assert_eq!(pi, Err(CalcError::ProtectedName("pi".to_string())))
+    }
+
+    #[test]
+    fn test_interpreter_undef() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("x = 1", &mut ram, &mut function).unwrap();
+        run("f(y) = y", &mut ram, &mut function).unwrap();
+        run("undef(x, f)", &mut ram, &mut function).unwrap();
+        assert!(!ram.contains_key("x"));
+        assert!(!function.contains_key("f"));
+        let result = run("undef(x)", &mut ram, &mut function);
+        assert_eq!(result, Err(CalcError::UndefinedVariable("x".to_string())))
+    }
+
+    #[test]
+    fn test_interpreter_clear() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("x = 1", &mut ram, &mut function).unwrap();
+        run("f(y) = y", &mut ram, &mut function).unwrap();
+        ram.insert("$1".to_string(), Parameters::Int(2));
+        ram.insert("ans".to_string(), Parameters::Int(2));
+        run("clear()", &mut ram, &mut function).unwrap();
+        assert_eq!(ram.len(), 6);
+        assert!(["pi", "e", "inf", "i"].iter().all(|c| ram.contains_key(*c)));
+        assert!(!ram.contains_key("x"));
+        assert!(function.is_empty());
+        let result = run("out[1] + ans", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "4")
+    }
+
+    #[test]
+    fn test_interpreter_big_integers() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let result = run("fact(25)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "15511210043330985984000000");
+        let result = run("fact(25)/fact(24)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "25");
+        let result = run("99999999999999999999+1", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "100000000000000000000");
+        for f in ["ceil", "floor", "round"] {
+            let result = run(&format!("{f}(2^70)"), &mut ram, &mut function).unwrap();
+            assert_eq!(result.to_string(), "1180591620717411303424");
+        }
+        assert_eq!(
+            run("fact(2^70)", &mut ram, &mut function),
+            Err(CalcError::Overflow("fact".to_string()))
+        );
+        let result = run("2^70", &mut ram, &mut function).unwrap();
+        assert_eq!(result.type_name(), "an integer")
+    }
+
+    #[test]
+    fn test_interpreter_rationalize() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        ram.insert("pi".to_string(), Parameters::Float(PI));
+        let result = run("rationalize(pi, 1000)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "355/113");
+        let result = run("rationalize(pi, 2^80)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "884279719003555/281474976710656");
+        for maxden in ["0", "-5"] {
+            assert_eq!(
+                run(
+                    &format!("rationalize(pi, {maxden})"),
+                    &mut ram,
+                    &mut function
+                ),
+                Err(CalcError::wrong_type(
+                    "rationalize",
+                    "a non positive maxden"
+                ))
+            );
+        }
+        let result = run("0.1+0.2", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "3/10");
+        let result = run("0.00000000001", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "1/100000000000")
+    }
+
+    #[test]
+    fn test_interpreter_exact_decimals() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        for (input, expected) in [
+            ("0.123456789", "123456789/1000000000"),
+            ("1.0000001", "10000001/10000000"),
+            ("123456789.123 - 123456789", "123/1000"),
+            ("2.5e-3", "1/400"),
+        ] {
+            let result = run(input, &mut ram, &mut function).unwrap();
+            assert_eq!(result.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_interpreter_radicals() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let result = run("sqrt(8)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "2√2");
+        let result = run("sqrt(2)*sqrt(2)", &mut ram, &mut function).unwrap();
+        assert_eq!(result, Parameters::Rational(Rationals::new(1, 2)));
+        run("a = [[sqrt(2),1],[1,sqrt(2)]]", &mut ram, &mut function).unwrap();
+        let result = run("det(a)", &mut ram, &mut function).unwrap();
+        assert_eq!(result.to_string(), "1");
+        let result = run("invert(a)*a", &mut ram, &mut function).unwrap();
+        let expected = run("[[1,0],[0,1]]", &mut ram, &mut function).unwrap();
+        assert_eq!(
+            result.pretty_print(Some(&mut ram), Some(&mut function)),
+            expected.pretty_print(Some(&mut ram), Some(&mut function))
+        )
+    }
 }
@@ -1,22 +1,235 @@
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::f64::consts::{E, PI};
 
 use gnuplot::{AxesCommon, Figure};
-
-use crate::configuration::loader::{load, load_config, Config};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive};
+
+use crate::configuration::loader::{load, load_config, Config, DEFAULT_RECURSION_LIMIT};
+use crate::errors::calc_error::CalcError;
+use crate::exact_math::complex::Complex;
+use crate::exact_math::polynomial::{rational_parameter, Polynomial};
+use crate::exact_math::radicals::Radicals;
+use crate::exact_math::rationals::Rationals;
+use crate::exact_math::symbolic::Symbolic;
+use crate::exact_math::units::Quantity;
 use crate::interpreting::interpreter::interpret;
 use crate::parsing::ast::{Ast, Parameters};
+use crate::utils::integer_utils::{int_or_big, with_prefix, MAX_BITS};
+use crate::utils::integration_utils::{self, DEFAULT_TOLERANCE as INTEGRATION_TOLERANCE};
 use crate::utils::matrix_utils::{lup_decompose, lup_determinant, lup_invert, transpose};
 use crate::utils::plot_utils::computes_lines;
+use crate::utils::solve_utils;
+
+use super::function::{
+    add as other_add, divide, from_polynomial, from_radical, from_symbolic, mult, to_float,
+    to_polynomial, to_symbolic,
+};
+
+type Definition = (Vec<Ast>, Ast);
+type Functions = HashMap<String, Definition>;
+
+/// The variables and functions a call to a user function changed, with the
+/// values they had before, put back when the call returns.
+#[derive(Default)]
+struct Overwritten {
+    variables: Vec<(String, Option<Parameters>)>,
+    functions: Vec<(String, Option<Definition>)>,
+}
+
+thread_local! {
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static RECURSION_LIMIT: Cell<usize> = const { Cell::new(DEFAULT_RECURSION_LIMIT) };
+    static DEGREES: Cell<bool> = const { Cell::new(false) };
+    static OVERWRITTEN: RefCell<Vec<Overwritten>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Keeps the value of the variable `name` before the body of a user function
+/// changes it, outside of a call nothing is kept.
+pub fn save_variable(ram: &HashMap<String, Parameters>, name: &str) {
+    OVERWRITTEN.with(|o| {
+        if let Some(frame) = o.borrow_mut().last_mut() {
+            frame
+                .variables
+                .push((name.to_string(), ram.get(name).cloned()));
+        }
+    });
+}
+
+/// Keeps the definition of the function `name` before the body of a user
+/// function changes it.
+pub fn save_function(functions: &Functions, name: &str) {
+    OVERWRITTEN.with(|o| {
+        if let Some(frame) = o.borrow_mut().last_mut() {
+            frame
+                .functions
+                .push((name.to_string(), functions.get(name).cloned()));
+        }
+    });
+}
 
-use super::function::{add as other_add, mult};
+/// Keeps every variable and function, before `clear()`.
+pub fn save_all(ram: &HashMap<String, Parameters>, functions: &Functions) {
+    let names = ram
+        .keys()
+        .map(String::as_str)
+        .chain(CONSTANTS.iter().copied());
+    names.for_each(|name| save_variable(ram, name));
+    functions
+        .keys()
+        .for_each(|name| save_function(functions, name));
+}
+
+impl Overwritten {
+    /// Puts the values back, the oldest last so that it wins.
+    fn restore(self, ram: &mut HashMap<String, Parameters>, functions: &mut Functions) {
+        for (name, value) in self.variables.into_iter().rev() {
+            match value {
+                Some(v) => ram.insert(name, v),
+                None => ram.remove(&name),
+            };
+        }
+        for (name, value) in self.functions.into_iter().rev() {
+            match value {
+                Some(f) => functions.insert(name, f),
+                None => functions.remove(&name),
+            };
+        }
+    }
+}
+
+pub fn set_recursion_limit(limit: usize) {
+    RECURSION_LIMIT.with(|l| l.set(limit));
+}
+
+/// Makes the trigonometric functions and their inverses use degrees without
+/// a second argument.
+pub fn set_degrees(degrees: bool) {
+    DEGREES.with(|d| d.set(degrees));
+}
+
+/// Names handled by `exec` and the interpreter that user definitions must not
+/// shadow.
+pub const BUILTINS: &[&str] = &[
+    "cos",
+    "sin",
+    "tan",
+    "cosh",
+    "sinh",
+    "tanh",
+    "exp",
+    "acos",
+    "asin",
+    "atan",
+    "ln",
+    "log",
+    "sqrt",
+    "fact",
+    "factorial",
+    "abs",
+    "ceil",
+    "floor",
+    "round",
+    "norm",
+    "transpose_vector",
+    "transpose",
+    "det",
+    "invert",
+    "plot",
+    "termplot",
+    "rationalize",
+    "integrate",
+    "solve",
+    "poly",
+    "polydiv",
+    "gcd",
+    "roots",
+    "factor",
+    "re",
+    "im",
+    "conj",
+    "arg",
+    "hex",
+    "bin",
+    "to_base",
+    "if",
+    "diff",
+    "undef",
+    "clear",
+];
+
+/// Builtins of one number that stay unevaluated on symbolic arguments.
+pub const SYMBOLIC_BUILTINS: &[&str] = &[
+    "cos", "sin", "tan", "cosh", "sinh", "tanh", "exp", "acos", "asin", "atan", "ln", "log",
+    "sqrt", "abs", "ceil", "floor", "round",
+];
+
+pub const CONSTANTS: &[&str] = &["pi", "e", "inf", "i"];
+
+/// The variables a session starts with, one for each of `CONSTANTS`.
+pub fn constants() -> HashMap<String, Parameters> {
+    HashMap::from([
+        ("pi".to_string(), Parameters::Float(PI)),
+        ("e".to_string(), Parameters::Float(E)),
+        ("inf".to_string(), Parameters::Float(f64::INFINITY)),
+        (
+            "i".to_string(),
+            Parameters::Complex(Box::from(Complex::i())),
+        ),
+    ])
+}
+
+pub fn is_protected(name: &str) -> bool {
+    BUILTINS.contains(&name) || CONSTANTS.contains(&name)
+}
 
 pub fn exec(
     s: String,
     lst: Vec<Parameters>,
     ram: Option<&mut HashMap<String, Parameters>>,
     functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
+    // only these builtins know about radicals, the others get their float value
+    let lst = match s.as_str() {
+        "abs" | "det" | "invert" | "transpose" | "transpose_vector" | "norm" => lst,
+        name if BUILTINS.contains(&name) => lst.into_iter().map(approx_radicals).collect(),
+        _ => lst,
+    };
+    // a scalar function of an unbound name is kept as a symbolic expression
+    let symbolic = lst.iter().any(|p| match p {
+        Parameters::Symbolic(_) | Parameters::Polynomial(_) => true,
+        Parameters::Identifier(name) => ram.as_ref().is_none_or(|r| !r.contains_key(name)),
+        _ => false,
+    });
+    if symbolic && SYMBOLIC_BUILTINS.contains(&s.as_str()) {
+        let args = lst
+            .iter()
+            .map(|p| to_symbolic(p).ok_or_else(|| CalcError::wrong_type(&s, p.type_name())))
+            .collect::<Result<Vec<Symbolic>, CalcError>>()?;
+        return Ok(from_symbolic(Symbolic::Function(s, args).simplify()));
+    }
+    // outside of the real domain, and on complex numbers, the principal value is taken
+    if lst.len() == 1 && SYMBOLIC_BUILTINS.contains(&s.as_str()) && is_complex_argument(&s, &lst[0])
+    {
+        let z = Complex::from_parameter(&lst[0]).unwrap();
+        return match s.as_str() {
+            "abs" => z.abs(),
+            name => z
+                .function(name)
+                .ok_or_else(|| CalcError::wrong_type(name, lst[0].type_name())),
+        };
+    }
+    // log(x, b) = ln(x)/ln(b), complex as soon as x or b is negative
+    if s == "log" && lst.len() == 2 && lst.iter().any(|p| is_complex_argument("ln", p)) {
+        let ln = |p: &Parameters| {
+            Complex::from_parameter(p)
+                .and_then(|z| z.function("ln"))
+                .ok_or_else(|| CalcError::wrong_type("log", p.type_name()))
+        };
+        return divide(ln(&lst[0])?, ln(&lst[1])?, None);
+    }
     match s.as_str() {
         "cos" => cos(&lst, &ram),
         "sin" => sin(&lst, &ram),
@@ -42,54 +255,135 @@ pub fn exec(
         "transpose" => transpose_matrices(&lst, &ram),
         "det" => det_matrix(&lst, &ram),
         "invert" => inverse_matrix(&lst, &ram),
-        "plot" => plot_fn(&lst, &ram, functions, false),
-        "termplot" => plot_fn(&lst, &ram, functions, true),
-        s => {
-            let mut sram: HashMap<String, Parameters> = HashMap::new();
-            sram.insert("pi".to_string(), Parameters::Float(PI));
-            sram.insert("e".to_string(), Parameters::Float(E));
-            match functions.cloned() {
-                None => Parameters::Identifier("This function is unknown".to_string()),
-                Some(mut f) => {
-                    let fs = f.get_mut(s);
-                    let (vec, ast): (&mut Vec<Ast>, &mut Ast) = match fs {
-                        None => {
-                            return Parameters::Identifier("This function is unknown".to_string());
-                        }
-                        Some((a, b)) => (a, b),
-                    };
-
-                    let mut names = Vec::new();
-                    for v in vec {
-                        match v {
-                            Ast::Nil => (),
-                            Ast::Call { .. } => (),
-                            Ast::Node {
-                                value: v,
-                                left: _l,
-                                right: _r,
-                            } => match v {
-                                Parameters::Identifier(s) => names.push(s.clone()),
-                                _ => (),
-                            },
-                        }
-                    }
-                    names.iter().zip(lst).for_each(|(name, param)| {
-                        sram.insert(name.to_string(), param);
-                    });
-                    interpret(ast, &mut sram, &mut HashMap::new())
-                }
-            }
+        "plot" => plot_fn(&lst, ram, functions, false),
+        "termplot" => plot_fn(&lst, ram, functions, true),
+        "rationalize" => rationalize(&lst, &ram),
+        "integrate" => integrate(&lst, ram, functions),
+        "solve" => solve(&lst, ram, functions),
+        "poly" => poly(&lst, &ram),
+        "polydiv" => polydiv(&lst, &ram),
+        "gcd" => gcd(&lst, &ram),
+        "roots" => roots(&lst, &ram),
+        "factor" => factor(&lst, &ram),
+        "re" => re(&lst, &ram),
+        "im" => im(&lst, &ram),
+        "conj" => conj(&lst, &ram),
+        "arg" => arg(&lst, &ram),
+        "hex" => hex(&lst, &ram),
+        "bin" => bin(&lst, &ram),
+        "to_base" => to_base(&lst, &ram),
+        s => match (ram.as_ref().and_then(|r| r.get(s)), functions) {
+            // a variable holding a polynomial is called to evaluate it
+            (Some(Parameters::Polynomial(p)), _) => evaluate_polynomial(&p.clone(), &lst),
+            (_, None) => Err(CalcError::UnknownFunction(s.to_string())),
+            (_, Some(f)) => call_user_function(s, lst, ram, f),
+        },
+    }
+}
+
+/// Whether a builtin of one number takes a complex value on the argument.
+fn is_complex_argument(name: &str, p: &Parameters) -> bool {
+    match (p, to_float(p)) {
+        (Parameters::Complex(_), _) => true,
+        (_, Some(x)) => match name {
+            "sqrt" | "ln" | "log" => x < 0.0,
+            "asin" | "acos" => x.abs() > 1.0,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn approx_radicals(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Radical(r) => Parameters::Float(r.approx()),
+        Parameters::InterpreterVector(lst) => Parameters::InterpreterVector(Box::from(
+            lst.into_iter()
+                .map(approx_radicals)
+                .collect::<Vec<Parameters>>(),
+        )),
+        p => p,
+    }
+}
+
+/// Calls a user-defined function with its arguments bound on top of the
+/// globals. What the body assigns, defines or removes is put back when it
+/// returns, so nothing leaks into the globals.
+pub fn call_user_function(
+    name: &str,
+    lst: Vec<Parameters>,
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: &mut Functions,
+) -> Result<Parameters, CalcError> {
+    // the body may redefine the function it belongs to
+    let (vec, ast) = match functions.get(name) {
+        None => return Err(CalcError::UnknownFunction(name.to_string())),
+        Some(f) => f.clone(),
+    };
+
+    let mut names = Vec::new();
+    for v in vec {
+        match v {
+            Ast::Node {
+                value: Parameters::Identifier(s),
+                ..
+            } => names.push(s.clone()),
+            _ => (),
         }
     }
+    if names.len() != lst.len() {
+        return Err(CalcError::ArityMismatch {
+            function: name.to_string(),
+            expected: names.len(),
+            found: lst.len(),
+        });
+    }
+
+    let depth = CALL_DEPTH.with(|d| d.get());
+    let limit = RECURSION_LIMIT.with(|l| l.get());
+    if depth >= limit {
+        return Err(CalcError::RecursionLimit {
+            function: name.to_string(),
+            limit,
+        });
+    }
+
+    let mut globals;
+    let ram = match ram {
+        Some(ram) => ram,
+        None => {
+            globals = constants();
+            &mut globals
+        }
+    };
+    CALL_DEPTH.with(|d| d.set(depth + 1));
+    OVERWRITTEN.with(|o| o.borrow_mut().push(Overwritten::default()));
+    names.into_iter().zip(lst).for_each(|(name, param)| {
+        save_variable(ram, &name);
+        ram.insert(name, param);
+    });
+    let result = interpret(&ast, ram, functions);
+    let overwritten = OVERWRITTEN
+        .with(|o| o.borrow_mut().pop())
+        .unwrap_or_default();
+    overwritten.restore(ram, functions);
+    CALL_DEPTH.with(|d| d.set(depth));
+    result
 }
 
-pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn cos(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "cos".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    let mut degrees = false;
+    let mut degrees = DEGREES.with(|d| d.get());
 
     if p.len() > 1 {
         match p.get(1) {
@@ -98,7 +392,7 @@ pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = if degrees {
                 ((*i).clone() as f64) * (PI / 180.0)
@@ -121,65 +415,74 @@ pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    ((i as f64) * PI / 180.0).cos()
-                } else {
-                    (i as f64).cos()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    (f * PI / 180.0).cos()
-                } else {
-                    f.cos()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    (s.approx() * PI / 180.0).cos()
-                } else {
-                    s.approx().cos()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(cos(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(cos(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        ((i as f64) * PI / 180.0).cos()
+                    } else {
+                        (i as f64).cos()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        (f * PI / 180.0).cos()
+                    } else {
+                        f.cos()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        (s.approx() * PI / 180.0).cos()
+                    } else {
+                        s.approx().cos()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(cos(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(cos(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         cos(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        cos(&vec![t.clone()], ram)
+                        cos(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("cos", a.type_name())),
+    })
 }
 
-pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn sin(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "sin".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    let mut degrees = false;
+    let mut degrees = DEGREES.with(|d| d.get());
 
     if p.len() > 1 {
         match p.get(1) {
@@ -188,7 +491,7 @@ pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = if degrees {
                 ((*i).clone() as f64) * (PI / 180.0)
@@ -211,65 +514,74 @@ pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    ((i as f64) * PI / 180.0).sin()
-                } else {
-                    (i as f64).sin()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    (f * PI / 180.0).sin()
-                } else {
-                    f.sin()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    (s.approx() * PI / 180.0).sin()
-                } else {
-                    s.approx().sin()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(sin(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(sin(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        ((i as f64) * PI / 180.0).sin()
+                    } else {
+                        (i as f64).sin()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        (f * PI / 180.0).sin()
+                    } else {
+                        f.sin()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        (s.approx() * PI / 180.0).sin()
+                    } else {
+                        s.approx().sin()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(sin(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(sin(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         sin(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        sin(&vec![t.clone()], ram)
+                        sin(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("sin", a.type_name())),
+    })
 }
 
-pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn tan(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "tan".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    let mut degrees = false;
+    let mut degrees = DEGREES.with(|d| d.get());
 
     if p.len() > 1 {
         match p.get(1) {
@@ -278,7 +590,7 @@ pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = if degrees {
                 ((*i).clone() as f64) * (PI / 180.0)
@@ -302,62 +614,71 @@ pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    ((i as f64) * PI / 180.0).tan()
-                } else {
-                    (i as f64).tan()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    (f * PI / 180.0).tan()
-                } else {
-                    f.tan()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    (s.approx() * PI / 180.0).tan()
-                } else {
-                    s.approx().tan()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(tan(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(tan(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        ((i as f64) * PI / 180.0).tan()
+                    } else {
+                        (i as f64).tan()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        (f * PI / 180.0).tan()
+                    } else {
+                        f.tan()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        (s.approx() * PI / 180.0).tan()
+                    } else {
+                        s.approx().tan()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(tan(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(tan(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         tan(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        tan(&vec![t.clone()], ram)
+                        tan(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("tan", a.type_name())),
+    })
 }
 
-pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn cosh(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "cosh".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut degrees = false;
@@ -369,7 +690,7 @@ pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = if degrees {
                 ((*i).clone() as f64) * (PI / 180.0)
@@ -393,62 +714,71 @@ pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    ((i as f64) * PI / 180.0).cosh()
-                } else {
-                    (i as f64).cosh()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    (f * PI / 180.0).cosh()
-                } else {
-                    f.cosh()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    (s.approx() * PI / 180.0).cosh()
-                } else {
-                    s.approx().cosh()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(cosh(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(cosh(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        ((i as f64) * PI / 180.0).cosh()
+                    } else {
+                        (i as f64).cosh()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        (f * PI / 180.0).cosh()
+                    } else {
+                        f.cosh()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        (s.approx() * PI / 180.0).cosh()
+                    } else {
+                        s.approx().cosh()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(cosh(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(cosh(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         cosh(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        cosh(&vec![t.clone()], ram)
+                        cosh(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("cosh", a.type_name())),
+    })
 }
 
-pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn sinh(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "sinh".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut degrees = false;
@@ -460,7 +790,7 @@ pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = if degrees {
                 ((*i).clone() as f64) * (PI / 180.0)
@@ -484,62 +814,71 @@ pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    ((i as f64) * PI / 180.0).sinh()
-                } else {
-                    (i as f64).sinh()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    (f * PI / 180.0).sinh()
-                } else {
-                    f.sinh()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    (s.approx() * PI / 180.0).sinh()
-                } else {
-                    s.approx().sinh()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(sinh(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(sinh(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        ((i as f64) * PI / 180.0).sinh()
+                    } else {
+                        (i as f64).sinh()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        (f * PI / 180.0).sinh()
+                    } else {
+                        f.sinh()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        (s.approx() * PI / 180.0).sinh()
+                    } else {
+                        s.approx().sinh()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(sinh(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(sinh(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         sinh(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        sinh(&vec![t.clone()], ram)
+                        sinh(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("sinh", a.type_name())),
+    })
 }
 
-pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn tanh(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "tanh".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut degrees = false;
@@ -551,7 +890,7 @@ pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = if degrees {
                 ((*i).clone() as f64) * (PI / 180.0)
@@ -575,65 +914,74 @@ pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    ((i as f64) * PI / 180.0).tanh()
-                } else {
-                    (i as f64).tanh()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    (f * PI / 180.0).tanh()
-                } else {
-                    f.tanh()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    (s.approx() * PI / 180.0).tanh()
-                } else {
-                    s.approx().tanh()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(tanh(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(tanh(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        ((i as f64) * PI / 180.0).tanh()
+                    } else {
+                        (i as f64).tanh()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        (f * PI / 180.0).tanh()
+                    } else {
+                        f.tanh()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        (s.approx() * PI / 180.0).tanh()
+                    } else {
+                        s.approx().tanh()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(tanh(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(tanh(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         tanh(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        tanh(&vec![t.clone()], ram)
+                        tanh(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("tanh", a.type_name())),
+    })
 }
 
-pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn acos(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "acos".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    let mut degrees = false;
+    let mut degrees = DEGREES.with(|d| d.get());
 
     if p.len() > 1 {
         match p.get(1) {
@@ -642,7 +990,7 @@ pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             Parameters::Float(if degrees {
@@ -664,65 +1012,74 @@ pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    (i as f64).acos() * 180.0 / PI
-                } else {
-                    (i as f64).acos()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    f.acos() * 180.0 / PI
-                } else {
-                    f.acos()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    s.approx().acos() * 180.0 / PI
-                } else {
-                    s.approx().acos()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(acos(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(acos(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        (i as f64).acos() * 180.0 / PI
+                    } else {
+                        (i as f64).acos()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        f.acos() * 180.0 / PI
+                    } else {
+                        f.acos()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        s.approx().acos() * 180.0 / PI
+                    } else {
+                        s.approx().acos()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(acos(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(acos(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         acos(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        acos(&vec![t.clone()], ram)
+                        acos(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("acos", a.type_name())),
+    })
 }
 
-pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn asin(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "asin".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    let mut degrees = false;
+    let mut degrees = DEGREES.with(|d| d.get());
 
     if p.len() > 1 {
         match p.get(1) {
@@ -731,7 +1088,7 @@ pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             Parameters::Float(if degrees {
@@ -754,65 +1111,74 @@ pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    (i as f64).asin() * 180.0 / PI
-                } else {
-                    (i as f64).asin()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    f.asin() * 180.0 / PI
-                } else {
-                    f.asin()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    s.approx().asin() * 180.0 / PI
-                } else {
-                    s.approx().asin()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(asin(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(asin(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        (i as f64).asin() * 180.0 / PI
+                    } else {
+                        (i as f64).asin()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        f.asin() * 180.0 / PI
+                    } else {
+                        f.asin()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        s.approx().asin() * 180.0 / PI
+                    } else {
+                        s.approx().asin()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(asin(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(asin(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         asin(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        asin(&vec![t.clone()], ram)
+                        asin(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("asin", a.type_name())),
+    })
 }
 
-pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn atan(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "atan".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    let mut degrees = false;
+    let mut degrees = DEGREES.with(|d| d.get());
 
     if p.len() > 1 {
         match p.get(1) {
@@ -821,7 +1187,7 @@ pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             Parameters::Float(if degrees {
@@ -844,62 +1210,71 @@ pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if degrees {
-                    (i as f64).atan() * 180.0 / PI
-                } else {
-                    (i as f64).atan()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if degrees {
-                    f.atan() * 180.0 / PI
-                } else {
-                    f.atan()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
-                    s.approx().atan() * 180.0 / PI
-                } else {
-                    s.approx().atan()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if degrees {
-                                res.push(atan(&vec![s.clone(), Parameters::Bool(false)], ram))
-                            } else {
-                                res.push(atan(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if degrees {
+                        (i as f64).atan() * 180.0 / PI
+                    } else {
+                        (i as f64).atan()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if degrees {
+                        f.atan() * 180.0 / PI
+                    } else {
+                        f.atan()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if degrees {
+                        s.approx().atan() * 180.0 / PI
+                    } else {
+                        s.approx().atan()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if degrees {
+                                    res.push(atan(&vec![s.clone(), Parameters::Bool(false)], ram)?)
+                                } else {
+                                    res.push(atan(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
                 Some(t) => {
                     if degrees {
                         atan(
                             &vec![t.clone(), Parameters::Identifier("false".to_string())],
                             ram,
-                        )
+                        )?
                     } else {
-                        atan(&vec![t.clone()], ram)
+                        atan(&vec![t.clone()], ram)?
                     }
                 }
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("atan", a.type_name())),
+    })
 }
 
-pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn exp(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "exp".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut plus = false;
@@ -919,7 +1294,7 @@ pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             if plus {
@@ -945,51 +1320,60 @@ pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if plus {
-                    ln.powf(i as f64)
-                } else {
-                    (i as f64).exp()
-                })),
-                Parameters::Float(f) => {
-                    res.push(Parameters::Float(if plus { ln.powf(f) } else { f.exp() }))
-                }
-                Parameters::Rational(s) => res.push(Parameters::Float(if plus {
-                    ln.powf(s.approx())
-                } else {
-                    s.approx().exp()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if plus {
-                                res.push(exp(&vec![s.clone(), Parameters::Float(ln)], ram))
-                            } else {
-                                res.push(exp(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if plus {
+                        ln.powf(i as f64)
+                    } else {
+                        (i as f64).exp()
+                    })),
+                    Parameters::Float(f) => {
+                        res.push(Parameters::Float(if plus { ln.powf(f) } else { f.exp() }))
+                    }
+                    Parameters::Rational(s) => res.push(Parameters::Float(if plus {
+                        ln.powf(s.approx())
+                    } else {
+                        s.approx().exp()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if plus {
+                                    res.push(exp(&vec![s.clone(), Parameters::Float(ln)], ram)?)
+                                } else {
+                                    res.push(exp(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => exp(&vec![t.clone(), Parameters::Float(ln)], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => exp(&vec![t.clone(), Parameters::Float(ln)], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("exp", a.type_name())),
+    })
 }
 
-pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn ln(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "ln".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut plus = false;
@@ -1009,7 +1393,7 @@ pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             if plus {
@@ -1036,51 +1420,60 @@ pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if plus {
-                    (i as f64).log(sln)
-                } else {
-                    (i as f64).ln()
-                })),
-                Parameters::Float(f) => {
-                    res.push(Parameters::Float(if plus { f.log(sln) } else { f.ln() }))
-                }
-                Parameters::Rational(s) => res.push(Parameters::Float(if plus {
-                    s.approx().log(sln)
-                } else {
-                    s.approx().ln()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if plus {
-                                res.push(ln(&vec![s.clone(), Parameters::Float(sln)], ram))
-                            } else {
-                                res.push(ln(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    Parameters::Int(i) => res.push(Parameters::Float(if plus {
+                        (i as f64).log(sln)
+                    } else {
+                        (i as f64).ln()
+                    })),
+                    Parameters::Float(f) => {
+                        res.push(Parameters::Float(if plus { f.log(sln) } else { f.ln() }))
+                    }
+                    Parameters::Rational(s) => res.push(Parameters::Float(if plus {
+                        s.approx().log(sln)
+                    } else {
+                        s.approx().ln()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if plus {
+                                    res.push(ln(&vec![s.clone(), Parameters::Float(sln)], ram)?)
+                                } else {
+                                    res.push(ln(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => ln(&vec![t.clone(), Parameters::Float(sln)], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => ln(&vec![t.clone(), Parameters::Float(sln)], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("ln", a.type_name())),
+    })
 }
 
-pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn sqrt(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "sqrt".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut plus = false;
@@ -1100,7 +1493,11 @@ pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
         }
     }
 
-    match p.get(0).unwrap() {
+    let exact = !plus || sln == 2.0;
+
+    Ok(match p.get(0).unwrap() {
+        x @ (Parameters::Int(_) | Parameters::Rational(_)) if exact => exact_sqrt(x),
+        Parameters::Quantity(q) if exact => q.pow(&Parameters::Rational(Rationals::new(2, 1)))?,
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             if plus {
@@ -1126,143 +1523,212 @@ pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
 
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
-            vec.clone().into_iter().for_each(|x| match x {
-                Parameters::Int(i) => res.push(Parameters::Float(if plus {
-                    (i as f64).powf(1.0 / sln)
-                } else {
-                    (i as f64).sqrt()
-                })),
-                Parameters::Float(f) => res.push(Parameters::Float(if plus {
-                    f.powf(1.0 / sln)
-                } else {
-                    f.sqrt()
-                })),
-                Parameters::Rational(s) => res.push(Parameters::Float(if plus {
-                    s.clone().approx().powf(1.0 / sln)
-                } else {
-                    s.clone().approx().sqrt()
-                })),
-                Parameters::Identifier(s) => match ram {
-                    None => (),
-                    Some(ref t) => match t.get(s.as_str()) {
-                        None => (),
-                        Some(s) => {
-                            if plus {
-                                res.push(sqrt(&vec![s.clone(), Parameters::Float(sln)], ram))
-                            } else {
-                                res.push(sqrt(&vec![s.clone()], ram))
+            for x in vec.clone().into_iter() {
+                match x {
+                    x @ (Parameters::Int(_) | Parameters::Rational(_)) if exact => {
+                        res.push(exact_sqrt(&x))
+                    }
+                    Parameters::Int(i) => res.push(Parameters::Float(if plus {
+                        (i as f64).powf(1.0 / sln)
+                    } else {
+                        (i as f64).sqrt()
+                    })),
+                    Parameters::Float(f) => res.push(Parameters::Float(if plus {
+                        f.powf(1.0 / sln)
+                    } else {
+                        f.sqrt()
+                    })),
+                    Parameters::Rational(s) => res.push(Parameters::Float(if plus {
+                        s.clone().approx().powf(1.0 / sln)
+                    } else {
+                        s.clone().approx().sqrt()
+                    })),
+                    Parameters::Identifier(s) => match ram {
+                        None => return Err(CalcError::UndefinedVariable(s.clone())),
+                        Some(ref t) => match t.get(s.as_str()) {
+                            None => return Err(CalcError::UndefinedVariable(s.clone())),
+                            Some(s) => {
+                                if plus {
+                                    res.push(sqrt(&vec![s.clone(), Parameters::Float(sln)], ram)?)
+                                } else {
+                                    res.push(sqrt(&vec![s.clone()], ram)?)
+                                }
                             }
-                        }
+                        },
                     },
-                },
-                _ => (),
-            });
+                    _ => (),
+                }
+            }
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => sqrt(&vec![t.clone(), Parameters::Float(sln)], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => sqrt(&vec![t.clone(), Parameters::Float(sln)], ram)?,
             },
         },
-        _ => Parameters::Null,
+        a => return Err(CalcError::wrong_type("sqrt", a.type_name())),
+    })
+}
+
+/// The square root of a non negative integer or rational as a radical, falling
+/// back to a float when it cannot be computed exactly.
+fn exact_sqrt(x: &Parameters) -> Parameters {
+    let r = match x {
+        Parameters::Int(i) => Rationals::new(1, *i),
+        Parameters::Rational(s) => s.clone(),
+        _ => return Parameters::Float(f64::NAN),
+    };
+    match Radicals::sqrt(&r) {
+        None => Parameters::Float(r.approx().sqrt()),
+        Some(root) => match root.to_rational() {
+            Some(q) if q.is_integer() => int_or_big(q.reduce().over),
+            _ => from_radical(root),
+        },
     }
 }
 
-pub fn fact(n: i64) -> i64 {
-    fn aux(n: i64, acc: i64) -> i64 {
-        match n {
-            0 => acc,
-            i => aux(i - 1, i * acc),
+pub fn fact(n: i64) -> Result<Parameters, CalcError> {
+    let mut acc = BigInt::from(1);
+    for i in 2..=n {
+        acc *= i;
+        if acc.bits() > MAX_BITS {
+            return Err(CalcError::Overflow("fact".to_string()));
         }
     }
-    aux(n, 1)
+    Ok(int_or_big(acc))
 }
 
 pub fn factorial(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
-    }
-
-    match p.get(0).unwrap() {
-        Parameters::Int(i) => Parameters::Int(fact(*i)),
-        Parameters::Float(f) => Parameters::Int(fact(*f as i64)),
+        return Err(CalcError::ArityMismatch {
+            function: "fact".to_string(),
+            expected: 1,
+            found: 0,
+        });
+    }
+
+    Ok(match p.get(0).unwrap() {
+        Parameters::Int(i) => fact(*i)?,
+        Parameters::Float(f) => fact(*f as i64)?,
+        Parameters::Rational(r) if r.is_integer() => {
+            match integer_argument("fact", &p[0])?.to_i64() {
+                Some(n) => fact(n)?,
+                // past an i64 the factorial is far past MAX_BITS
+                None => return Err(CalcError::Overflow("fact".to_string())),
+            }
+        }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => factorial(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => factorial(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("fact", a.type_name())),
+    })
 }
 
-pub fn abs(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn abs(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "abs".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Int(i.abs()),
         Parameters::Float(f) => Parameters::Float(f.abs()),
         Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
+        Parameters::Radical(r) => Parameters::Radical(r.clone()).abs(None),
+        Parameters::Quantity(q) => {
+            let value = abs(&vec![q.value.clone()], ram)?;
+            Parameters::Quantity(Box::from(Quantity::new(value, q.units.clone())))
+        }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => abs(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => abs(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("abs", a.type_name())),
+    })
 }
 
-pub fn ceil(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn ceil(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "ceil".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Float((*i as f64).ceil()),
         Parameters::Float(f) => Parameters::Float(f.ceil()),
+        Parameters::Rational(r) if r.is_integer() => int_or_big(r.clone().reduce().over),
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => ceil(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => ceil(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("ceil", a.type_name())),
+    })
 }
 
-pub fn floor(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn floor(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "floor".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Float((*i as f64).floor()),
         Parameters::Float(f) => Parameters::Float(f.floor()),
+        Parameters::Rational(r) if r.is_integer() => int_or_big(r.clone().reduce().over),
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => floor(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => floor(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("floor", a.type_name())),
+    })
 }
 
-pub fn round(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+pub fn round(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "round".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
     let mut plus = false;
@@ -1282,7 +1748,7 @@ pub fn round(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>
         }
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             if plus {
@@ -1298,23 +1764,486 @@ pub fn round(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>
                 Parameters::Float((*f).round())
             }
         }
+        Parameters::Rational(s) if s.is_integer() && sln >= 0.0 => {
+            int_or_big(s.clone().reduce().over)
+        }
         Parameters::Rational(s) => {
             if plus {
                 Parameters::Float(
-                    (s.clone().approx() * 10.0_f64.powf(sln).round()) / (10.0_f64.powf(sln)),
+                    (s.clone().approx() * 10.0_f64.powf(sln)).round() / (10.0_f64.powf(sln)),
                 )
             } else {
                 Parameters::Float(s.clone().approx().round())
             }
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => round(&vec![t.clone(), Parameters::Float(sln)], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => round(&vec![t.clone(), Parameters::Float(sln)], ram)?,
             },
         },
-        _ => Parameters::Null,
+        a => return Err(CalcError::wrong_type("round", a.type_name())),
+    })
+}
+
+pub fn rationalize(
+    p: &[Parameters],
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.is_empty() {
+        return Err(CalcError::ArityMismatch {
+            function: "rationalize".to_string(),
+            expected: 1,
+            found: 0,
+        });
+    }
+
+    let max_denominator = match p.get(1) {
+        None => None,
+        Some(a) => match integer_argument("rationalize", a)? {
+            n if n.is_positive() => Some(n),
+            _ => {
+                return Err(CalcError::wrong_type(
+                    "rationalize",
+                    "a non positive maxden",
+                ))
+            }
+        },
+    };
+
+    let value = match p.first().unwrap() {
+        Parameters::Int(i) => Rationals::new(1, *i),
+        Parameters::Rational(s) => s.clone(),
+        Parameters::Float(f) => match Rationals::from_f64(*f) {
+            Some(r) => r,
+            None => return Ok(Parameters::Float(*f)),
+        },
+        Parameters::Identifier(s) => match ram {
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
+            Some(ref t) => match t.get(s.as_str()) {
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => {
+                    let mut lst = p.to_vec();
+                    lst[0] = t.clone();
+                    return rationalize(&lst, ram);
+                }
+            },
+        },
+        a => return Err(CalcError::wrong_type("rationalize", a.type_name())),
+    };
+
+    Ok(Parameters::Rational(match max_denominator {
+        Some(max) => value.best_approximation(&max, 0.0),
+        None => value.simplify(),
+    }))
+}
+
+type RealFunction<'a> = Box<dyn Fn(f64) -> Result<f64, CalcError> + 'a>;
+
+/// The variables and functions user functions are called with by the
+/// numerical algorithms.
+type Scope<'a> = RefCell<(
+    Option<&'a mut HashMap<String, Parameters>>,
+    &'a mut Functions,
+)>;
+
+/// A real function of one variable for the numerical algorithms: the name of
+/// a builtin or of a user function, or a compound expression of one unbound
+/// variable. Any other name is an unknown function, not the identity.
+fn real_function<'a, 'b: 'a>(
+    name: &'a str,
+    f: &'a Parameters,
+    scope: &'a Scope<'b>,
+) -> Result<RealFunction<'a>, CalcError> {
+    let value =
+        move |p: Parameters| to_float(&p).ok_or_else(|| CalcError::wrong_type(name, p.type_name()));
+    Ok(match f {
+        Parameters::Identifier(s) if scope.borrow().1.contains_key(s) => Box::new(move |x| {
+            let (ram, functions) = &mut *scope.borrow_mut();
+            let args = vec![Parameters::Float(x)];
+            value(call_user_function(s, args, ram.as_deref_mut(), functions)?)
+        }),
+        Parameters::Identifier(s) if SYMBOLIC_BUILTINS.contains(&s.as_str()) => {
+            Box::new(move |x| value(exec(s.clone(), vec![Parameters::Float(x)], None, None)?))
+        }
+        Parameters::Identifier(s) => return Err(CalcError::UnknownFunction(s.clone())),
+        f => {
+            let expression =
+                to_symbolic(f).ok_or_else(|| CalcError::wrong_type(name, f.type_name()))?;
+            let variable = match expression.symbols().as_slice() {
+                [] => String::new(),
+                [x] => x.clone(),
+                _ => {
+                    return Err(CalcError::wrong_type(
+                        name,
+                        "an expression of several variables",
+                    ))
+                }
+            };
+            Box::new(move |x| {
+                expression
+                    .evaluate(&variable, x)
+                    .ok_or_else(|| CalcError::wrong_type(name, f.type_name()))
+            })
+        }
+    })
+}
+
+pub fn integrate(
+    p: &[Parameters],
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 3 && p.len() != 4 {
+        return Err(CalcError::ArityMismatch {
+            function: "integrate".to_string(),
+            expected: 3,
+            found: p.len(),
+        });
+    }
+
+    let number = |p: &Parameters| {
+        to_float(p).ok_or_else(|| CalcError::wrong_type("integrate", p.type_name()))
+    };
+    let (a, b) = (number(&p[1])?, number(&p[2])?);
+    let tolerance = match p.get(3) {
+        None => INTEGRATION_TOLERANCE,
+        Some(t) if number(t)? > 0.0 => number(t)?,
+        Some(_) => {
+            return Err(CalcError::wrong_type(
+                "integrate",
+                "a non positive tolerance",
+            ))
+        }
+    };
+
+    let mut no_functions = Functions::new();
+    let scope = RefCell::new((ram, functions.unwrap_or(&mut no_functions)));
+    let f = real_function("integrate", &p[0], &scope)?;
+    let (value, _) = integration_utils::integrate(&mut |x| f(x), a, b, tolerance)?;
+    Ok(Parameters::Float(value))
+}
+
+pub fn solve(
+    p: &[Parameters],
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 2 && p.len() != 3 {
+        return Err(CalcError::ArityMismatch {
+            function: "solve".to_string(),
+            expected: 2,
+            found: p.len(),
+        });
+    }
+
+    let number =
+        |p: &Parameters| to_float(p).ok_or_else(|| CalcError::wrong_type("solve", p.type_name()));
+    // Newton starts from the given point, or from the middle of the bracket
+    let (x0, range) = match p.get(2) {
+        None => (number(&p[1])?, None),
+        Some(b) => {
+            let (a, b) = (number(&p[1])?, number(b)?);
+            ((a + b) / 2.0, Some((a, b)))
+        }
+    };
+
+    let mut no_functions = Functions::new();
+    let scope = RefCell::new((ram, functions.unwrap_or(&mut no_functions)));
+    let f = real_function("solve", &p[0], &scope)?;
+    Ok(Parameters::Float(solve_utils::solve(&*f, x0, range)?))
+}
+
+/// The variable a polynomial argument is in, if it has a single one.
+fn polynomial_variable(p: &Parameters) -> Option<String> {
+    match p {
+        Parameters::Polynomial(p) => Some(p.variable.clone()),
+        Parameters::Identifier(s) => Some(s.clone()),
+        Parameters::Symbolic(s) => match s.symbols().as_slice() {
+            [x] => Some(x.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The arguments as polynomials in the same variable, the one of the first
+/// argument that is not a constant, or `x`.
+fn polynomial_arguments(name: &str, p: &[Parameters]) -> Result<Vec<Polynomial>, CalcError> {
+    let variable = p
+        .iter()
+        .find_map(polynomial_variable)
+        .unwrap_or_else(|| "x".to_string());
+    p.iter()
+        .map(|a| {
+            to_polynomial(a, &variable).ok_or_else(|| CalcError::wrong_type(name, a.type_name()))
+        })
+        .collect()
+}
+
+/// Evaluates a polynomial held by a variable by Horner's rule, exactly for
+/// exact arguments.
+fn evaluate_polynomial(polynomial: &Polynomial, p: &[Parameters]) -> Result<Parameters, CalcError> {
+    let x = match p {
+        [x] => to_symbolic(x).map_or(x.clone(), from_symbolic),
+        _ => {
+            return Err(CalcError::ArityMismatch {
+                function: polynomial.to_string(),
+                expected: 1,
+                found: p.len(),
+            })
+        }
+    };
+    polynomial
+        .coefficients
+        .iter()
+        .rev()
+        .try_fold(Parameters::Int(0), |acc, c| {
+            other_add(
+                mult(acc, x.clone(), None)?,
+                rational_parameter(c.clone()),
+                None,
+            )
+        })
+}
+
+pub fn poly(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 1 && p.len() != 2 {
+        return Err(CalcError::ArityMismatch {
+            function: "poly".to_string(),
+            expected: 1,
+            found: p.len(),
+        });
+    }
+
+    let variable = match p.get(1) {
+        None => polynomial_variable(&p[0]).unwrap_or_else(|| "x".to_string()),
+        Some(Parameters::Identifier(s)) => s.clone(),
+        Some(a) => return Err(CalcError::wrong_type("poly", a.type_name())),
+    };
+    // coefficients are given from the highest degree down
+    let polynomial = match &p[0] {
+        Parameters::InterpreterVector(v) => {
+            let coefficients =
+                v.iter()
+                    .rev()
+                    .map(|c| match c {
+                        Parameters::Int(i) => Ok(Rationals::new(1, *i)),
+                        Parameters::Rational(r) => Ok(r.clone()),
+                        Parameters::Float(f) => Rationals::from_f64(*f)
+                            .ok_or(CalcError::wrong_type("poly", c.type_name())),
+                        c => Err(CalcError::wrong_type("poly", c.type_name())),
+                    })
+                    .collect::<Result<Vec<Rationals>, CalcError>>()?;
+            Polynomial::new(coefficients, &variable)
+        }
+        a => to_polynomial(a, &variable)
+            .ok_or_else(|| CalcError::wrong_type("poly", a.type_name()))?,
+    };
+    Ok(from_polynomial(polynomial))
+}
+
+pub fn polydiv(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 2 {
+        return Err(CalcError::ArityMismatch {
+            function: "polydiv".to_string(),
+            expected: 2,
+            found: p.len(),
+        });
+    }
+
+    let polynomials = polynomial_arguments("polydiv", p)?;
+    match polynomials[0].div_rem(&polynomials[1]) {
+        Some((q, r)) => Ok(Parameters::InterpreterVector(Box::from(vec![
+            from_polynomial(q),
+            from_polynomial(r),
+        ]))),
+        None => Err(CalcError::DivisionByZero),
+    }
+}
+
+pub fn gcd(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 2 {
+        return Err(CalcError::ArityMismatch {
+            function: "gcd".to_string(),
+            expected: 2,
+            found: p.len(),
+        });
+    }
+
+    match (&p[0], &p[1]) {
+        (Parameters::Int(a), Parameters::Int(b)) => {
+            Ok(int_or_big(BigInt::from(*a).gcd(&BigInt::from(*b))))
+        }
+        _ => {
+            let polynomials = polynomial_arguments("gcd", p)?;
+            Ok(from_polynomial(polynomials[0].gcd(&polynomials[1])))
+        }
+    }
+}
+
+pub fn roots(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 1 {
+        return Err(CalcError::ArityMismatch {
+            function: "roots".to_string(),
+            expected: 1,
+            found: p.len(),
+        });
+    }
+
+    let polynomial = polynomial_arguments("roots", p)?.remove(0);
+    if polynomial.is_zero() {
+        return Err(CalcError::wrong_type("roots", "the zero polynomial"));
+    }
+    Ok(Parameters::InterpreterVector(Box::from(polynomial.roots())))
+}
+
+/// The polynomial as a product of its factors, kept unexpanded.
+pub fn factor(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 1 {
+        return Err(CalcError::ArityMismatch {
+            function: "factor".to_string(),
+            expected: 1,
+            found: p.len(),
+        });
+    }
+
+    let polynomial = polynomial_arguments("factor", p)?.remove(0);
+    let (constant, factors) = polynomial.factor();
+    let mut product: Vec<Symbolic> = factors
+        .into_iter()
+        .map(|(f, multiplicity)| match multiplicity {
+            1 => f.to_symbolic(),
+            m => Symbolic::Power(
+                Box::from(f.to_symbolic()),
+                Box::from(Symbolic::Number(Parameters::Int(m as i64))),
+            ),
+        })
+        .collect();
+    let constant = rational_parameter(constant);
+    if product.is_empty() || constant != Parameters::Int(1) {
+        product.insert(0, Symbolic::Number(constant));
+    }
+    Ok(match product.len() {
+        1 => from_symbolic(product.remove(0)),
+        _ => Parameters::Symbolic(Box::from(Symbolic::Product(product))),
+    })
+}
+
+/// The single argument of `re`, `im`, `conj` and `arg` as a complex number.
+fn complex_argument(name: &str, p: &[Parameters]) -> Result<Complex, CalcError> {
+    if p.len() != 1 {
+        return Err(CalcError::ArityMismatch {
+            function: name.to_string(),
+            expected: 1,
+            found: p.len(),
+        });
+    }
+    Complex::from_parameter(&p[0]).ok_or_else(|| CalcError::wrong_type(name, p[0].type_name()))
+}
+
+pub fn re(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    Ok(complex_argument("re", p)?.re)
+}
+
+pub fn im(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    Ok(complex_argument("im", p)?.im)
+}
+
+pub fn conj(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    complex_argument("conj", p)?.conj()
+}
+
+pub fn arg(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    Ok(Parameters::Float(complex_argument("arg", p)?.arg()))
+}
+
+/// The value of an integer argument, big integers included.
+fn integer_argument(name: &str, p: &Parameters) -> Result<BigInt, CalcError> {
+    match p {
+        Parameters::Int(i) => Ok(BigInt::from(*i)),
+        Parameters::Rational(r) if r.is_integer() => Ok(r.clone().reduce().over),
+        p => Err(CalcError::wrong_type(name, p.type_name())),
+    }
+}
+
+fn prefixed(name: &str, base: u32, p: &[Parameters]) -> Result<Parameters, CalcError> {
+    if p.len() != 1 {
+        return Err(CalcError::ArityMismatch {
+            function: name.to_string(),
+            expected: 1,
+            found: p.len(),
+        });
+    }
+    let n = integer_argument(name, &p[0])?;
+    Ok(Parameters::Str(with_prefix(&n, base)))
+}
+
+pub fn hex(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    prefixed("hex", 16, p)
+}
+
+pub fn bin(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    prefixed("bin", 2, p)
+}
+
+/// The digits of an integer in a base from 2 to 36, letters standing for the
+/// digits past 9.
+pub fn to_base(
+    p: &[Parameters],
+    _ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Parameters, CalcError> {
+    if p.len() != 2 {
+        return Err(CalcError::ArityMismatch {
+            function: "to_base".to_string(),
+            expected: 2,
+            found: p.len(),
+        });
+    }
+    let n = integer_argument("to_base", &p[0])?;
+    match &p[1] {
+        Parameters::Int(base) if (2..=36).contains(base) => {
+            Ok(Parameters::Str(n.to_str_radix(*base as u32)))
+        }
+        Parameters::Int(_) => Err(CalcError::wrong_type(
+            "to_base",
+            "a base outside of 2 to 36",
+        )),
+        b => Err(CalcError::wrong_type("to_base", b.type_name())),
     }
 }
 
@@ -1322,21 +2251,25 @@ pub fn norm(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
     function: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "norm".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Int((*i).abs()),
         Parameters::Float(f) => Parameters::Float((*f).abs()),
         Parameters::InterpreterVector(lst) => {
             let mut sum = Parameters::Int(0);
 
-            (*lst)
-                .iter()
-                .map(|x| mult(x.clone(), x.clone(), ram.as_deref()))
-                .for_each(|x| sum = other_add(sum.clone(), x.clone(), ram.as_deref()));
+            for x in (*lst).iter() {
+                let squared = mult(x.clone(), x.clone(), ram.as_deref())?;
+                sum = other_add(sum.clone(), squared, ram.as_deref())?;
+            }
 
             match sum {
                 Parameters::Int(i) => Parameters::Float((i as f64).sqrt()),
@@ -1346,25 +2279,29 @@ pub fn norm(
             }
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => norm(&vec![t.clone()], ram, function),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => norm(&vec![t.clone()], ram, function)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("norm", a.type_name())),
+    })
 }
 
 pub fn transpose_vectors(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "transpose_vector".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Int((*i).abs()),
         Parameters::Float(f) => Parameters::Float((*f).abs()),
         Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
@@ -1382,25 +2319,29 @@ pub fn transpose_vectors(
             Parameters::InterpreterVector(Box::from(result))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => transpose_vectors(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => transpose_vectors(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("transpose_vector", a.type_name())),
+    })
 }
 
 pub fn transpose_matrices(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "transpose".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Int((*i).abs()),
         Parameters::Float(f) => Parameters::Float((*f).abs()),
         Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
@@ -1430,25 +2371,29 @@ pub fn transpose_matrices(
         }
 
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => transpose_matrices(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => transpose_matrices(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("transpose", a.type_name())),
+    })
 }
 
 pub fn det_matrix(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "det".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Int((*i).abs()),
         Parameters::Float(f) => Parameters::Float((*f).abs()),
         Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
@@ -1465,7 +2410,7 @@ pub fn det_matrix(
             });
 
             if !is_matrix {
-                return Parameters::Float(0.0);
+                return Ok(Parameters::Float(0.0));
             }
 
             let mut p = Vec::new();
@@ -1473,37 +2418,38 @@ pub fn det_matrix(
                 p.push(Parameters::Int(0));
             }
             let n = res.len();
-            let r = lup_decompose(&mut res, &mut p, n, ram.as_deref());
+            let r = lup_decompose(&mut res, &mut p, n, ram.as_deref())?;
 
             match r {
                 0 => Parameters::Int(0),
-                _ => {
-                    let det = lup_determinant(&mut res, &mut p, n, ram.as_deref());
-                    det
-                }
+                _ => lup_determinant(&mut res, &mut p, n, ram.as_deref())?,
             }
         }
 
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => det_matrix(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => det_matrix(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("det", a.type_name())),
+    })
 }
 
 pub fn inverse_matrix(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     if p.len() < 1 {
-        return Parameters::Null;
+        return Err(CalcError::ArityMismatch {
+            function: "invert".to_string(),
+            expected: 1,
+            found: 0,
+        });
     }
 
-    match p.get(0).unwrap() {
+    Ok(match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Int((*i).abs()),
         Parameters::Float(f) => Parameters::Float((*f).abs()),
         Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
@@ -1520,7 +2466,7 @@ pub fn inverse_matrix(
             });
 
             if !is_matrix {
-                return Parameters::InterpreterVector(Box::from(res1));
+                return Ok(Parameters::InterpreterVector(Box::from(res1)));
             }
 
             let mut p = Vec::new();
@@ -1528,7 +2474,7 @@ pub fn inverse_matrix(
                 p.push(Parameters::Int(0));
             }
             let n = res.len();
-            let r = lup_decompose(&mut res, &mut p, n, ram.as_deref());
+            let r = lup_decompose(&mut res, &mut p, n, ram.as_deref())?;
 
             match r {
                 0 => Parameters::Null,
@@ -1541,26 +2487,26 @@ pub fn inverse_matrix(
                         }
                         vec_ia.push(vec);
                     }
-                    let det = lup_determinant(&mut res, &mut p, n, ram.as_deref());
+                    let det = lup_determinant(&mut res, &mut p, n, ram.as_deref())?;
                     match det {
                         Parameters::Int(0) => {
-                            return Parameters::Str(
+                            return Ok(Parameters::Str(
                                 "Determinant is zero, matrix is not invertible".to_string(),
-                            )
+                            ))
                         }
                         Parameters::Float(s) if s.abs() < 1e-10 => {
-                            return Parameters::Str(
+                            return Ok(Parameters::Str(
                                 "Determinant is zero, matrix is not invertible".to_string(),
-                            )
+                            ))
                         }
                         Parameters::Rational(s) if s.clone().is_null() => {
-                            return Parameters::Str(
+                            return Ok(Parameters::Str(
                                 "Determinant is zero, matrix is not invertible".to_string(),
-                            )
+                            ))
                         }
                         _ => (),
                     }
-                    lup_invert(&mut res, &mut p, n, &mut vec_ia, ram.as_deref());
+                    lup_invert(&mut res, &mut p, n, &mut vec_ia, ram.as_deref())?;
                     let mut resd = Vec::new();
                     for i in 0..n {
                         resd.push(Parameters::InterpreterVector(Box::new(vec_ia[i].clone())));
@@ -1571,22 +2517,22 @@ pub fn inverse_matrix(
         }
 
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => return Err(CalcError::UndefinedVariable(s.clone())),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => inverse_matrix(&vec![t.clone()], ram),
+                None => return Err(CalcError::UndefinedVariable(s.clone())),
+                Some(t) => inverse_matrix(&vec![t.clone()], ram)?,
             },
         },
-        _ => Parameters::Null,
-    }
+        a => return Err(CalcError::wrong_type("invert", a.type_name())),
+    })
 }
 
 pub fn plot_fn(
     p: &Vec<Parameters>,
-    ram: &Option<&mut HashMap<String, Parameters>>,
+    mut ram: Option<&mut HashMap<String, Parameters>>,
     functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
     terminal: bool,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     let color = match load() {
         Ok(cfg) => load_config(cfg).general_color,
         Err(_) => load_config(Config::default()).general_color,
@@ -1595,11 +2541,14 @@ pub fn plot_fn(
     if p.len() == 0 {
         let m = color.paint(" > plot(): displays help\n > plot(f): plot f\n > plot(f,title,xlabel,ylabel): plot f with title,xlabel,ylabel\n > plot(f,mode): plot f with the mode=LINE|LINEMARKS|MARKS(default)\n > plot(f,title,xlabel,ylabel,mode): plot f with title,xlabel,ylabel and mode\n > plot(f,start,end,step,mode): plot f between start and end with steps and mode\n > plot(f,start,end,step,title,xlabel,ylabel,mode): combines\n");
         println!("{m}");
-        return Parameters::Null;
+        return Ok(Parameters::Null);
     }
 
     let fs = p.first().unwrap();
-    let mut f: fn(&Vec<Parameters>, &Option<&mut HashMap<String, Parameters>>) -> Parameters = cos;
+    let mut f: fn(
+        &Vec<Parameters>,
+        &Option<&mut HashMap<String, Parameters>>,
+    ) -> Result<Parameters, CalcError> = cos;
     let mut fd: String = "".to_string();
     let mut rad: bool = false;
     let mut fun: bool = true;
@@ -1644,30 +2593,30 @@ pub fn plot_fn(
             "sqrt" => f = sqrt,
             s => match functions {
                 None => match ram.as_ref().unwrap().get(s) {
-                    None => return Parameters::Null,
+                    None => return Ok(Parameters::Null),
                     Some(Parameters::InterpreterVector(vec)) => {
                         fun = false;
                         first_vector = Some(&**vec);
                     }
-                    _ => return Parameters::Null,
+                    _ => return Ok(Parameters::Null),
                 },
                 Some(ref t) => {
                     if t.contains_key(s) {
                         fd = s.to_string();
                     } else {
                         match ram.as_ref().unwrap().get(s) {
-                            None => return Parameters::Null,
+                            None => return Ok(Parameters::Null),
                             Some(Parameters::InterpreterVector(vec)) => {
                                 fun = false;
                                 first_vector = Some(&**vec)
                             }
-                            _ => return Parameters::Null,
+                            _ => return Ok(Parameters::Null),
                         }
                     }
                 }
             },
         },
-        _ => return Parameters::Null,
+        _ => return Ok(Parameters::Null),
     }
 
     let mut start = 0.0;
@@ -1861,58 +2810,22 @@ pub fn plot_fn(
     let mut x = Vec::new();
     let mut y = Vec::new();
     if fun {
-        let (mut vec, mut ast): (Vec<Ast>, Ast) = (Vec::new(), Ast::Nil);
-        match functions {
-            None => (),
-            Some(ref s) => {
-                if s.contains_key(&fd) {
-                    (vec, ast) = s.get(&fd).unwrap().clone();
-                }
-            }
-        }
-
-        let mut sram: HashMap<String, Parameters> = HashMap::new();
-        sram.insert("pi".to_string(), Parameters::Float(PI));
-        sram.insert("e".to_string(), Parameters::Float(E));
+        let mut no_functions = Functions::new();
+        let functions = functions.unwrap_or(&mut no_functions);
         while start <= end {
             x.push(start);
-            if &fd == "" {
-                let p = f(&vec![Parameters::Float(start)], ram);
-                y.push(match p {
-                    Parameters::Float(f) => f,
-                    Parameters::Int(i) => i as f64,
-                    Parameters::Rational(s) => s.approx(),
-                    _ => f64::NAN,
-                });
+            let p = if &fd == "" {
+                f(&vec![Parameters::Float(start)], &ram)
             } else {
-                let mut names = Vec::new();
-                for v in vec.clone() {
-                    match v {
-                        Ast::Nil => (),
-                        Ast::Call { .. } => (),
-                        Ast::Node {
-                            value: v,
-                            left: _l,
-                            right: _r,
-                        } => match v {
-                            Parameters::Identifier(s) => names.push(s.clone()),
-                            _ => (),
-                        },
-                    }
-                }
-                names
-                    .iter()
-                    .zip(vec![Parameters::Float(start)])
-                    .for_each(|(name, param)| {
-                        sram.insert(name.to_string(), param.clone());
-                    });
-                y.push(match interpret(&ast, &mut sram, &mut HashMap::new()) {
-                    Parameters::Float(p) => p,
-                    Parameters::Int(i) => i as f64,
-                    Parameters::Rational(s) => s.approx(),
-                    _ => f64::NAN,
-                });
-            }
+                let args = vec![Parameters::Float(start)];
+                call_user_function(&fd, args, ram.as_deref_mut(), functions)
+            };
+            y.push(match p {
+                Ok(Parameters::Float(f)) => f,
+                Ok(Parameters::Int(i)) => i as f64,
+                Ok(Parameters::Rational(s)) => s.approx(),
+                _ => f64::NAN,
+            });
             start += steps;
         }
     } else {
@@ -1931,7 +2844,7 @@ pub fn plot_fn(
                     _ => (),
                 });
             }
-            _ => return Parameters::Null,
+            _ => return Ok(Parameters::Null),
         }
 
         match second_vector {
@@ -1949,7 +2862,7 @@ pub fn plot_fn(
                     _ => (),
                 });
             }
-            _ => return Parameters::Null,
+            _ => return Ok(Parameters::Null),
         }
     }
     println!("{:?}/{:?}", &x, &y);
@@ -1980,5 +2893,5 @@ pub fn plot_fn(
     } else {
         computes_lines(&x, &y, st, end, steps, title, xlabel, ylabel);
     }
-    Parameters::Null
+    Ok(Parameters::Null)
 }
@@ -1,11 +1,14 @@
 use std::str::FromStr;
 
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
 use crate::lexing::token::Operator::*;
-use crate::lexing::token::Token;
-use crate::lexing::token::Token::PreAnd;
+use crate::lexing::token::{Span, SpannedToken, Token};
 
 pub fn is_an_allowed_char(character: char) -> bool {
-    character.is_alphanumeric()
+    character.is_alphabetic()
+        || character.is_ascii_digit()
         || character == '+'
         || character == '-'
         || character == '*'
@@ -22,6 +25,9 @@ pub fn is_an_allowed_char(character: char) -> bool {
         || character == '>'
         || character == '|'
         || character == '&'
+        || character == '%'
+        || character == '~'
+        || character == '$'
         || character == '['
         || character == ']'
         || character == '_'
@@ -29,18 +35,47 @@ pub fn is_an_allowed_char(character: char) -> bool {
         || character == ' '
 }
 
-fn lex_int(
-    current_char: char,
-    chars: &mut Vec<char>,
-    current_pos: usize,
-    len: usize,
-) -> (i64, usize) {
-    let (a, b) = lex_raddix(current_char, chars, current_pos, len);
-    let err = i64::from_str(&a);
-    if err.is_err() {
-        (0, b)
-    } else {
-        (err.unwrap(), b)
+fn int_token(digits: &str) -> Token {
+    match i64::from_str(digits) {
+        Ok(i) => Token::INT(i),
+        Err(_) => match BigInt::from_str(digits) {
+            Ok(b) => Token::BigInt(b),
+            Err(_) => Token::INT(0),
+        },
+    }
+}
+
+/// Lexes a `0x`, `0o` or `0b` literal, returning the position after it. A
+/// prefix without a digit is an error token spanning the prefix.
+fn lex_prefixed(chars: &[char], pos: usize) -> Option<(Token, usize)> {
+    let (radix, name) = match (chars.get(pos), chars.get(pos + 1)) {
+        (Some('0'), Some('x' | 'X')) => (16, "a hexadecimal"),
+        (Some('0'), Some('o' | 'O')) => (8, "an octal"),
+        (Some('0'), Some('b' | 'B')) => (2, "a binary"),
+        _ => return None,
+    };
+    let mut digits = String::new();
+    let mut end = pos + 2;
+    while let Some(c) = chars.get(end) {
+        let separator = *c == '_'
+            && !digits.is_empty()
+            && chars.get(end + 1).is_some_and(|c| c.is_digit(radix));
+        if c.is_digit(radix) {
+            digits.push(*c);
+        } else if !separator {
+            break;
+        }
+        end += 1;
+    }
+    if digits.is_empty() {
+        let prefix: String = chars[pos..pos + 2].iter().collect();
+        let message = format!("expected {name} digit after `{prefix}`");
+        return Some((Token::Error(message), pos + 2));
+    }
+    let n = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+    match n.to_i64() {
+        Some(i) => Some((Token::INT(i), end)),
+        None => Some((Token::BigInt(n), end)),
     }
 }
 
@@ -51,8 +86,17 @@ fn lex_raddix(
     len: usize,
 ) -> (String, usize) {
     let mut str: String = String::new();
-    while current_pos < len && (current_char.is_ascii_digit()) {
-        str += &*current_char.to_string();
+    // an underscore between digits separates groups, 1_000_000
+    while current_pos < len
+        && (current_char.is_ascii_digit()
+            || (current_char == '_'
+                && chars
+                    .get(current_pos + 1)
+                    .is_some_and(|c| c.is_ascii_digit())))
+    {
+        if current_char != '_' {
+            str += &*current_char.to_string();
+        }
 
         current_pos += 1;
         let a = chars.get(current_pos);
@@ -71,7 +115,10 @@ fn lex_string(
     len: usize,
 ) -> (String, usize) {
     let mut str: String = String::new();
-    while current_pos < len && (current_char.is_alphanumeric() || current_char == '_') {
+    // a trailing quote names a derivative, f' is the derivative of f
+    while current_pos < len
+        && (current_char.is_alphanumeric() || current_char == '_' || current_char == '\'')
+    {
         str += &*current_char.to_string();
 
         current_pos += 1;
@@ -84,28 +131,153 @@ fn lex_string(
     (str, current_pos)
 }
 
-fn lex_float(
-    whole_side: i64,
+/// Reads an exponent after a number, the `e9` of `1e9` or the `E-9` of `1E-9`,
+/// returning it with the position after it.
+fn lex_exponent(chars: &[char], pos: usize) -> Option<(i64, usize)> {
+    if !matches!(chars.get(pos), Some('e' | 'E')) {
+        return None;
+    }
+    let mut end = pos + 1;
+    let negative = match chars.get(end) {
+        Some('-') => {
+            end += 1;
+            true
+        }
+        Some('+') => {
+            end += 1;
+            false
+        }
+        _ => false,
+    };
+    let digits: String = chars[end..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let exponent = i64::from_str(&digits).unwrap_or(i64::MAX);
+    let exponent = if negative { -exponent } else { exponent };
+    Some((exponent, end + digits.len()))
+}
+
+/// Largest power of ten by which a literal such as `6.022e23` or `1.5e-9` is
+/// scaled while still being read exactly rather than as a float.
+const MAX_EXACT_EXPONENT: i64 = 1000;
+
+/// The exact value of `whole.fraction` times ten to the `exponent`, an
+/// integer or a decimal fraction, a float only past `MAX_EXACT_EXPONENT`.
+fn scientific_token(whole: &str, fraction: &str, exponent: i64) -> Token {
+    let fraction = fraction.trim_end_matches('0');
+    let shift = fraction.len() as i64;
+    if (shift..=MAX_EXACT_EXPONENT).contains(&exponent) {
+        let zeros = "0".repeat((exponent - shift) as usize);
+        int_token(&format!("{whole}{fraction}{zeros}"))
+    } else if (shift - MAX_EXACT_EXPONENT..shift).contains(&exponent) {
+        let over = BigInt::from_str(&format!("{whole}{fraction}")).unwrap_or_default();
+        let under = BigInt::from(10).pow((shift - exponent) as u32);
+        Token::Decimal(over, under)
+    } else {
+        let literal = format!("{whole}{fraction}e{}", exponent.saturating_sub(shift));
+        Token::FLOAT(f64::from_str(&literal).unwrap_or(f64::NAN))
+    }
+}
+
+/// Reads the fractional part and the exponent of a number whose integer part
+/// is `whole`, `current_pos` being right after it.
+fn lex_number(
+    whole: &str,
     chars: &mut Vec<char>,
-    mut current_pos: usize,
+    current_pos: usize,
     len: usize,
-) -> (f64, usize) {
-    current_pos += 1;
-    let current_char_options = chars.get(current_pos);
-    let current_char = match current_char_options {
-        Some(t) => t,
-        None => &'0',
+) -> (Token, usize) {
+    let (fraction, current_pos) = match chars.get(current_pos) {
+        Some('.') => {
+            let next = chars.get(current_pos + 1).copied().unwrap_or('0');
+            let (digits, end) = lex_raddix(next, chars, current_pos + 1, len);
+            (Some(digits), end)
+        }
+        _ => (None, current_pos),
+    };
+    match (lex_exponent(chars, current_pos), fraction) {
+        (Some((exponent, end)), fraction) => (
+            scientific_token(whole, fraction.as_deref().unwrap_or(""), exponent),
+            end,
+        ),
+        (None, Some(fraction)) => (scientific_token(whole, &fraction, 0), current_pos),
+        (None, None) => (int_token(whole), current_pos),
+    }
+}
+
+/// Reads a reference to a past result, `$3` or `out[3]`, as the name `$3`
+/// the result is stored under.
+fn lex_output_reference(chars: &[char], pos: usize) -> Option<(Token, usize)> {
+    let bracket = chars[pos..].starts_with(&['o', 'u', 't', '[']);
+    let start = match chars.get(pos) {
+        Some('$') => pos + 1,
+        _ if bracket => pos + 4,
+        _ => return None,
+    };
+    let digits: String = chars[start..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let mut end = start + digits.len();
+    if bracket {
+        if chars.get(end) != Some(&']') {
+            return None;
+        }
+        end += 1;
+    }
+    let number = usize::from_str(&digits).ok()?;
+    Some((Token::IDENTIFIER(format!("${number}")), end))
+}
+
+/// The token of a keyword, whose first letter may be capitalized.
+fn keyword(word: &str) -> Option<Token> {
+    let mut chars = word.chars();
+    let lower = match chars.next() {
+        Some(c) => c.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => return None,
     };
-    let (a, b) = lex_raddix(*current_char, chars, current_pos, len);
-    let f = f64::from_str(&*(whole_side.to_string().as_str().to_owned() + "." + a.as_str()));
-    if f.is_err() {
-        return (f64::NAN, b);
+    match lower.as_str() {
+        "false" => Some(Token::BOOL(false)),
+        "true" => Some(Token::BOOL(true)),
+        "or" => Some(Token::OPE(Or)),
+        "and" => Some(Token::OPE(And)),
+        "geq" => Some(Token::OPE(GreaterOrEqual)),
+        "leq" => Some(Token::OPE(LesserOrEqual)),
+        "lt" => Some(Token::OPE(LesserThan)),
+        "gt" => Some(Token::OPE(GreaterThan)),
+        "eq" => Some(Token::OPE(EQUALITY)),
+        "to" | "in" => Some(Token::OPE(Conversion)),
+        "xor" => Some(Token::OPE(Xor)),
+        _ => None,
     }
-    (f.unwrap(), b)
 }
 
-pub fn lex(input: String) -> Vec<Token> {
+/// Whether the characters after a number, spaces aside, start a name that is
+/// not a keyword or an opening parenthesis.
+fn starts_implicit_product(chars: &[char], mut pos: usize) -> bool {
+    while chars.get(pos) == Some(&' ') {
+        pos += 1;
+    }
+    match chars.get(pos) {
+        Some('(') => true,
+        Some(c) if c.is_alphabetic() => {
+            let word: String = chars[pos..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .collect();
+            keyword(&word).is_none()
+        }
+        _ => false,
+    }
+}
+
+pub fn lex(input: String) -> Vec<SpannedToken> {
     let mut vec: Vec<Token> = Vec::new();
+    let mut spans: Vec<Span> = Vec::new();
 
     let mut current_pos = 0;
 
@@ -113,14 +285,30 @@ pub fn lex(input: String) -> Vec<Token> {
 
     let mut quote_i = 0;
 
-    let length = input.len();
-    while current_pos < input.len() {
+    let length = chars.len();
+    while current_pos < length {
         let current_character: char = chars.get(current_pos).unwrap().to_ascii_lowercase();
         if !is_an_allowed_char(current_character) {
+            let character = chars[current_pos];
             current_pos += 1;
+            let token = if quote_i % 2 == 1 {
+                // strings keep every character
+                Token::IDENTIFIER(character.to_string())
+            } else if character.is_whitespace() {
+                continue;
+            } else {
+                Token::Error(format!("unexpected character `{character}`"))
+            };
+            vec.push(token);
+            spans.push(Span {
+                start: current_pos - 1,
+                end: current_pos,
+            });
             continue;
         };
 
+        let start = current_pos;
+        let before = vec.len();
         match current_character {
             '+' => {
                 vec.push(Token::OPE(PLUS));
@@ -134,10 +322,39 @@ pub fn lex(input: String) -> Vec<Token> {
                 vec.push(Token::OPE(MULTIPLICATION));
                 current_pos += 1
             }
-            '/' => {
-                vec.push(Token::OPE(DIVIDE));
+            '/' => match vec.pop() {
+                Some(Token::OPE(DIVIDE)) => {
+                    vec.push(Token::OPE(IntegerDivision));
+                    current_pos += 1
+                }
+                Some(p) => {
+                    vec.push(p);
+                    vec.push(Token::OPE(DIVIDE));
+                    current_pos += 1
+                }
+                None => {
+                    vec.push(Token::OPE(DIVIDE));
+                    current_pos += 1
+                }
+            },
+            '%' => {
+                vec.push(Token::OPE(Modulo));
+                current_pos += 1
+            }
+            '~' => {
+                vec.push(Token::OPE(BitNot));
                 current_pos += 1
             }
+            '$' => match lex_output_reference(&chars, current_pos) {
+                Some((token, end)) => {
+                    vec.push(token);
+                    current_pos = end;
+                }
+                None => {
+                    vec.push(Token::IDENTIFIER("$".to_string()));
+                    current_pos += 1
+                }
+            },
             ')' => {
                 vec.push(Token::RPAR);
                 current_pos += 1
@@ -146,14 +363,36 @@ pub fn lex(input: String) -> Vec<Token> {
                 vec.push(Token::LPAR);
                 current_pos += 1
             }
-            '>' => {
-                vec.push(Token::OPE(GreaterThan));
-                current_pos += 1
-            }
-            '<' => {
-                vec.push(Token::OPE(LesserThan));
-                current_pos += 1
-            }
+            '>' => match vec.pop() {
+                Some(Token::OPE(GreaterThan)) => {
+                    vec.push(Token::OPE(RightShift));
+                    current_pos += 1
+                }
+                Some(p) => {
+                    vec.push(p);
+                    vec.push(Token::OPE(GreaterThan));
+                    current_pos += 1
+                }
+                None => {
+                    vec.push(Token::OPE(GreaterThan));
+                    current_pos += 1
+                }
+            },
+            '<' => match vec.pop() {
+                Some(Token::OPE(LesserThan)) => {
+                    vec.push(Token::OPE(LeftShift));
+                    current_pos += 1
+                }
+                Some(p) => {
+                    vec.push(p);
+                    vec.push(Token::OPE(LesserThan));
+                    current_pos += 1
+                }
+                None => {
+                    vec.push(Token::OPE(LesserThan));
+                    current_pos += 1
+                }
+            },
             '"' => {
                 vec.push(Token::QUOTE);
                 quote_i += 1;
@@ -239,64 +478,97 @@ pub fn lex(input: String) -> Vec<Token> {
                 current_pos += 1
             }
             ch => {
-                if ch.is_numeric() {
-                    let (a, b) = lex_int(current_character, &mut chars, current_pos, length);
-                    current_pos = b;
-                    let cha = chars.get(current_pos);
-                    match cha {
-                        Some(char) => {
-                            if *char == '.' {
-                                let (a1, b1) = lex_float(a, &mut chars, current_pos, length);
-                                current_pos = b1;
-                                vec.push(Token::FLOAT(a1))
-                            } else {
-                                vec.push(Token::INT(a));
-                                current_pos = b;
-                            }
+                if ch.is_ascii_digit() {
+                    match lex_prefixed(&chars, current_pos) {
+                        Some((token, end)) => {
+                            vec.push(token);
+                            current_pos = end;
                         }
                         None => {
-                            vec.push(Token::INT(a));
-                            current_pos = b;
+                            let (a, b) =
+                                lex_raddix(current_character, &mut chars, current_pos, length);
+                            let (token, end) = lex_number(&a, &mut chars, b, length);
+                            vec.push(token);
+                            current_pos = end;
                         }
                     }
+                    // a number followed by a name or a parenthesis is an implicit
+                    // product, 2x is 2*x, 3(x+1) is 3*(x+1) and 3 m is 3*m
+                    if quote_i % 2 == 0 && starts_implicit_product(&chars, current_pos) {
+                        vec.push(Token::OPE(ImplicitMultiplication));
+                    }
                 }
                 if ch.is_alphabetic() || ch == '_' {
-                    let (a, b) = lex_string(current_character, &mut chars, current_pos, length);
-                    current_pos = b;
-                    if &a == "false" {
-                        vec.push(Token::BOOL(false))
-                    } else if &a == "true" {
-                        vec.push(Token::BOOL(true))
-                    } else if &a == "or" {
-                        vec.push(Token::OPE(Or))
-                    } else if &a == "and" {
-                        vec.push(Token::OPE(And))
-                    } else if &a == "geq" {
-                        vec.push(Token::OPE(GreaterOrEqual))
-                    } else if &a == "leq" {
-                        vec.push(Token::OPE(LesserOrEqual))
-                    } else if &a == "lt" {
-                        vec.push(Token::OPE(LesserThan))
-                    } else if &a == "gt" {
-                        vec.push(Token::OPE(GreaterThan))
-                    } else if &a == "eq" {
-                        vec.push(Token::OPE(EQUALITY))
-                    } else {
-                        vec.push(Token::IDENTIFIER(a))
+                    match lex_output_reference(&chars, current_pos) {
+                        Some((token, end)) => {
+                            vec.push(token);
+                            current_pos = end;
+                        }
+                        None => {
+                            // names keep their case, M is mega and m is milli
+                            let (a, b) =
+                                lex_string(chars[current_pos], &mut chars, current_pos, length);
+                            current_pos = b;
+                            match keyword(&a) {
+                                Some(token) => vec.push(token),
+                                None => vec.push(Token::IDENTIFIER(a)),
+                            }
+                        }
                     }
                 }
                 if ch == '.' {
-                    let (a, b) = lex_float(0, &mut chars, current_pos, length);
-                    current_pos = b;
-                    vec.push(Token::FLOAT(a))
+                    let (token, end) = lex_number("0", &mut chars, current_pos, length);
+                    current_pos = end;
+                    vec.push(token)
                 }
             }
         }
+
+        if vec.len() > before {
+            spans.push(Span {
+                start,
+                end: current_pos,
+            });
+            // the implicit product has no width
+            if vec.len() > before + 1 {
+                spans.push(Span {
+                    start: current_pos,
+                    end: current_pos,
+                });
+            }
+        } else if vec.len() == before
+            && matches!(current_character, '=' | '&' | '|' | '/' | '<' | '>')
+        {
+            // the character was merged into the previous token (`==`, `<=`, `&&`, `//`, ...)
+            if let Some(span) = spans.last_mut() {
+                span.end = current_pos;
+            }
+        }
     }
+
+    let offsets: Vec<usize> = input
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(input.len()))
+        .collect();
     let mut result = Vec::new();
-    vec.iter()
-        .filter(|x| x != &&Token::PreOr && x != &&PreAnd)
-        .for_each(|x1| result.push(x1.clone()));
+    vec.into_iter()
+        .zip(spans)
+        // a lone `&` or `|` is the bitwise operator
+        .map(|(x, span)| match x {
+            Token::PreAnd => (Token::OPE(BitAnd), span),
+            Token::PreOr => (Token::OPE(BitOr), span),
+            x => (x, span),
+        })
+        .for_each(|(token, span)| {
+            result.push(SpannedToken {
+                token,
+                span: Span {
+                    start: offsets[span.start],
+                    end: offsets[span.end],
+                },
+            })
+        });
     result
 }
 
@@ -305,141 +577,311 @@ mod tests {
     use crate::lexing::lexer::lex;
     use crate::lexing::token::Operator::*;
     use crate::lexing::token::Token::*;
+    use crate::lexing::token::{Span, Token};
+    use num_bigint::BigInt;
+
+    fn lex_without_spans(input: &str) -> Vec<Token> {
+        lex(input.to_string())
+            .into_iter()
+            .map(|t| t.token)
+            .collect()
+    }
 
     #[test]
     fn lex_plus() {
-        let mut expected = Vec::new();
-        expected.push(OPE(PLUS));
-        let result = lex("+".to_string());
+        let expected = vec![OPE(PLUS)];
+        let result = lex_without_spans("+");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_minus() {
-        let mut expected = Vec::new();
-        expected.push(OPE(MINUS));
-        let result = lex("-".to_string());
+        let expected = vec![OPE(MINUS)];
+        let result = lex_without_spans("-");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_mult() {
-        let mut expected = Vec::new();
-        expected.push(OPE(MULTIPLICATION));
-        let result = lex("*".to_string());
+        let expected = vec![OPE(MULTIPLICATION)];
+        let result = lex_without_spans("*");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_divide() {
-        let mut expected = Vec::new();
-        expected.push(OPE(DIVIDE));
-        let result = lex("/".to_string());
+        let expected = vec![OPE(DIVIDE)];
+        let result = lex_without_spans("/");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_operators() {
-        let mut expected = Vec::new();
-        expected.push(OPE(PLUS));
-        expected.push(OPE(MULTIPLICATION));
-        expected.push(OPE(MINUS));
-        expected.push(OPE(DIVIDE));
-        let result = lex("+*-/".to_string());
+        let expected = vec![OPE(PLUS), OPE(MULTIPLICATION), OPE(MINUS), OPE(DIVIDE)];
+        let result = lex_without_spans("+*-/");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_lpar() {
-        let mut expected = Vec::new();
-        expected.push(LPAR);
-        let result = lex("(".to_string());
+        let expected = vec![LPAR];
+        let result = lex_without_spans("(");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_rpar() {
-        let mut expected = Vec::new();
-        expected.push(RPAR);
-        let result = lex(")".to_string());
+        let expected = vec![RPAR];
+        let result = lex_without_spans(")");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_equal() {
-        let mut expected = Vec::new();
-        expected.push(EQUAL);
-        let result = lex("=".to_string());
+        let expected = vec![EQUAL];
+        let result = lex_without_spans("=");
         assert_eq!(result, expected);
     }
 
     #[test]
     fn lex_tokens() {
-        let mut expected = Vec::new();
-        expected.push(LPAR);
-        expected.push(RPAR);
-        expected.push(EQUAL);
-        let result = lex("()=".to_string());
+        let expected = vec![LPAR, RPAR, EQUAL];
+        let result = lex_without_spans("()=");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn lex_simple_int() {
-        let mut expected = Vec::new();
-        expected.push(INT(1));
-        let result = lex("1".to_string());
+        let expected = vec![INT(1)];
+        let result = lex_without_spans("1");
         assert_eq!(result, expected);
     }
 
     #[test]
     fn lex_complex_int() {
-        let mut expected = Vec::new();
-        expected.push(INT(100));
-        let result = lex("100".to_string());
+        let expected = vec![INT(100)];
+        let result = lex_without_spans("100");
         assert_eq!(result, expected);
     }
 
     #[test]
     fn lex_simple_string() {
-        let mut expected = Vec::new();
-        expected.push(IDENTIFIER("test".to_string()));
-        let result = lex("test".to_string());
+        let expected = vec![IDENTIFIER("test".to_string())];
+        let result = lex_without_spans("test");
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_complex_operation() {
-        let mut expected = Vec::new();
-        expected.push(INT(1));
-        expected.push(OPE(PLUS));
-        expected.push(INT(1));
-        let result = lex("1 + 1".to_string());
+        let expected = vec![INT(1), OPE(PLUS), INT(1)];
+        let result = lex_without_spans("1 + 1");
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_complex_equality() {
-        let mut expected = Vec::new();
-        expected.push(IDENTIFIER("var1".to_string()));
-        expected.push(EQUAL);
-        expected.push(INT(100));
-        let result = lex("var1 = 100".to_string());
+        let expected = vec![IDENTIFIER("var1".to_string()), EQUAL, INT(100)];
+        let result = lex_without_spans("var1 = 100");
         assert_eq!(result, expected)
     }
 
     #[test]
     fn test_simple_float() {
-        let mut expected = Vec::new();
-        expected.push(FLOAT(0.14));
-        let result = lex(".14".to_string());
+        let expected = vec![Decimal(BigInt::from(14), BigInt::from(100))];
+        let result = lex_without_spans(".14");
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_complex_float() {
-        let mut expected = Vec::new();
-        expected.push(FLOAT(314.05));
-        let result = lex("314.05".to_string());
+        let expected = vec![Decimal(BigInt::from(31405), BigInt::from(100))];
+        let result = lex_without_spans("314.05");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_spans() {
+        let expected = vec![
+            Span { start: 0, end: 4 },
+            Span { start: 5, end: 7 },
+            Span { start: 8, end: 11 },
+        ];
+        let result: Vec<Span> = lex("var1 <= 100".to_string())
+            .into_iter()
+            .map(|t| t.span)
+            .collect();
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_spans_are_byte_offsets() {
+        let expected = vec![Span { start: 0, end: 2 }, Span { start: 3, end: 4 }];
+        let result: Vec<Span> = lex("é +".to_string()).into_iter().map(|t| t.span).collect();
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_unknown_character() {
+        let result = lex("√2\t{".to_string());
+        let unexpected = |c| Token::Error(format!("unexpected character `{c}`"));
+        assert_eq!(result[0].token, unexpected('√'));
+        assert_eq!(result[0].span, Span { start: 0, end: 3 });
+        assert_eq!(result[2].token, unexpected('{'));
+        assert_eq!(result[2].span, Span { start: 5, end: 6 });
+        // non-ASCII digits are not numbers
+        let result = lex("2²".to_string());
+        assert_eq!(result[1].token, unexpected('²'));
+        // strings keep them
+        let expected = vec![
+            QUOTE,
+            IDENTIFIER("a".to_string()),
+            IDENTIFIER(":".to_string()),
+            QUOTE,
+        ];
+        assert_eq!(lex_without_spans("\"a:\""), expected)
+    }
+
+    #[test]
+    fn lex_prefix_without_digits() {
+        for (input, message) in [
+            ("0x", "expected a hexadecimal digit after `0x`"),
+            ("0xG", "expected a hexadecimal digit after `0x`"),
+            ("0x_ff", "expected a hexadecimal digit after `0x`"),
+            ("0O8", "expected an octal digit after `0O`"),
+            ("0b2", "expected a binary digit after `0b`"),
+        ] {
+            let result = lex(input.to_string());
+            assert_eq!(result[0].token, Token::Error(message.to_string()));
+            assert_eq!(result[0].span, Span { start: 0, end: 2 });
+        }
+    }
+
+    #[test]
+    fn lex_big_int() {
+        let expected = vec![Token::BigInt(BigInt::from(i64::MAX) + 1_i64)];
+        let result = lex_without_spans("9223372036854775808");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_prefixed_int() {
+        let expected = vec![
+            Token::INT(255),
+            OPE(PLUS),
+            Token::INT(10),
+            OPE(PLUS),
+            Token::INT(15),
+        ];
+        let result = lex_without_spans("0xff + 0b1010 + 0O17");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_digit_separators() {
+        let expected = vec![Token::INT(1000000), OPE(PLUS), Token::INT(0xffff)];
+        let result = lex_without_spans("1_000_000 + 0xff_ff");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_implicit_product() {
+        let expected = vec![
+            Token::INT(2),
+            Token::OPE(ImplicitMultiplication),
+            Token::IDENTIFIER("x".to_string()),
+        ];
+        let result = lex_without_spans("2x");
+        assert_eq!(result, expected);
+        let result = lex_without_spans("2 x");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_conversion() {
+        let expected = vec![
+            Token::INT(60),
+            Token::OPE(ImplicitMultiplication),
+            Token::IDENTIFIER("mph".to_string()),
+            Token::OPE(Conversion),
+            Token::IDENTIFIER("km".to_string()),
+            Token::OPE(DIVIDE),
+            Token::IDENTIFIER("h".to_string()),
+        ];
+        let result = lex_without_spans("60 mph to km/h");
+        assert_eq!(result, expected);
+        let result = lex_without_spans("60 mph in km/h");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_keeps_case() {
+        let expected = vec![Token::IDENTIFIER("MPa".to_string()), Token::BOOL(true)];
+        let result = lex_without_spans("MPa True");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_integer_operators() {
+        let expected = vec![
+            Token::INT(7),
+            Token::OPE(IntegerDivision),
+            Token::INT(2),
+            Token::OPE(Modulo),
+            Token::INT(3),
+            Token::OPE(LeftShift),
+            Token::INT(1),
+            Token::OPE(RightShift),
+            Token::OPE(BitNot),
+            Token::INT(4),
+        ];
+        let result = lex_without_spans("7 // 2 % 3 << 1 >> ~4");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_bitwise() {
+        let expected = vec![
+            Token::INT(6),
+            Token::OPE(BitAnd),
+            Token::INT(3),
+            Token::OPE(BitOr),
+            Token::INT(1),
+            Token::OPE(Xor),
+            Token::INT(2),
+            Token::OPE(And),
+            Token::BOOL(true),
+        ];
+        let result = lex_without_spans("6 & 3 | 1 xor 2 && true");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_scientific_notation() {
+        let expected = vec![
+            Token::BigInt(BigInt::from(6022) * BigInt::from(10).pow(20)),
+            Token::OPE(PLUS),
+            Token::Decimal(BigInt::from(1), BigInt::from(10).pow(9)),
+            Token::OPE(PLUS),
+            Token::INT(2),
+            Token::OPE(ImplicitMultiplication),
+            Token::IDENTIFIER("e".to_string()),
+            Token::OPE(PLUS),
+            Token::FLOAT(0.0),
+        ];
+        let result = lex_without_spans("6.022e23 + 1E-9 + 2e + 1e-2000");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn lex_output_references() {
+        let expected = vec![
+            Token::IDENTIFIER("$3".to_string()),
+            Token::OPE(PLUS),
+            Token::IDENTIFIER("$12".to_string()),
+            Token::OPE(PLUS),
+            Token::IDENTIFIER("out".to_string()),
+        ];
+        let result = lex_without_spans("$3 + out[12] + out");
         assert_eq!(result, expected)
     }
 }
@@ -1,13 +1,19 @@
 use std::fmt::{Display, Formatter};
 
+use num_bigint::BigInt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operator {
     PLUS,
     MINUS,
     MULTIPLICATION,
+    ImplicitMultiplication,
     DIVIDE,
+    IntegerDivision,
+    Modulo,
     EXPO,
     EQUALITY,
+    Conversion,
     GreaterThan,
     LesserThan,
     GreaterOrEqual,
@@ -15,6 +21,12 @@ pub enum Operator {
     And,
     Or,
     NOT,
+    BitAnd,
+    BitOr,
+    Xor,
+    BitNot,
+    LeftShift,
+    RightShift,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -22,6 +34,10 @@ pub enum Token {
     OPE(Operator),
     IDENTIFIER(String),
     INT(i64),
+    BigInt(BigInt),
+    /// A decimal literal read exactly, its numerator and power of ten
+    /// denominator
+    Decimal(BigInt, BigInt),
     FLOAT(f64),
     BOOL(bool),
     EQUAL,
@@ -35,6 +51,20 @@ pub enum Token {
     WHITESPACE,
     PreAnd,
     PreOr,
+    /// Input the lexer could not read, reported by the parser at its span
+    Error(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
@@ -42,12 +72,16 @@ pub enum TokenType {
     PLUS,
     MINUS,
     MULTIPLICATION,
+    ImplicitMultiplication,
     DIVIDE,
+    IntegerDivision,
+    Modulo,
     IDENTIFIER,
     INT,
     FLOAT,
     EQUAL,
     EQUALITY,
+    Conversion,
     GREATER,
     LESSER,
     GREATEREQ,
@@ -55,6 +89,12 @@ pub enum TokenType {
     AND,
     LESSEREQ,
     NOT,
+    BitAnd,
+    BitOr,
+    Xor,
+    BitNot,
+    LeftShift,
+    RightShift,
     BOOL,
     RPAR,
     LPAR,
@@ -68,16 +108,21 @@ pub enum TokenType {
 }
 
 pub enum Precedence {
-    ASSIGNMENT = 1,
-    CONDITIONAL = 2,
-    SUM = 4,
-    MINUS = 3,
-    PRODUCT = 6,
-    DIVIDE = 5,
-    EXPONENT = 7,
-    //PREFIX = 8,
-    //POSTFIX = 9,
-    CALL = 10,
+    Assignment = 1,
+    Or = 2,
+    And = 3,
+    Conditional = 4,
+    Conversion = 5,
+    BitOr = 6,
+    Xor = 7,
+    BitAnd = 8,
+    Shift = 9,
+    Sum = 10,
+    Product = 12,
+    Implicit = 13,
+    Exponent = 14,
+    //Postfix = 15,
+    Call = 16,
 }
 
 impl Display for Operator {
@@ -86,9 +131,13 @@ impl Display for Operator {
             Operator::PLUS => write!(f, "+"),
             Operator::MINUS => write!(f, "-"),
             Operator::DIVIDE => write!(f, "/"),
+            Operator::IntegerDivision => write!(f, "//"),
+            Operator::Modulo => write!(f, "%"),
             Operator::MULTIPLICATION => write!(f, "*"),
+            Operator::ImplicitMultiplication => write!(f, "*"),
             Operator::EXPO => write!(f, "^"),
             Operator::EQUALITY => write!(f, "=="),
+            Operator::Conversion => write!(f, "to"),
             Operator::GreaterOrEqual => write!(f, ">="),
             Operator::GreaterThan => write!(f, ">"),
             Operator::LesserOrEqual => write!(f, "<="),
@@ -96,6 +145,12 @@ impl Display for Operator {
             Operator::NOT => write!(f, "!"),
             Operator::Or => write!(f, "||"),
             Operator::And => write!(f, "&&"),
+            Operator::BitAnd => write!(f, "&"),
+            Operator::BitOr => write!(f, "|"),
+            Operator::Xor => write!(f, "xor"),
+            Operator::BitNot => write!(f, "~"),
+            Operator::LeftShift => write!(f, "<<"),
+            Operator::RightShift => write!(f, ">>"),
         }
     }
 }
@@ -108,6 +163,8 @@ impl Display for Token {
             Token::EQUAL => write!(f, "="),
             Token::FLOAT(i) => write!(f, "{}", i),
             Token::INT(i) => write!(f, "{}", i),
+            Token::BigInt(i) => write!(f, "{}", i),
+            Token::Decimal(over, under) => write!(f, "{over}/{under}"),
             Token::IDENTIFIER(s) => write!(f, "{}", s),
             Token::OPE(s) => write!(f, "{}", s),
             Token::COMMA => write!(f, ","),
@@ -119,6 +176,7 @@ impl Display for Token {
             Token::LBRACKET => write!(f, "["),
             Token::QUOTE => write!(f, "\""),
             Token::WHITESPACE => write!(f, " "),
+            Token::Error(message) => write!(f, "{message}"),
         }
     }
 }
@@ -130,9 +188,13 @@ impl Token {
                 Operator::PLUS => TokenType::PLUS,
                 Operator::MINUS => TokenType::MINUS,
                 Operator::MULTIPLICATION => TokenType::MULTIPLICATION,
+                Operator::ImplicitMultiplication => TokenType::ImplicitMultiplication,
                 Operator::DIVIDE => TokenType::DIVIDE,
+                Operator::IntegerDivision => TokenType::IntegerDivision,
+                Operator::Modulo => TokenType::Modulo,
                 Operator::EXPO => TokenType::EXPO,
                 Operator::EQUALITY => TokenType::EQUALITY,
+                Operator::Conversion => TokenType::Conversion,
                 Operator::GreaterThan => TokenType::GREATER,
                 Operator::GreaterOrEqual => TokenType::GREATEREQ,
                 Operator::LesserThan => TokenType::LESSER,
@@ -140,9 +202,17 @@ impl Token {
                 Operator::NOT => TokenType::NOT,
                 Operator::And => TokenType::AND,
                 Operator::Or => TokenType::OR,
+                Operator::BitAnd => TokenType::BitAnd,
+                Operator::BitOr => TokenType::BitOr,
+                Operator::Xor => TokenType::Xor,
+                Operator::BitNot => TokenType::BitNot,
+                Operator::LeftShift => TokenType::LeftShift,
+                Operator::RightShift => TokenType::RightShift,
             },
             Token::IDENTIFIER(_) => TokenType::IDENTIFIER,
             Token::INT(_) => TokenType::INT,
+            Token::BigInt(_) => TokenType::INT,
+            Token::Decimal(_, _) => TokenType::FLOAT,
             Token::FLOAT(_) => TokenType::FLOAT,
             Token::EQUAL => TokenType::EQUAL,
             Token::RPAR => TokenType::RPAR,
@@ -253,6 +323,13 @@ mod test {
         assert_eq!(value, expected);
     }
 
+    #[test]
+    fn test_token_type_operators_bitwise() {
+        let expected = TokenType::BitAnd;
+        let value = Token::OPE(super::Operator::BitAnd).to_token_type();
+        assert_eq!(value, expected);
+    }
+
     #[test]
     fn test_token_type_identifier() {
         let expected = TokenType::IDENTIFIER;
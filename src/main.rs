@@ -1,29 +1,51 @@
 use std::collections::HashMap;
-use std::env::{self, Args};
-use std::f64::consts::{E, PI};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
 use std::process::exit;
 use std::str::SplitWhitespace;
 use std::sync::Arc;
+use std::thread;
 
 use ansi_term::Color;
 use configuration::loader::Config;
 use linefeed::{Completer, Completion, Interface, ReadResult, Terminal};
 
+use crate::configuration::cli::{help, parse_args, Cli, OutputFormat};
+use crate::configuration::history::{format_entries, History};
 use crate::configuration::loader::{
-    load, load_config, write_config, write_default_config, Greeting, Loaded, Prompt,
+    load, load_config, load_units, set_config_source, write_config, write_default_config,
+    ConfigSource, Greeting, Loaded, Prompt,
 };
+use crate::errors::calc_error::CalcError;
+use crate::exact_math::complex::set_polar_display;
+use crate::exact_math::rationals::{set_fraction_display, set_rationalize_limits, FractionDisplay};
+use crate::exact_math::units::{lookup, set_user_units};
 use crate::interpreting::interpreter::interpret;
+use crate::interpreting::stdlib::{constants, set_degrees, set_recursion_limit};
 use crate::lexing::lexer::lex;
 use crate::parsing::ast::{Ast, Parameters};
 use crate::parsing::parser::{init_calc_parser, CalcParser};
+use crate::utils::float_utils::{set_decimal_digits, set_notation, Notation, MAX_DECIMAL_DIGITS};
+use crate::utils::integer_utils::{set_output_base, set_thousands_separator};
+use crate::utils::json_utils::json_line;
 
 mod configuration;
+mod errors;
 mod exact_math;
 mod interpreting;
 mod lexing;
 mod parsing;
 mod utils;
 
+fn format_error(error: &CalcError, line: &str) -> String {
+    match error {
+        CalcError::ParseError(e) => e.render(line),
+        e => e.to_string(),
+    }
+}
+
 fn show_config(config: Config) -> (String, Option<Config>) {
     let loaded = load_config(config.clone());
 
@@ -80,6 +102,7 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                             general_color: (s.to_string()),
                             greeting: (config.greeting),
                             prompt: (config.prompt),
+                            ..config
                         };
                         match write_config(&cfg) {
                             Ok(_) => (format!("Greeting color has been set to {}, reload for this to take effect\n",&s).to_string(),None),
@@ -104,6 +127,7 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 prompt: s.to_string(),
                                 prompt_color: config.prompt.prompt_color,
                             },
+                            ..config
                         };
 
                         match write_config(&cfg) {
@@ -140,6 +164,7 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 prompt: config.prompt.prompt,
                                 prompt_color: s.to_string(),
                             },
+                            ..config
                         };
 
                         match write_config(&cfg) {
@@ -167,6 +192,7 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 greeting_message: config.greeting.greeting_message,
                             },
                             prompt: config.prompt,
+                            ..config
                         };
 
                         match write_config(&cfg) {
@@ -194,6 +220,7 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 greeting_color: config.greeting.greeting_color,
                             },
                             prompt: config.prompt,
+                            ..config
                         };
 
                         match write_config(&cfg) {
@@ -213,6 +240,206 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                     }
                 }
             }
+            Some("recursion_limit") => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(limit)) => {
+                    let cfg = Config {
+                        recursion_limit: limit,
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Recursion limit has been set to {limit}\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(Err(_)) => (
+                    "The recursion limit must be a positive integer\n".to_string(),
+                    None,
+                ),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some("max_denominator") => match args.next().map(|s| s.parse::<u64>()) {
+                Some(Ok(max)) if max > 0 => {
+                    let cfg = Config {
+                        max_denominator: max,
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Maximum denominator has been set to {max}\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(_) => (
+                    "The maximum denominator must be a positive integer\n".to_string(),
+                    None,
+                ),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some("tolerance") => match args.next().map(|s| s.parse::<f64>()) {
+                Some(Ok(tolerance)) if tolerance >= 0.0 => {
+                    let cfg = Config {
+                        tolerance,
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Tolerance has been set to {tolerance}\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(_) => (
+                    "The tolerance must be a non negative number\n".to_string(),
+                    None,
+                ),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some("complex_display") => match args.next() {
+                Some(display @ ("rectangular" | "polar")) => {
+                    let cfg = Config {
+                        complex_display: display.to_string(),
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Complex numbers are now displayed in {display} form\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(_) => (
+                    "The complex display is either rectangular or polar\n".to_string(),
+                    None,
+                ),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some(
+                key @ ("notation"
+                | "decimal_digits"
+                | "trim_zeros"
+                | "fraction_display"
+                | "thousands_separator"),
+            ) => match display_setting(&config, key, args.next()) {
+                Ok((cfg, message)) => match write_config(&cfg) {
+                    Ok(_) => (message, Some(cfg)),
+                    _ => (
+                        "An error occured while writing the config\n".to_string(),
+                        None,
+                    ),
+                },
+                Err(message) => (message, None),
+            },
+            Some("history_size") => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(size)) => {
+                    let cfg = Config {
+                        history_size: size,
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("The history will keep {size} lines from the next session\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(Err(_)) => (
+                    "The history size must be a non negative integer\n".to_string(),
+                    None,
+                ),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some("history_dedup") => match args.next().map(|s| s.parse::<bool>()) {
+                Some(Ok(dedup)) => {
+                    let cfg = Config {
+                        history_dedup: dedup,
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            if dedup {
+                                "Repeated lines will replace their earlier entry in the history\n"
+                                    .to_string()
+                            } else {
+                                "Repeated lines will all be kept in the history\n".to_string()
+                            },
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(Err(_)) => ("history_dedup is either true or false\n".to_string(), None),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some("output_base") => match args.next().map(|s| s.parse::<u32>()) {
+                Some(Ok(base)) if (2..=36).contains(&base) => {
+                    let cfg = Config {
+                        output_base: base,
+                        ..config
+                    };
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Integers are now displayed in base {base}\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                Some(_) => (
+                    "The output base must be an integer from 2 to 36\n".to_string(),
+                    None,
+                ),
+                None => (
+                    "You need more argument for this command\n".to_string(),
+                    None,
+                ),
+            },
             _ => (
                 "You need more argument for this command\n".to_string(),
                 None,
@@ -220,7 +447,142 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
         }
     }
 
-    handle_second_argument(config, args.nth(0), args)
+    handle_second_argument(config, args.next(), args)
+}
+
+/// Changes one of the settings of how results are printed, returning the new
+/// configuration with a confirmation, or why the value is refused.
+fn display_setting(
+    config: &Config,
+    key: &str,
+    value: Option<&str>,
+) -> Result<(Config, String), String> {
+    let value = match value {
+        Some(value) => value,
+        None => return Err("You need more argument for this command\n".to_string()),
+    };
+    let config = config.clone();
+    match key {
+        "notation" => match Notation::from_name(value) {
+            Some(_) => Ok((
+                Config {
+                    notation: value.to_string(),
+                    ..config
+                },
+                format!("Floats are now displayed in {value} notation\n"),
+            )),
+            None => Err("The notation is auto, fixed, scientific or engineering\n".to_string()),
+        },
+        "decimal_digits" => match value.parse::<usize>() {
+            Ok(digits) if digits <= MAX_DECIMAL_DIGITS => Ok((
+                Config {
+                    decimal_digits: digits,
+                    ..config
+                },
+                format!("Results are now displayed with {digits} decimal digits\n"),
+            )),
+            _ => Err(format!(
+                "The decimal digits must be an integer from 0 to {MAX_DECIMAL_DIGITS}\n"
+            )),
+        },
+        "trim_zeros" => match value.parse::<bool>() {
+            Ok(trim_zeros) => Ok((
+                Config {
+                    trim_zeros,
+                    ..config
+                },
+                if trim_zeros {
+                    "Trailing zeros are now removed\n".to_string()
+                } else {
+                    "Trailing zeros are now kept\n".to_string()
+                },
+            )),
+            Err(_) => Err("trim_zeros is either true or false\n".to_string()),
+        },
+        "fraction_display" => match FractionDisplay::from_name(value) {
+            Some(_) => Ok((
+                Config {
+                    fraction_display: value.to_string(),
+                    ..config
+                },
+                format!("Rationals are now displayed in {value} form\n"),
+            )),
+            None => Err("The fraction display is fraction, decimal or mixed\n".to_string()),
+        },
+        "thousands_separator" => {
+            let separator = match value {
+                "none" => "",
+                "space" => " ",
+                c if c.chars().count() == 1 => c,
+                _ => {
+                    return Err(
+                        "The thousands separator is a single character, space or none\n"
+                            .to_string(),
+                    )
+                }
+            };
+            Ok((
+                Config {
+                    thousands_separator: separator.to_string(),
+                    ..config
+                },
+                format!("The thousands separator has been set to {value}\n"),
+            ))
+        }
+        _ => Err(format!("{key} is not a display setting\n")),
+    }
+}
+
+fn show_display(config: &Config) -> String {
+    let separator = match config.thousands_separator.as_str() {
+        "" => "none",
+        " " => "space",
+        s => s,
+    };
+    format!(
+        " notation: {}\n decimal_digits: {}\n trim_zeros: {}\n fraction_display: {}\n thousands_separator: {separator}\n",
+        config.notation, config.decimal_digits, config.trim_zeros, config.fraction_display
+    )
+}
+
+/// Keeps the `number`th result of the session as `$number`, also reachable as
+/// `out[number]`, and as the last result `ans` and `_`.
+fn record_output(ram: &mut HashMap<String, Parameters>, number: usize, result: &Parameters) {
+    for name in [format!("${number}"), "ans".to_string(), "_".to_string()] {
+        ram.insert(name, result.clone());
+    }
+}
+
+/// Hands the loaded settings over to the modules which use them.
+fn apply_settings(loaded: &Loaded) {
+    set_recursion_limit(loaded.recursion_limit);
+    set_rationalize_limits(loaded.max_denominator, loaded.tolerance);
+    set_polar_display(loaded.polar);
+    set_output_base(loaded.output_base);
+    set_notation(loaded.notation);
+    set_decimal_digits(loaded.decimal_digits, loaded.trim_zeros);
+    set_fraction_display(loaded.fraction_display);
+    set_thousands_separator(loaded.thousands_separator);
+}
+
+/// The entry number of a `history run n` line.
+fn history_run(line: &str) -> Option<usize> {
+    match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["history", "run", number] => number.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Records a line in the history, both the one of the line editor and the
+/// history file.
+fn remember<Term: Terminal>(interface: &Interface<Term>, history: &mut History, line: &str) {
+    if let Some(i) = history.push(line) {
+        interface.remove_history(i);
+    }
+    if history.entries().last().is_some_and(|e| e == line) {
+        interface.add_history(line.to_string());
+        let _ = history.save();
+    }
 }
 
 fn reload_config() -> (String, Option<Config>) {
@@ -237,7 +599,7 @@ fn reload_config() -> (String, Option<Config>) {
 }
 
 fn show_help_config() -> (String, Option<Config>) {
-    (format!("Config help, \n > config show: show config \n > config set: set config \n > config reload: reload config \n > config reset: reset config\n\n"),None)
+    ("Config help, \n > config show: show config \n > config set: set config \n > config reload: reload config \n > config reset: reset config\n\n".to_string(),None)
 }
 
 fn handle_config(line: &str, config: Config) -> (String, Option<Config>) {
@@ -245,7 +607,7 @@ fn handle_config(line: &str, config: Config) -> (String, Option<Config>) {
         None => show_help_config(),
         Some(t) => {
             let mut w = t.split_whitespace();
-            match w.nth(0) {
+            match w.next() {
                 None => show_help_config(),
                 Some("set") => set_config(config, &mut w.clone()),
                 Some("reload") => reload_config(),
@@ -256,44 +618,323 @@ fn handle_config(line: &str, config: Config) -> (String, Option<Config>) {
     }
 }
 
+const VERSION: &str = concat!("v", env!("CARGO_PKG_VERSION"));
+
+/// Evaluation is recursive, so it runs on a thread with a larger stack to let
+/// deep user-defined recursion reach the configured recursion limit first.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Registers the units of the units file, warning about the ones that cannot
+/// be defined.
+fn apply_units() {
+    if let Ok(file) = load_units() {
+        let names: Vec<String> = file.units.keys().cloned().collect();
+        set_user_units(file.units);
+        for name in names.iter().filter(|n| lookup(n).is_none()) {
+            let message = format!("The unit {name} of the units file cannot be defined");
+            eprintln!("{}", Color::Red.paint(message));
+        }
+    }
+}
+
 fn main() {
-    let mut args: Args = env::args();
-    if args.len() > 1 {
-        args.nth(0);
-        let mut a = vec![];
-        args.for_each(|f| a.push(f));
-        let arg_final = a.join("");
+    let child = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .unwrap();
+    if child.join().is_err() {
+        exit(1);
+    }
+}
+
+fn evaluate(
+    line: &str,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Result<Parameters, CalcError> {
+    let lexed = lex(line.to_string());
+    let mut parser = init_calc_parser(&lexed).with_source(line);
+    parser
+        .parse()
+        .map_err(CalcError::ParseError)
+        .and_then(|p| interpret(&p, ram, functions))
+}
+
+/// The part of a script line before its `#` comment, a `#` between quotes
+/// being part of a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+/// Reports the error of a line, on stderr unless it is part of the JSON
+/// written to `out`.
+fn report_line_error(
+    out: &mut impl Write,
+    number: usize,
+    error: &CalcError,
+    text: &str,
+    format: OutputFormat,
+) {
+    if format == OutputFormat::Json {
+        let _ = writeln!(out, "{}", json_line(number, text, Err(error)));
+        return;
+    }
+    let message = match error {
+        CalcError::ParseError(_) => format!("line {number}:\n{}", format_error(error, text)),
+        e => format!("line {number}: {e}"),
+    };
+    eprintln!("{}", Color::Red.paint(message));
+}
+
+/// Writes the result of the `number`th line, `input`, outside of the REPL in
+/// the chosen format. Only JSON writes something for lines without a value.
+fn print_result(
+    out: &mut impl Write,
+    (number, input): (usize, &str),
+    result: &Parameters,
+    format: OutputFormat,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) {
+    let printed = match (format, result) {
+        (OutputFormat::Json, result) => json_line(number, input, Ok(result)),
+        (_, Parameters::Null) => return,
+        (OutputFormat::Plain, result) => result.pretty_print(Some(ram), Some(functions)),
+        (OutputFormat::Latex, result) => result.to_latex(),
+    };
+    let _ = writeln!(out, "{printed}");
+}
 
+/// Runs lines one by one with shared variables and functions, writing every
+/// result to `out`. Returns whether every line succeeded, stopping at the
+/// first error unless `keep_going` is set.
+fn run_lines(
+    lines: impl IntoIterator<Item = String>,
+    keep_going: bool,
+    format: OutputFormat,
+    out: &mut impl Write,
+) -> bool {
+    let mut ram = constants();
+    let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+    let mut outputs = 0;
+    let mut success = true;
+    for (number, line) in lines.into_iter().enumerate() {
+        let line = strip_comment(&line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        match evaluate(line, &mut ram, &mut functions) {
+            Ok(result) => {
+                if !matches!(result, Parameters::Identifier(_) | Parameters::Null) {
+                    outputs += 1;
+                    record_output(&mut ram, outputs, &result);
+                }
+                print_result(
+                    out,
+                    (number + 1, line),
+                    &result,
+                    format,
+                    &mut ram,
+                    &mut functions,
+                )
+            }
+            Err(e) => {
+                report_line_error(out, number + 1, &e, line, format);
+                success = false;
+                if !keep_going {
+                    break;
+                }
+            }
+        }
+    }
+    success
+}
 
-        if arg_final == "-h" || arg_final == "--help" {
+fn run_script(path: &str, keep_going: bool, format: OutputFormat) -> bool {
+    match fs::read_to_string(path) {
+        Ok(content) => run_lines(
+            content.lines().map(str::to_string),
+            keep_going,
+            format,
+            &mut io::stdout(),
+        ),
+        Err(e) => {
+            eprintln!("{}", Color::Red.paint(format!("Cannot read {path}: {e}")));
+            false
+        }
+    }
+}
 
-            println!("-----Help Calc-----");
-            println!("");
-            println!("mini-calc > launch the mini-calc REPL");
-            println!("mini-calc [arg] > compute non interactively");
-            println!("mini-calc -h || --help > open this help");
-            println!("");
-            println!("------Help Calc-----");
-            exit(0);
+fn stdin_lines() -> impl Iterator<Item = String> {
+    io::stdin().lock().lines().map_while(Result::ok)
+}
 
+/// Evaluates `expression` once per line, the value of the line being bound to
+/// `x`. With `reduce` the first value starts an accumulator `acc` which each
+/// following line replaces by `expression`, and only the final value is
+/// written. Lines are evaluated in a frame of their own, so that `x` and `acc`
+/// are only seen by `expression`.
+fn run_map(
+    lines: impl IntoIterator<Item = String>,
+    expression: &str,
+    reduce: bool,
+    keep_going: bool,
+    format: OutputFormat,
+    out: &mut impl Write,
+) -> bool {
+    let mut inputs = constants();
+    let mut ram = constants();
+    let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+    let mut accumulator: Option<Parameters> = None;
+    let mut success = true;
+    let mut last = 0;
+    for (number, line) in lines.into_iter().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        last = number + 1;
+        let value = match evaluate(line, &mut inputs, &mut HashMap::new()) {
+            Ok(value) => value,
+            Err(e) => {
+                report_line_error(out, number + 1, &e, line, format);
+                success = false;
+                if keep_going {
+                    continue;
+                }
+                break;
+            }
+        };
+        if reduce && accumulator.is_none() {
+            accumulator = Some(value);
+            continue;
+        }
+        ram.insert("x".to_string(), value);
+        if let Some(acc) = &accumulator {
+            ram.insert("acc".to_string(), acc.clone());
         }
+        match evaluate(expression, &mut ram, &mut functions) {
+            Ok(result) if reduce => accumulator = Some(result),
+            Ok(result) => print_result(
+                out,
+                (number + 1, line),
+                &result,
+                format,
+                &mut ram,
+                &mut functions,
+            ),
+            Err(e) => {
+                report_line_error(out, number + 1, &e, expression, format);
+                success = false;
+                if !keep_going {
+                    break;
+                }
+            }
+        }
+    }
+    // a reduction stopped by an error has no value
+    if let Some(acc) = accumulator.filter(|_| success || keep_going) {
+        print_result(
+            out,
+            (last, expression),
+            &acc,
+            format,
+            &mut ram,
+            &mut functions,
+        );
+    }
+    success
+}
+
+/// Applies the options which change settings, over the configuration.
+fn apply_options(cli: &Cli, loaded: &Loaded) {
+    if let Some(precision) = cli.precision {
+        set_decimal_digits(precision, loaded.trim_zeros);
+    }
+    set_degrees(cli.degrees);
+}
 
-        let lexed = lex(arg_final);
-        let mut parser = init_calc_parser(&lexed);
-        let parsed = parser.parse();
-        let mut ram: HashMap<String, Parameters> = HashMap::new();
-        let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
-        ram.insert("pi".to_string(), Parameters::Float(PI));
-        ram.insert("e".to_string(), Parameters::Float(E));
-        let result = interpret(&parsed, &mut ram, &mut functions);
-        if result != Parameters::Null {
-            println!(
-                "{}",
-                result.pretty_print(Some(&mut ram), Some(&mut functions))
-            )
+fn run() {
+    let mut cli = match parse_args(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(message) => {
+            eprintln!("{}", Color::Red.paint(message));
+            eprintln!("Try mini-calc --help");
+            exit(2);
         }
+    };
+    if cli.help {
+        print!("{}", help());
+        exit(0);
+    }
+    if cli.version {
+        println!("mini-calc {VERSION}");
         exit(0);
     }
+    if cli.no_config {
+        set_config_source(ConfigSource::Disabled);
+    } else if let Some(path) = &cli.config {
+        set_config_source(ConfigSource::Path(path.clone()));
+    }
+
+    let mut expressions = cli.expressions.clone();
+    let script = match (&cli.file, cli.positional.as_slice()) {
+        (Some(path), _) => Some(path.clone()),
+        (None, [path]) if expressions.is_empty() && Path::new(path).is_file() => Some(path.clone()),
+        (None, []) => None,
+        (None, positional) => {
+            // `mini-calc 2 - 3` is the expression 2 - 3
+            expressions.push(positional.join(" "));
+            None
+        }
+    };
+    let batch = script.is_some()
+        || !expressions.is_empty()
+        || cli.map.is_some()
+        || cli.reduce.is_some()
+        || !io::stdin().is_terminal();
+    if batch {
+        let loaded = load_config(load().unwrap_or_default());
+        apply_settings(&loaded);
+        apply_options(&cli, &loaded);
+        apply_units();
+
+        let (keep_going, format) = (cli.keep_going, cli.format);
+        let success = match (script, &cli.map, &cli.reduce) {
+            (Some(path), _, _) => run_script(&path, keep_going, format),
+            // the expressions of the command line are the lines of a script
+            _ if !expressions.is_empty() => {
+                run_lines(expressions, keep_going, format, &mut io::stdout())
+            }
+            (None, Some(expression), _) => run_map(
+                stdin_lines(),
+                expression,
+                false,
+                keep_going,
+                format,
+                &mut io::stdout(),
+            ),
+            (None, None, Some(expression)) => run_map(
+                stdin_lines(),
+                expression,
+                true,
+                keep_going,
+                format,
+                &mut io::stdout(),
+            ),
+            // piped input without an expression, every line is one
+            (None, None, None) => run_lines(stdin_lines(), keep_going, format, &mut io::stdout()),
+        };
+        exit(if success { 0 } else { 1 });
+    }
 
     let mut config = match load() {
         Ok(config) => config,
@@ -310,15 +951,26 @@ fn main() {
     };
 
     let mut loaded: Loaded = load_config(config.clone());
+    // display settings changed for this session only
+    let mut session = config.clone();
+    apply_settings(&loaded);
+    apply_options(&cli, &loaded);
+    apply_units();
     let message = &loaded.greeting_message;
-    println!("{}", message.to_string());
+    println!("{message}");
 
     let interface = Interface::new("calc").unwrap();
     let style = &loaded.clone().prompt_style;
     let mut text = &loaded.clone().prompt;
     let mut verbose = false;
-    let version: String = "v2.12.2".to_string();
+    let mut json_output = cli.format == OutputFormat::Json;
+    let version = VERSION;
     interface.set_completer(Arc::new(CalcCompleter));
+    let mut history = History::load(loaded.history_size, loaded.history_dedup);
+    interface.set_history_size(loaded.history_size);
+    for entry in history.entries() {
+        interface.add_history(entry.clone());
+    }
     interface
         .set_prompt(&format!(
             "\x01{prefix}\x02{text}\x01{suffix}\x02",
@@ -328,11 +980,25 @@ fn main() {
         ))
         .unwrap();
 
-    let mut ram: HashMap<String, Parameters> = HashMap::new();
+    let mut ram = constants();
     let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
-    ram.insert("pi".to_string(), Parameters::Float(PI));
-    ram.insert("e".to_string(), Parameters::Float(E));
-    while let ReadResult::Input(line) = interface.read_line().unwrap() {
+    let mut outputs = 0;
+    let mut evaluated = 0;
+    while let ReadResult::Input(input) = interface.read_line().unwrap() {
+        let line = match history_run(&input) {
+            None => input,
+            Some(number) => match history.get(number) {
+                Some(entry) => {
+                    println!("{entry}");
+                    entry.clone()
+                }
+                None => {
+                    let message = format!("There is no entry {number} in the history");
+                    println!("{}", Color::Red.paint(message));
+                    continue;
+                }
+            },
+        };
         match line.as_str().trim() {
             "info" => {
                 let message = loaded.general_color.paint(format!(" Calc {version} \n Author: Charlotte Thomas \n Written in Rust \n Repo: https://github.com/coco33920/calc\n"));
@@ -341,7 +1007,7 @@ fn main() {
             "exit" => break,
             "help" => {
                 let message = loaded.general_color.paint(format!(
-                    " Calc {version} Help \n > info : show infos \n > exit : exit the program \n > help : print this help \n > verbose : toggle the verbose \n > version : prints the version \n > config : root of the config \n"
+                    " Calc {version} Help \n > info : show infos \n > exit : exit the program \n > help : print this help \n > verbose : toggle the verbose \n > json : toggle the JSON output \n > version : prints the version \n > config : root of the config \n > display : show or change how results are printed for this session \n > history : list, search and run again earlier lines \n"
                 ));
                 println!("{}", message)
             }
@@ -355,13 +1021,26 @@ fn main() {
                 let message2 = Color::Red.paint(if verbose { "on" } else { "off" });
                 println!("{}{}", message, message2)
             }
+            "json" => {
+                json_output = !json_output;
+                let message = loaded.general_color.paint("You toggled the JSON output : ");
+                let message2 = Color::Red.paint(if json_output { "on" } else { "off" });
+                println!("{}{}", message, message2)
+            }
             str => {
                 if str.starts_with("config") {
                     let (s, q) = handle_config(&line, config.clone());
                     match q {
                         Some(q) => {
                             config = q.clone();
+                            session = q.clone();
                             loaded = load_config(q);
+                            apply_settings(&loaded);
+                            // a digit count set now replaces --precision
+                            if str.split_whitespace().nth(2) == Some("decimal_digits") {
+                                cli.precision = None;
+                            }
+                            apply_options(&cli, &loaded);
                             text = &loaded.prompt;
                             interface
                                 .set_prompt(&format!(
@@ -378,6 +1057,38 @@ fn main() {
                             print!("{m}");
                         }
                     }
+                } else if str.split_whitespace().next() == Some("history") {
+                    let mut words = str.split_whitespace().skip(1);
+                    let message = match words.next() {
+                        None => format_entries(
+                            history.entries().iter().enumerate().map(|(i, e)| (i + 1, e)),
+                        ),
+                        Some("search") => {
+                            let text = words.collect::<Vec<&str>>().join(" ");
+                            format_entries(history.search(&text).into_iter())
+                        }
+                        _ => " > history : list the history \n > history search <text> : list the entries containing text \n > history run <n> : evaluate the entry n again \n".to_string(),
+                    };
+                    print!("{}", loaded.general_color.paint(message));
+                } else if str.split_whitespace().next() == Some("display") {
+                    let mut words = str.split_whitespace().skip(1);
+                    let message = match words.next() {
+                        None => show_display(&session),
+                        Some(key) => match display_setting(&session, key, words.next()) {
+                            Ok((cfg, message)) => {
+                                if key == "decimal_digits" {
+                                    cli.precision = None;
+                                }
+                                let display = load_config(cfg.clone());
+                                apply_settings(&display);
+                                apply_options(&cli, &display);
+                                session = cfg;
+                                message
+                            }
+                            Err(message) => message,
+                        },
+                    };
+                    print!("{}", loaded.general_color.paint(message));
                 } else {
                     let a = lex(str.to_string());
                     let parser: &mut CalcParser = &mut parsing::parser::init_calc_parser(&a);
@@ -390,24 +1101,45 @@ fn main() {
                         println!()
                     }
 
-                    let result = interpret(&p, &mut ram, &mut functions);
-                    if result != Parameters::Null {
-                        println!(
+                    let result = p
+                        .map_err(CalcError::ParseError)
+                        .and_then(|p| interpret(&p, &mut ram, &mut functions));
+                    evaluated += 1;
+                    if let Ok(result) = &result {
+                        if !matches!(result, Parameters::Identifier(_) | Parameters::Null) {
+                            outputs += 1;
+                            record_output(&mut ram, outputs, result);
+                        }
+                    }
+                    match result {
+                        _ if json_output => {
+                            println!("{}", json_line(evaluated, str, result.as_ref()))
+                        }
+                        Ok(Parameters::Null) => (),
+                        Ok(result @ Parameters::Identifier(_)) => println!(
                             "{}",
                             result.pretty_print(Some(&mut ram), Some(&mut functions))
-                        )
+                        ),
+                        Ok(result) => {
+                            let printed = result.pretty_print(Some(&mut ram), Some(&mut functions));
+                            let separator = if printed.contains('\n') { "\n" } else { " " };
+                            println!("${outputs} ={separator}{printed}")
+                        }
+                        Err(e) => println!("{}", Color::Red.paint(format_error(&e, str))),
                     }
                 }
             }
         }
-        interface.add_history_unique(line);
+        remember(&interface, &mut history, &line);
     }
     exit(0);
 }
 
 struct CalcCompleter;
 
-static CMD: &[&str] = &["config", "exit", "verbose", "version", "help", "info"];
+static CMD: &[&str] = &[
+    "config", "display", "exit", "history", "verbose", "json", "version", "help", "info",
+];
 static CONFIG_CMD: &[&str] = &["reload", "reset", "set", "show"];
 static SET_CMD: &[&str] = &[
     "general_color",
@@ -415,7 +1147,21 @@ static SET_CMD: &[&str] = &[
     "greeting_message",
     "prompt",
     "prompt_color",
+    "recursion_limit",
+    "max_denominator",
+    "tolerance",
+    "complex_display",
+    "output_base",
+    "notation",
+    "decimal_digits",
+    "trim_zeros",
+    "fraction_display",
+    "thousands_separator",
+    "history_size",
+    "history_dedup",
 ];
+// the keys whose value is one of CMD_COLOR
+static COLOR_KEYS: &[&str] = &["general_color", "greeting_color", "prompt_color"];
 static CMD_COLOR: &[&str] = &[
     "black", "purple", "cyan", "blue", "red", "yellow", "green", "white",
 ];
@@ -468,7 +1214,7 @@ impl<Term: Terminal> Completer<Term> for CalcCompleter {
                         Some(co)
                     }
                     Some(c) => {
-                        if SET_CMD.contains(&c) {
+                        if COLOR_KEYS.contains(&c) {
                             let mut co = Vec::new();
                             for cmd in CMD_COLOR {
                                 if cmd.starts_with(word) {
@@ -487,3 +1233,121 @@ impl<Term: Terminal> Completer<Term> for CalcCompleter {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{run_lines, run_map, strip_comment};
+    use crate::configuration::cli::OutputFormat;
+
+    /// Runs `lines` as a script, returning its success and what it wrote.
+    fn run(lines: &[&str], keep_going: bool, format: OutputFormat) -> (bool, String) {
+        let mut out = Vec::new();
+        let ok = run_lines(
+            lines.iter().map(|l| l.to_string()),
+            keep_going,
+            format,
+            &mut out,
+        );
+        (ok, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_strip_comment() {
+        assert_eq!(strip_comment("x = 3 # three"), "x = 3 ");
+        assert_eq!(strip_comment("\"#1\" # label"), "\"#1\" ");
+        assert_eq!(strip_comment("# only a comment"), "");
+    }
+
+    #[test]
+    fn test_run_lines() {
+        let (ok, out) = run(
+            &["x = 2", "", "x + 1 # three", "ans * 2"],
+            false,
+            OutputFormat::Plain,
+        );
+        assert!(ok);
+        assert_eq!(out, " x = 2\n3\n6\n");
+    }
+
+    #[test]
+    fn test_run_lines_stops_at_first_error() {
+        assert_eq!(
+            run(&["1", "1/0", "2"], false, OutputFormat::Plain),
+            (false, "1\n".to_string())
+        );
+        assert_eq!(
+            run(&["$9", "2"], false, OutputFormat::Plain),
+            (false, String::new())
+        );
+    }
+
+    /// Maps or reduces `lines` with `expression`, returning its success and
+    /// what it wrote.
+    fn map(lines: &[&str], expression: &str, reduce: bool, keep_going: bool) -> (bool, String) {
+        let mut out = Vec::new();
+        let lines = lines.iter().map(|l| l.to_string());
+        let ok = run_map(
+            lines,
+            expression,
+            reduce,
+            keep_going,
+            OutputFormat::Plain,
+            &mut out,
+        );
+        (ok, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_run_map() {
+        let (ok, out) = map(&["1", "", "2/4", "3"], "x^2", false, false);
+        assert!(ok);
+        assert_eq!(out, "1\n1/4\n9\n");
+        assert_eq!(
+            map(&["1", "2", "3"], "acc + x", true, false),
+            (true, "6\n".to_string())
+        );
+        assert_eq!(map(&[], "acc + x", true, false), (true, String::new()));
+        assert_eq!(map(&[], "x", false, false), (true, String::new()));
+    }
+
+    #[test]
+    fn test_run_map_errors() {
+        assert_eq!(
+            map(&["1", "0", "2"], "1/x", false, false),
+            (false, "1\n".to_string())
+        );
+        assert_eq!(
+            map(&["1", "0", "2"], "1/x", false, true),
+            (false, "1\n1/2\n".to_string())
+        );
+        assert_eq!(
+            map(&["1", "1/0", "2"], "x", false, true),
+            (false, "1\n2\n".to_string())
+        );
+        assert_eq!(
+            map(&["1", "$5", "2"], "acc + x", true, false),
+            (false, String::new())
+        );
+        let (ok, out) = map(&["1", "$5", "2"], "acc + x", true, true);
+        assert_eq!((ok, out), (false, "3\n".to_string()));
+    }
+
+    #[test]
+    fn test_run_map_lines_do_not_see_x() {
+        let (ok, out) = map(&["2", "x", "acc"], "x", false, false);
+        assert!(ok);
+        assert_eq!(out, "2\nx\nacc\n");
+    }
+
+    #[test]
+    fn test_run_lines_keep_going() {
+        assert_eq!(
+            run(&["1", "1/0", "2"], true, OutputFormat::Plain),
+            (false, "1\n2\n".to_string())
+        );
+        let (ok, out) = run(&["1/0", "$1"], true, OutputFormat::Json);
+        assert!(!ok);
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.lines().all(|l| l.contains("\"error\"")));
+    }
+}
@@ -1,10 +1,20 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-use crate::exact_math::rationals::Rationals;
+use num_bigint::BigInt;
+use num_traits::Signed;
+
+use crate::exact_math::complex::Complex;
+use crate::exact_math::polynomial::Polynomial;
+use crate::exact_math::radicals::Radicals;
+use crate::exact_math::rationals::{format_rational, Rationals};
+use crate::exact_math::symbolic::Symbolic;
+use crate::exact_math::units::Quantity;
 use crate::lexing::token::{Operator, Token};
 use crate::parsing::ast::Ast::{Nil, Node};
 use crate::parsing::ast::Parameters::*;
+use crate::utils::float_utils::format_float;
+use crate::utils::integer_utils::{format_integer, group_thousands};
 use crate::utils::matrix_utils::transpose;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +25,11 @@ pub enum Parameters {
     Str(String),
     Identifier(String),
     Rational(Rationals),
+    Radical(Radicals),
+    Symbolic(Box<Symbolic>),
+    Polynomial(Polynomial),
+    Complex(Box<Complex>),
+    Quantity(Box<Quantity>),
     PlusOperation,
     MinusOperation,
     MultiplicationOperation,
@@ -30,6 +45,15 @@ pub enum Parameters {
     Assign,
     Null,
     ExpoOperation,
+    ConversionOperation,
+    ModuloOperation,
+    IntegerDivisionOperation,
+    LeftShiftOperation,
+    RightShiftOperation,
+    BitAndOperation,
+    BitOrOperation,
+    XorOperation,
+    BitNotOperation,
     Vector(Box<Vec<Ast>>),
     InterpreterVector(Box<Vec<Parameters>>),
 }
@@ -52,7 +76,7 @@ impl Display for Parameters {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Int(i) => write!(f, "{}", i),
-            Float(fl) => write!(f, "{:.10}", fl),
+            Float(fl) => write!(f, "{}", format_float(*fl)),
             Identifier(s) => write!(f, "{}", s),
             PlusOperation => write!(f, "+"),
             MinusOperation => write!(f, "-"),
@@ -61,6 +85,15 @@ impl Display for Parameters {
             Assign => write!(f, "="),
             Null => write!(f, ""),
             ExpoOperation => write!(f, "^"),
+            ConversionOperation => write!(f, "to"),
+            ModuloOperation => write!(f, "%"),
+            IntegerDivisionOperation => write!(f, "//"),
+            LeftShiftOperation => write!(f, "<<"),
+            RightShiftOperation => write!(f, ">>"),
+            BitAndOperation => write!(f, "&"),
+            BitOrOperation => write!(f, "|"),
+            XorOperation => write!(f, "xor"),
+            BitNotOperation => write!(f, "~"),
             GreaterOperation => write!(f, ">"),
             LesserOperation => write!(f, "<"),
             GreaterOrEqualOperation => write!(f, ">="),
@@ -74,6 +107,11 @@ impl Display for Parameters {
             InterpreterVector(a) => write!(f, "{:?}", a),
             Str(s) => write!(f, "{s}"),
             Rational(s) => write!(f, "{s}"),
+            Radical(s) => write!(f, "{s}"),
+            Symbolic(s) => write!(f, "{s}"),
+            Polynomial(p) => write!(f, "{p}"),
+            Complex(z) => write!(f, "{z}"),
+            Quantity(q) => write!(f, "{q}"),
         }
     }
 }
@@ -101,30 +139,16 @@ impl Display for Ast {
 impl Parameters {
     pub fn pretty_print(
         &self,
-        mut ram: Option<&mut HashMap<String, Parameters>>,
-        mut function: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+        ram: Option<&mut HashMap<String, Parameters>>,
+        function: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
     ) -> String {
         match self {
-            Identifier(s) => {
-                if s.starts_with("@") {
-                    match s.strip_prefix("@") {
-                        None => format!(""),
-                        Some(c) => format!("{c}"),
-                    }
-                } else {
-                    if ram == None {
-                        return self.to_string();
-                    } else {
-                        match ram.as_mut().unwrap().get(s) {
-                            None => "This variable is not initialized yet".to_string(),
-                            Some(t) => t.clone().pretty_print(
-                                Some(ram.as_mut().unwrap()),
-                                Some(function.as_mut().unwrap()),
-                            ),
-                        }
-                    }
-                }
-            }
+            // integers are printed in the output base
+            Int(i) => format_integer(&BigInt::from(*i)),
+            Rational(r) => format_rational(r),
+            Quantity(q) => format!("{} {}", q.value.pretty_print(None, None), q.unit_string()),
+            // messages are marked by a leading @, other names are printed as is
+            Identifier(s) => s.strip_prefix('@').unwrap_or(s).to_string(),
             InterpreterVector(lst) => {
                 let mut vec = Vec::new();
 
@@ -151,26 +175,25 @@ impl Parameters {
                 if !matrix {
                     format!("|{}|", vec.join(" "))
                 } else {
-                    let mut vss = Vec::new();
-                    let mut max_size = 0;
-                    vec.clone()
-                        .into_iter()
-                        .for_each(|x| vss.push(x[1..(x.len() - 1)].to_string()));
-                    vec.into_iter().for_each(|x| {
-                        if x.len() > max_size {
-                            max_size = x.len()
-                        }
-                    });
-
-                    let mut matrix = Vec::new();
-                    for el in vss.into_iter() {
-                        let mut col = Vec::new();
-                        let v = el.split_whitespace();
-                        for i in v {
-                            col.push(i.to_string());
-                        }
-                        matrix.push(col);
-                    }
+                    // cells are printed one by one, they may contain spaces
+                    let matrix: Vec<Vec<String>> = lst
+                        .iter()
+                        .map(|row| match row {
+                            InterpreterVector(r) => r
+                                .iter()
+                                .map(|x| {
+                                    x.pretty_print(
+                                        Some(&mut ram.as_deref().unwrap().clone()),
+                                        Some(&mut function.as_deref().unwrap().clone()),
+                                    )
+                                })
+                                .collect(),
+                            x => vec![x.pretty_print(
+                                Some(&mut ram.as_deref().unwrap().clone()),
+                                Some(&mut function.as_deref().unwrap().clone()),
+                            )],
+                        })
+                        .collect();
 
                     let mut final_v = Vec::new();
                     let cols = transpose(matrix.clone());
@@ -178,16 +201,17 @@ impl Parameters {
                     for x in cols {
                         let mut max_size = 0;
                         x.clone().into_iter().for_each(|y| {
-                            if y.len() > max_size {
-                                max_size = y.len()
+                            if y.chars().count() > max_size {
+                                max_size = y.chars().count()
                             }
                         });
 
                         let mut new_line = Vec::new();
 
                         for y in x.clone() {
-                            let vs = vec![" "; (max_size - y.len()) / 2];
-                            let vs2 = vec![" "; (max_size - y.len()) - vs.len()];
+                            let width = y.chars().count();
+                            let vs = vec![" "; (max_size - width) / 2];
+                            let vs2 = vec![" "; (max_size - width) - vs.len()];
                             new_line.push(format!("{}{}{}", vs2.join(""), y, vs.join("")));
                         }
 
@@ -202,8 +226,8 @@ impl Parameters {
                     vfinal.into_iter().for_each(|x| v_final.push(x.join(" ")));
 
                     v_final.clone().into_iter().for_each(|x| {
-                        if x.len() > max_length {
-                            max_length = x.len()
+                        if x.chars().count() > max_length {
+                            max_length = x.chars().count()
                         }
                     });
 
@@ -220,19 +244,112 @@ impl Parameters {
             _ => format!("{self}"),
         }
     }
+
+    /// The value as a LaTeX math expression, fractions as `\frac`, vectors
+    /// and matrices as `pmatrix`.
+    pub fn to_latex(&self) -> String {
+        match self {
+            Int(i) => format_integer(&BigInt::from(*i)),
+            Rational(r) => {
+                let r = r.clone().reduce();
+                if r.is_integer() {
+                    return format_rational(&r);
+                }
+                let sign = if r.over.is_negative() { "-" } else { "" };
+                format!(
+                    "{sign}\\frac{{{}}}{{{}}}",
+                    group_thousands(&r.over.magnitude().to_string()),
+                    group_thousands(&r.under.to_string())
+                )
+            }
+            Float(f) => match format_float(*f).split_once('e') {
+                Some((mantissa, exponent)) => format!("{mantissa} \\times 10^{{{exponent}}}"),
+                None => format_float(*f),
+            },
+            Bool(b) => format!("\\mathrm{{{b}}}"),
+            Str(s) => format!("\\text{{{s}}}"),
+            Identifier(s) => match s.strip_prefix('@') {
+                Some(message) => format!("\\text{{{message}}}"),
+                None => s.clone(),
+            },
+            Quantity(q) => format!(
+                "{} \\, \\mathrm{{{}}}",
+                q.value.to_latex(),
+                latex_symbols(&q.unit_string())
+            ),
+            InterpreterVector(lst) => {
+                let row = |r: &[Parameters]| {
+                    r.iter()
+                        .map(|x| x.to_latex())
+                        .collect::<Vec<String>>()
+                        .join(" & ")
+                };
+                let rows: Vec<String> = match lst.first() {
+                    Some(InterpreterVector(_)) => lst
+                        .iter()
+                        .map(|r| match r {
+                            InterpreterVector(r) => row(r),
+                            x => x.to_latex(),
+                        })
+                        .collect(),
+                    _ => vec![row(lst)],
+                };
+                format!(
+                    "\\begin{{pmatrix}} {} \\end{{pmatrix}}",
+                    rows.join(" \\\\ ")
+                )
+            }
+            _ => latex_symbols(&self.to_string()),
+        }
+    }
+}
+
+/// Rewrites the symbols of a printed value, `2√3*x^10`, in LaTeX.
+fn latex_symbols(s: &str) -> String {
+    let mut latex = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '√' | '^' => {
+                let mut argument = String::new();
+                if chars.peek() == Some(&'-') {
+                    argument.push('-');
+                    chars.next();
+                }
+                while let Some(d) = chars.next_if(|d| d.is_alphanumeric() || *d == '.') {
+                    argument.push(d);
+                }
+                if c == '√' {
+                    latex += &format!("\\sqrt{{{argument}}}");
+                } else {
+                    latex += &format!("^{{{argument}}}");
+                }
+            }
+            '*' => latex += " \\cdot ",
+            '∠' => latex += " \\angle ",
+            c => latex.push(c),
+        }
+    }
+    latex
 }
 
 pub fn token_to_parameter(token: Token) -> Parameters {
     match token {
         Token::INT(i) => Int(i),
+        Token::BigInt(i) => Rational(Rationals::from_bigint(1.into(), i)),
+        Token::Decimal(over, under) => Rational(Rationals::from_bigint(under, over).reduce()),
         Token::FLOAT(f) => Float(f),
         Token::IDENTIFIER(s) => Identifier(s),
         Token::OPE(Operator::PLUS) => PlusOperation,
         Token::OPE(Operator::MINUS) => MinusOperation,
         Token::OPE(Operator::MULTIPLICATION) => MultiplicationOperation,
+        Token::OPE(Operator::ImplicitMultiplication) => MultiplicationOperation,
         Token::OPE(Operator::DIVIDE) => DivideOperation,
+        Token::OPE(Operator::IntegerDivision) => IntegerDivisionOperation,
+        Token::OPE(Operator::Modulo) => ModuloOperation,
         Token::OPE(Operator::EXPO) => ExpoOperation,
         Token::OPE(Operator::EQUALITY) => Equal,
+        Token::OPE(Operator::Conversion) => ConversionOperation,
         Token::OPE(Operator::GreaterOrEqual) => GreaterOrEqualOperation,
         Token::OPE(Operator::GreaterThan) => GreaterOperation,
         Token::OPE(Operator::LesserThan) => LesserOperation,
@@ -240,6 +357,12 @@ pub fn token_to_parameter(token: Token) -> Parameters {
         Token::OPE(Operator::NOT) => Not,
         Token::OPE(Operator::Or) => OrOperation,
         Token::OPE(Operator::And) => AndOperation,
+        Token::OPE(Operator::BitAnd) => BitAndOperation,
+        Token::OPE(Operator::BitOr) => BitOrOperation,
+        Token::OPE(Operator::Xor) => XorOperation,
+        Token::OPE(Operator::BitNot) => BitNotOperation,
+        Token::OPE(Operator::LeftShift) => LeftShiftOperation,
+        Token::OPE(Operator::RightShift) => RightShiftOperation,
         Token::EQUAL => Assign,
         Token::BOOL(b) => Bool(b),
         Token::RBRACKET => Vector(Box::from(Vec::new())),
@@ -258,11 +381,33 @@ impl Ast {
 }
 
 impl Parameters {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Int(_) => "an integer",
+            Rational(r) if r.is_integer() => "an integer",
+            Float(_) => "a float",
+            Bool(_) => "a boolean",
+            Str(_) => "a string",
+            Identifier(_) => "an identifier",
+            Rational(_) => "a rational",
+            Radical(_) => "a radical",
+            Symbolic(_) => "a symbolic expression",
+            Polynomial(_) => "a polynomial",
+            Complex(_) => "a complex number",
+            Quantity(_) => "a quantity",
+            Null => "nothing",
+            Vector(_) | InterpreterVector(_) => "a vector",
+            _ => "an operator",
+        }
+    }
+
     pub fn abs(self, ram: Option<&HashMap<String, Parameters>>) -> Parameters {
         match self {
             Parameters::Int(i) => Parameters::Int(i.abs()),
             Parameters::Float(f) => Parameters::Float(f.abs()),
             Parameters::Rational(r) => Parameters::Rational(r.abs()),
+            Parameters::Radical(r) if r.approx() < 0.0 => Parameters::Radical(-r),
+            Parameters::Radical(r) => Parameters::Radical(r),
             Parameters::Identifier(s) => match ram {
                 None => Parameters::Null,
                 Some(t) => {
@@ -280,6 +425,9 @@ impl Parameters {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
+    use crate::exact_math::rationals::{set_fraction_display, FractionDisplay, Rationals};
     use crate::parsing::ast::{Ast, Parameters};
 
     #[test]
@@ -292,4 +440,42 @@ mod test {
         let result = Ast::new(Parameters::Int(2));
         assert_eq!(result, expected)
     }
+
+    #[test]
+    pub fn test_pretty_print_mixed_matrix() {
+        set_fraction_display(FractionDisplay::Mixed);
+        let row = |a: i64, b: i64| {
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Rational(Rationals::new(2, a)),
+                Parameters::Int(b),
+            ]))
+        };
+        let matrix = Parameters::InterpreterVector(Box::from(vec![row(3, 1), row(1, 10)]));
+        let expected = "+--------+\n|1 1/2  1|\n| 1/2  10|\n+--------+";
+        let result = matrix.pretty_print(Some(&mut HashMap::new()), Some(&mut HashMap::new()));
+        set_fraction_display(FractionDisplay::Fraction);
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    pub fn test_latex() {
+        let matrix = Parameters::InterpreterVector(Box::from(vec![
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Int(1),
+                Parameters::Rational(Rationals::new(3, -2)),
+            ])),
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Bool(true),
+                Parameters::Int(4),
+            ])),
+        ]));
+        assert_eq!(
+            matrix.to_latex(),
+            "\\begin{pmatrix} 1 & -\\frac{2}{3} \\\\ \\mathrm{true} & 4 \\end{pmatrix}"
+        );
+        assert_eq!(
+            super::latex_symbols("2√3*x^-10"),
+            "2\\sqrt{3} \\cdot x^{-10}"
+        );
+    }
 }
@@ -1,9 +1,10 @@
-use crate::lexing::token::{Token, TokenType};
+use crate::errors::parse_error::ParseError;
+use crate::lexing::token::{Operator, Precedence, Token, TokenType};
 use crate::parsing::ast::{token_to_parameter, Ast};
 use crate::parsing::parser::CalcParser;
 
 pub trait PrefixParselet {
-    fn parse(&self, parser: &mut CalcParser, token: Token) -> Ast;
+    fn parse(&self, parser: &mut CalcParser, token: Token) -> Result<Ast, ParseError>;
 }
 
 #[derive(Clone)]
@@ -12,9 +13,6 @@ pub struct ValueParselet {}
 #[derive(Clone)]
 pub struct OperatorPrefixParselet {}
 
-#[derive(Clone)]
-pub struct NullParselet {}
-
 #[derive(Clone)]
 pub struct GroupParselet {}
 
@@ -25,80 +23,81 @@ pub struct VecParselet {}
 pub struct QuoteParselet {}
 
 impl PrefixParselet for ValueParselet {
-    fn parse(&self, _parser: &mut CalcParser, token: Token) -> Ast {
-        Ast::Node {
+    fn parse(&self, _parser: &mut CalcParser, token: Token) -> Result<Ast, ParseError> {
+        Ok(Ast::Node {
             value: token_to_parameter(token),
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
-        }
+        })
     }
 }
 
 impl PrefixParselet for OperatorPrefixParselet {
-    fn parse(&self, parser: &mut CalcParser, token: Token) -> Ast {
-        let operand = parser.parse_expression_empty();
-        Ast::Node {
+    fn parse(&self, parser: &mut CalcParser, token: Token) -> Result<Ast, ParseError> {
+        // a sign binds tighter than a product but looser than a power, so
+        // -2^2 is -(2^2) and -3+2 is (-3)+2
+        let precedence = match token {
+            Token::OPE(Operator::NOT) => Precedence::Conditional,
+            _ => Precedence::Product,
+        };
+        let operand = parser.parse_expression(precedence as i64)?;
+        Ok(Ast::Node {
             value: token_to_parameter(token),
             left: Box::from(operand),
             right: Box::from(Ast::Nil),
-        }
-    }
-}
-
-impl PrefixParselet for NullParselet {
-    fn parse(&self, _parser: &mut CalcParser, _token: Token) -> Ast {
-        Ast::Nil
+        })
     }
 }
 
 impl PrefixParselet for GroupParselet {
-    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
-        let expression = parser.parse_expression_empty();
-        parser.consume_expected(TokenType::RPAR);
-        expression
+    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Result<Ast, ParseError> {
+        let opened = parser.last_span();
+        let expression = parser.parse_expression_empty()?;
+        parser.consume_expected(TokenType::RPAR, opened)?;
+        Ok(expression)
     }
 }
 
 impl PrefixParselet for VecParselet {
-    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
+    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Result<Ast, ParseError> {
+        let opened = parser.last_span();
         let mut vec: Vec<Ast> = Vec::new();
 
         if !parser.match_token(TokenType::RBRACKET) {
-            vec.push(parser.parse_expression_empty());
+            vec.push(parser.parse_expression_empty()?);
             while parser.match_token(TokenType::COMMA) {
                 parser.consume();
-                vec.push(parser.parse_expression_empty());
+                vec.push(parser.parse_expression_empty()?);
             }
-            parser.consume_expected(TokenType::RBRACKET);
         }
+        parser.consume_expected(TokenType::RBRACKET, opened)?;
 
-        Ast::Node {
+        Ok(Ast::Node {
             value: crate::parsing::ast::Parameters::Vector(Box::from(vec)),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
-        }
+        })
     }
 }
 
 impl PrefixParselet for QuoteParselet {
-    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
+    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Result<Ast, ParseError> {
+        let opened = parser.last_span();
         let mut str: String = String::new();
 
-        if !parser.match_token(TokenType::QUOTE) {
-            while !parser.match_token(TokenType::QUOTE) {
-                match parser.consume() {
-                    Token::IDENTIFIER(s) => str = str + &s.to_string(),
+        while !parser.match_token(TokenType::QUOTE) && !parser.match_token(TokenType::Null) {
+            match parser.consume() {
+                Token::IDENTIFIER(s) => str = str + &s.to_string(),
 
-                    t => str = str + &t.to_string(),
-                }
+                t => str = str + &t.to_string(),
             }
-            parser.consume_expected(TokenType::QUOTE);
         }
+        parser.consume_expected(TokenType::QUOTE, opened)?;
 
-        Ast::Node {
+        Ok(Ast::Node {
             value: crate::parsing::ast::Parameters::Str(str.trim().to_string()),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
-        }
+        })
     }
 }
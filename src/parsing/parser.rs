@@ -1,55 +1,116 @@
 use core::slice::Iter;
 
+use crate::errors::parse_error::ParseError;
 use crate::lexing::token::Token::*;
-use crate::lexing::token::{Precedence, Token, TokenType};
+use crate::lexing::token::{Precedence, Span, SpannedToken, Token, TokenType};
 use crate::parsing::ast::Ast;
 use crate::parsing::parselets::infix_parselet::{
-    AssignParselet, CallParselet, InfixParselet, NullParset, OperatorInfixParselet,
+    AssignParselet, CallParselet, InfixParselet, OperatorInfixParselet,
 };
 use crate::parsing::parselets::prefix_parselet::{
-    GroupParselet, NullParselet, OperatorPrefixParselet, PrefixParselet, ValueParselet,
+    GroupParselet, OperatorPrefixParselet, PrefixParselet, ValueParselet,
 };
 
 use super::parselets::prefix_parselet::{QuoteParselet, VecParselet};
 
 #[derive(Clone)]
 pub struct CalcParser<'a> {
-    tokens: Iter<'a, Token>,
-    read: Vec<Token>,
+    tokens: Iter<'a, SpannedToken>,
+    read: Vec<SpannedToken>,
+    last: Span,
+    eof: Span,
+    source: Option<&'a str>,
 }
 
-pub fn init_calc_parser(input: &Vec<Token>) -> CalcParser {
+pub fn init_calc_parser(input: &Vec<SpannedToken>) -> CalcParser<'_> {
+    let end = input.last().map_or(0, |t| t.span.end);
     CalcParser {
         tokens: input.iter(),
         read: Vec::new(),
+        last: Span { start: 0, end: 0 },
+        eof: Span {
+            start: end,
+            end: end + 1,
+        },
+        source: None,
     }
 }
 
-impl CalcParser<'_> {
-    pub fn parse(&mut self) -> Ast {
-        self.parse_expression_empty()
+impl<'a> CalcParser<'a> {
+    /// Quotes tokens in error messages as they are written in `source`, the
+    /// line the tokens were lexed from.
+    pub fn with_source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
     }
-    pub fn parse_expression(&mut self, precedence: i64) -> Ast {
+
+    /// The text of `token` as written, `.2` rather than the `2/10` it stands
+    /// for, or its display when the source is not known.
+    fn written(&self, token: &Token, span: Span) -> String {
+        match self.source.and_then(|s| s.get(span.start..span.end)) {
+            Some(text) if !text.is_empty() => text.to_string(),
+            _ => token.to_string(),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Ast, ParseError> {
+        // what the lexer could not read is reported first, wherever it is
+        if let Some(t) = self.tokens.clone().find(|t| matches!(t.token, Error(_))) {
+            return Err(ParseError::new(t.token.to_string(), t.span));
+        }
+        if self.look_ahead(0) == Null {
+            return Ok(Ast::Nil);
+        }
+        let ast = self.parse_expression_empty()?;
+        match self.look_ahead(0) {
+            Null => Ok(ast),
+            t => {
+                let span = self.peek_span();
+                Err(ParseError::new(
+                    format!(
+                        "unexpected `{}` after the end of the expression",
+                        self.written(&t, span)
+                    ),
+                    span,
+                ))
+            }
+        }
+    }
+
+    pub fn parse_expression(&mut self, precedence: i64) -> Result<Ast, ParseError> {
         let mut token = self.consume();
         let prefix = self
             .clone()
             .get_prefix_parselet(token.clone().to_token_type());
 
-        let mut left = prefix.unwrap().parse(self, token.clone());
+        let mut left = match prefix {
+            Some(prefix) => prefix.parse(self, token.clone())?,
+            None => {
+                let found = match token {
+                    Null => "end of input".to_string(),
+                    t => format!("`{}`", self.written(&t, self.last)),
+                };
+                return Err(ParseError::new(
+                    format!("expected an expression, found {found}"),
+                    self.last,
+                ));
+            }
+        };
         while precedence < self.get_precedence() {
             token = self.consume();
             let parser = self
                 .clone()
                 .get_infix_parselet(token.clone().to_token_type())
                 .unwrap();
-            left = parser.parse(self, &left, token);
+            left = parser.parse(self, &left, token)?;
         }
-        left
+        Ok(left)
     }
 
-    pub fn parse_expression_empty(&mut self) -> Ast {
+    pub fn parse_expression_empty(&mut self) -> Result<Ast, ParseError> {
         self.parse_expression(0)
     }
+
     fn look_ahead(&mut self, distance: usize) -> Token {
         while distance >= self.read.len() {
             match self.tokens.next() {
@@ -59,15 +120,33 @@ impl CalcParser<'_> {
         }
         match self.read.get(distance) {
             None => Null,
-            Some(t) => t.clone(),
+            Some(t) => t.token.clone(),
+        }
+    }
+
+    /// Span of the next token, or just past the end of the input.
+    fn peek_span(&mut self) -> Span {
+        self.look_ahead(0);
+        match self.read.first() {
+            None => self.eof,
+            Some(t) => t.span,
         }
     }
+
+    /// Span of the last consumed token.
+    pub fn last_span(&self) -> Span {
+        self.last
+    }
+
     pub fn consume(&mut self) -> Token {
         self.look_ahead(0);
         if self.read.len() == 0 {
+            self.last = self.eof;
             return Null;
         }
-        self.read.remove(0)
+        let t = self.read.remove(0);
+        self.last = t.span;
+        t.token
     }
 
     pub fn match_token(&mut self, expected: TokenType) -> bool {
@@ -78,21 +157,26 @@ impl CalcParser<'_> {
         return true;
     }
 
-    pub fn consume_expected(&mut self, expected: TokenType) -> Token {
-        self.look_ahead(0);
-        if self.read.len() == 0 {
-            return Null;
-        }
-        match self.read.remove(0) {
-            t => {
-                if t.to_token_type() == expected {
-                    t
-                } else {
-                    println!("error!");
-                    Null
-                }
-            }
+    /// Consumes the delimiter closing the one opened at `opened`.
+    pub fn consume_expected(
+        &mut self,
+        expected: TokenType,
+        opened: Span,
+    ) -> Result<Token, ParseError> {
+        let (open, close) = match expected {
+            TokenType::RPAR => ("(", ")"),
+            TokenType::RBRACKET => ("[", "]"),
+            TokenType::QUOTE => ("\"", "\""),
+            _ => ("", ""),
+        };
+        if self.match_token(expected) {
+            return Ok(self.consume());
         }
+        Err(ParseError::unclosed(
+            format!("expected `{close}` to close `{open}` opened here"),
+            self.peek_span(),
+            opened,
+        ))
     }
 
     fn get_precedence(&mut self) -> i64 {
@@ -109,59 +193,95 @@ impl CalcParser<'_> {
         match token_type {
             TokenType::PLUS => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::SUM as i64),
+                precedence: (Precedence::Sum as i64),
             })),
             TokenType::MINUS => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::MINUS as i64),
+                precedence: (Precedence::Sum as i64),
             })),
             TokenType::MULTIPLICATION => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::PRODUCT as i64),
+                precedence: (Precedence::Product as i64),
             })),
             TokenType::DIVIDE => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::DIVIDE as i64),
+                precedence: (Precedence::Product as i64),
+            })),
+            TokenType::IntegerDivision => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Product as i64),
+            })),
+            TokenType::Modulo => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Product as i64),
+            })),
+            TokenType::LeftShift => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Shift as i64),
+            })),
+            TokenType::RightShift => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Shift as i64),
+            })),
+            TokenType::BitAnd => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::BitAnd as i64),
+            })),
+            TokenType::BitOr => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::BitOr as i64),
+            })),
+            TokenType::Xor => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Xor as i64),
+            })),
+            TokenType::ImplicitMultiplication => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Implicit as i64),
+            })),
+            TokenType::Conversion => Some(Box::from(OperatorInfixParselet {
+                is_right: false,
+                precedence: (Precedence::Conversion as i64),
             })),
             TokenType::EQUAL => Some(Box::from(AssignParselet {})),
             TokenType::EXPO => Some(Box::from(OperatorInfixParselet {
-                is_right: false,
-                precedence: (Precedence::EXPONENT as i64),
+                is_right: true,
+                precedence: (Precedence::Exponent as i64),
             })),
             TokenType::LPAR => Some(Box::from(CallParselet {})),
             TokenType::NOT => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Conditional as i64),
             })),
             TokenType::EQUALITY => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Conditional as i64),
             })),
             TokenType::LESSER => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Conditional as i64),
             })),
             TokenType::LESSEREQ => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Conditional as i64),
             })),
             TokenType::GREATER => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Conditional as i64),
             })),
             TokenType::GREATEREQ => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Conditional as i64),
             })),
             TokenType::OR => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::Or as i64),
             })),
             TokenType::AND => Some(Box::from(OperatorInfixParselet {
                 is_right: false,
-                precedence: (Precedence::CONDITIONAL as i64),
+                precedence: (Precedence::And as i64),
             })),
-            _ => Some(Box::from(NullParset {})),
+            _ => None,
         }
     }
 
@@ -169,29 +289,25 @@ impl CalcParser<'_> {
         match token_type {
             TokenType::PLUS => Some(Box::from(OperatorPrefixParselet {})),
             TokenType::MINUS => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::MULTIPLICATION => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::DIVIDE => Some(Box::from(OperatorPrefixParselet {})),
             TokenType::IDENTIFIER => Some(Box::from(ValueParselet {})),
             TokenType::INT => Some(Box::from(ValueParselet {})),
             TokenType::FLOAT => Some(Box::from(ValueParselet {})),
             TokenType::BOOL => Some(Box::from(ValueParselet {})),
             TokenType::LPAR => Some(Box::from(GroupParselet {})),
             TokenType::NOT => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::EQUALITY => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::LESSER => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::LESSEREQ => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::GREATER => Some(Box::from(OperatorPrefixParselet {})),
-            TokenType::GREATEREQ => Some(Box::from(OperatorPrefixParselet {})),
+            TokenType::BitNot => Some(Box::from(OperatorPrefixParselet {})),
             TokenType::LBRACKET => Some(Box::from(VecParselet {})),
             TokenType::QUOTE => Some(Box::from(QuoteParselet {})),
-            _ => Some(Box::from(NullParselet {})),
+            _ => None,
         }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::errors::parse_error::ParseError;
     use crate::lexing::lexer::lex;
+    use crate::lexing::token::Span;
     use crate::parsing::ast::Parameters::{
         DivideOperation, MultiplicationOperation, PlusOperation,
     };
@@ -203,7 +319,7 @@ mod test {
         let b = lex("".to_string());
         let parser: &mut CalcParser = &mut init_calc_parser(&b);
         let expected = Ast::Nil;
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected);
     }
 
@@ -217,7 +333,7 @@ mod test {
             right: Box::new(Ast::Nil),
         };
 
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected);
     }
 
@@ -230,7 +346,7 @@ mod test {
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -247,7 +363,7 @@ mod test {
             }),
             right: Box::from(Ast::new(Parameters::Int(1))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -260,7 +376,7 @@ mod test {
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -273,7 +389,7 @@ mod test {
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -290,7 +406,7 @@ mod test {
             }),
             right: Box::from(Ast::new(Parameters::Int(2))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -307,7 +423,7 @@ mod test {
             }),
             right: Box::from(Ast::new(Parameters::Int(2))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -320,7 +436,7 @@ mod test {
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -333,7 +449,33 @@ mod test {
             left: Box::new(Ast::new(Parameters::Bool(false))),
             right: Box::new(Ast::new(Parameters::Bool(true))),
         };
-        assert_eq!(parser.parse(), expected);
+        assert_eq!(parser.parse(), Ok(expected));
+    }
+
+    #[test]
+    pub fn test_logic_precedence() {
+        let b = lex("n == 0 || 1/n > 1 && true".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let node = |value, left, right| Ast::Node {
+            value,
+            left: Box::new(left),
+            right: Box::new(right),
+        };
+        let n = || Ast::new(Parameters::Identifier("n".to_string()));
+        let expected = node(
+            Parameters::OrOperation,
+            node(Parameters::Equal, n(), Ast::new(Parameters::Int(0))),
+            node(
+                Parameters::AndOperation,
+                node(
+                    Parameters::GreaterOperation,
+                    node(DivideOperation, Ast::new(Parameters::Int(1)), n()),
+                    Ast::new(Parameters::Int(1)),
+                ),
+                Ast::new(Parameters::Bool(true)),
+            ),
+        );
+        assert_eq!(parser.parse(), Ok(expected));
     }
 
     #[test]
@@ -345,7 +487,7 @@ mod test {
             left: Box::new(Ast::new(Parameters::Identifier("i".to_string()))),
             right: Box::new(Ast::new(Parameters::Int(1))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected);
     }
 
@@ -362,7 +504,7 @@ mod test {
                 right: Box::new(Ast::new(Parameters::Int(1))),
             }),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -383,7 +525,7 @@ mod test {
                 }),
             }),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -400,7 +542,7 @@ mod test {
                 right: Box::new(Ast::new(Parameters::Int(1))),
             }),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -422,7 +564,7 @@ mod test {
                 right: Box::from(Ast::new(Parameters::Int(1))),
             }),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -439,7 +581,7 @@ mod test {
                 right: Box::from(Ast::new(Parameters::Int(2))),
             }),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -456,7 +598,45 @@ mod test {
             }),
             right: Box::from(Ast::new(Parameters::Int(1))),
         };
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    pub fn test_minus_left_associative() {
+        let b = lex("5-1+1".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = Ast::Node {
+            value: Parameters::PlusOperation,
+            left: Box::from(Ast::Node {
+                value: Parameters::MinusOperation,
+                left: Box::from(Ast::new(Parameters::Int(5))),
+                right: Box::from(Ast::new(Parameters::Int(1))),
+            }),
+            right: Box::from(Ast::new(Parameters::Int(1))),
+        };
+        let result = parser.parse().unwrap();
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    pub fn test_prefix_minus_priority() {
+        let b = lex("-2^2+1".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = Ast::Node {
+            value: Parameters::PlusOperation,
+            left: Box::from(Ast::Node {
+                value: Parameters::MinusOperation,
+                left: Box::from(Ast::Node {
+                    value: Parameters::ExpoOperation,
+                    left: Box::from(Ast::new(Parameters::Int(2))),
+                    right: Box::from(Ast::new(Parameters::Int(2))),
+                }),
+                right: Box::from(Ast::Nil),
+            }),
+            right: Box::from(Ast::new(Parameters::Int(1))),
+        };
+        let result = parser.parse().unwrap();
         assert_eq!(result, expected)
     }
 
@@ -469,7 +649,7 @@ mod test {
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
         };
-        assert_eq!(parser.parse(), expected);
+        assert_eq!(parser.parse(), Ok(expected));
     }
 
     #[test]
@@ -481,6 +661,74 @@ mod test {
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
         };
-        assert_eq!(parser.parse(), expected);
+        assert_eq!(parser.parse(), Ok(expected));
+    }
+
+    #[test]
+    pub fn test_parse_garbage() {
+        let b = lex("2 + * )".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = ParseError::new(
+            "expected an expression, found `*`".to_string(),
+            Span { start: 4, end: 5 },
+        );
+        assert_eq!(parser.parse(), Err(expected));
+    }
+
+    #[test]
+    pub fn test_parse_unclosed_parenthesis() {
+        let b = lex("1+(2*3".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = ParseError::unclosed(
+            "expected `)` to close `(` opened here".to_string(),
+            Span { start: 6, end: 7 },
+            Span { start: 2, end: 3 },
+        );
+        assert_eq!(parser.parse(), Err(expected));
+    }
+
+    #[test]
+    pub fn test_parse_unclosed_call() {
+        let b = lex("f(1,2 3".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = ParseError::unclosed(
+            "expected `)` to close `(` opened here".to_string(),
+            Span { start: 6, end: 7 },
+            Span { start: 1, end: 2 },
+        );
+        assert_eq!(parser.parse(), Err(expected));
+    }
+
+    #[test]
+    pub fn test_parse_unexpected_character() {
+        let b = lex("1 @ 2".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = ParseError::new(
+            "unexpected character `@`".to_string(),
+            Span { start: 2, end: 3 },
+        );
+        assert_eq!(parser.parse(), Err(expected));
+    }
+
+    #[test]
+    pub fn test_parse_trailing_token() {
+        let b = lex("1+1)".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = ParseError::new(
+            "unexpected `)` after the end of the expression".to_string(),
+            Span { start: 3, end: 4 },
+        );
+        assert_eq!(parser.parse(), Err(expected));
+    }
+
+    #[test]
+    pub fn test_parse_error_quotes_source() {
+        let b = lex("1..2".to_string());
+        let parser = &mut init_calc_parser(&b).with_source("1..2");
+        let expected = ParseError::new(
+            "unexpected `.2` after the end of the expression".to_string(),
+            Span { start: 2, end: 4 },
+        );
+        assert_eq!(parser.parse(), Err(expected));
     }
 }
@@ -0,0 +1,186 @@
+use std::cell::Cell;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Signed, Zero};
+
+use crate::exact_math::rationals::Rationals;
+use crate::utils::integer_utils::group_thousands;
+
+pub const DEFAULT_DECIMAL_DIGITS: usize = 10;
+pub const MAX_DECIMAL_DIGITS: usize = 100;
+
+/// How float results are written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notation {
+    /// Fixed point, scientific for values too large or too small to read
+    Auto,
+    Fixed,
+    Scientific,
+    /// Scientific with an exponent multiple of three
+    Engineering,
+}
+
+impl Notation {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Notation::Auto),
+            "fixed" => Some(Notation::Fixed),
+            "scientific" => Some(Notation::Scientific),
+            "engineering" => Some(Notation::Engineering),
+            _ => None,
+        }
+    }
+}
+
+thread_local! {
+    static NOTATION: Cell<Notation> = const { Cell::new(Notation::Auto) };
+    static DIGITS: Cell<usize> = const { Cell::new(DEFAULT_DECIMAL_DIGITS) };
+    static TRIM_ZEROS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Chooses the notation float results are printed in.
+pub fn set_notation(notation: Notation) {
+    NOTATION.with(|n| n.set(notation));
+}
+
+/// Sets how many digits are written after the decimal point and whether the
+/// trailing zeros among them are dropped.
+pub fn set_decimal_digits(digits: usize, trim_zeros: bool) {
+    DIGITS.with(|d| d.set(digits.min(MAX_DECIMAL_DIGITS)));
+    TRIM_ZEROS.with(|t| t.set(trim_zeros));
+}
+
+fn digits() -> usize {
+    DIGITS.with(|d| d.get())
+}
+
+/// Joins the parts of a decimal number, grouping the integer part and
+/// trimming the fractional one as configured.
+fn join_decimal(negative: bool, integer: &str, fraction: &str) -> String {
+    let fraction = if TRIM_ZEROS.with(|t| t.get()) {
+        fraction.trim_end_matches('0')
+    } else {
+        fraction
+    };
+    let sign = if negative { "-" } else { "" };
+    let integer = group_thousands(integer);
+    if fraction.is_empty() {
+        format!("{sign}{integer}")
+    } else {
+        format!("{sign}{integer}.{fraction}")
+    }
+}
+
+/// Splits a formatted decimal, `-12.50`, into its sign, integer and fraction.
+fn split_decimal(s: &str) -> (bool, &str, &str) {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+    (negative, integer, fraction)
+}
+
+fn fixed(f: f64) -> String {
+    let s = format!("{f:.*}", digits());
+    let (negative, integer, fraction) = split_decimal(&s);
+    join_decimal(negative, integer, fraction)
+}
+
+fn scientific(f: f64) -> String {
+    let s = format!("{f:.*e}", digits());
+    let (mantissa, exponent) = s.split_once('e').unwrap();
+    let (negative, integer, fraction) = split_decimal(mantissa);
+    format!("{}e{exponent}", join_decimal(negative, integer, fraction))
+}
+
+/// Moves the decimal point of the scientific form so that the exponent is a
+/// multiple of three, `12.5e3` rather than `1.25e4`.
+fn engineering(f: f64) -> String {
+    // the significant digits of the scientific form, at least three so that
+    // the point can move
+    let s = format!("{f:.*e}", digits().max(2));
+    let (mantissa, exponent) = s.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let shift = exponent.rem_euclid(3) as usize;
+    let (negative, integer, fraction) = split_decimal(mantissa);
+    let all = format!("{integer}{fraction}");
+    format!(
+        "{}e{}",
+        join_decimal(negative, &all[..=shift], &all[shift + 1..]),
+        exponent - shift as i32
+    )
+}
+
+/// An exact rational written as a decimal rounded to the configured number of
+/// digits, halves rounding away from zero.
+pub fn format_decimal(r: &Rationals) -> String {
+    let r = r.clone().reduce();
+    let scale = BigInt::from(10).pow(digits() as u32);
+    let (quotient, remainder) = (r.over.abs() * &scale).div_rem(&r.under.abs());
+    let rounded = if remainder * 2 >= r.under.abs() {
+        quotient + 1
+    } else {
+        quotient
+    };
+    let (integer, fraction) = rounded.div_rem(&scale);
+    let fraction = if digits() == 0 {
+        String::new()
+    } else {
+        format!("{:0>width$}", fraction.to_string(), width = digits())
+    };
+    let negative = r.over.is_negative() && !rounded_is_zero(&integer, &fraction);
+    join_decimal(negative, &integer.to_string(), &fraction)
+}
+
+fn rounded_is_zero(integer: &BigInt, fraction: &str) -> bool {
+    integer.is_zero() && fraction.chars().all(|c| c == '0')
+}
+
+/// A float result in the configured notation.
+pub fn format_float(f: f64) -> String {
+    if !f.is_finite() || f == 0.0 {
+        return fixed(f);
+    }
+    match NOTATION.with(|n| n.get()) {
+        Notation::Fixed => fixed(f),
+        Notation::Scientific => scientific(f),
+        Notation::Engineering => engineering(f),
+        Notation::Auto if (1e-4..1e15).contains(&f.abs()) => fixed(f),
+        Notation::Auto => scientific(f),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{engineering, format_decimal, format_float, scientific, set_decimal_digits};
+    use crate::exact_math::rationals::Rationals;
+
+    #[test]
+    pub fn test_auto() {
+        assert_eq!(format_float(0.5), "0.5000000000");
+        assert_eq!(format_float(6.022e23), "6.0220000000e23");
+        assert_eq!(format_float(-1.5e-9), "-1.5000000000e-9");
+    }
+
+    #[test]
+    pub fn test_engineering() {
+        assert_eq!(engineering(12345.678), "12.345678000e3");
+        assert_eq!(engineering(-1.5e-5), "-15.000000000e-6");
+        assert_eq!(engineering(999.0), "999.00000000e0");
+        assert_eq!(scientific(1e-9), "1.0000000000e-9");
+    }
+
+    #[test]
+    pub fn test_decimal() {
+        assert_eq!(format_decimal(&Rationals::new(3, 2)), "0.6666666667");
+        assert_eq!(format_decimal(&Rationals::new(8, -1)), "-0.1250000000");
+        set_decimal_digits(2, true);
+        assert_eq!(format_decimal(&Rationals::new(8, -1)), "-0.13");
+        assert_eq!(format_decimal(&Rationals::new(1000, -1)), "0");
+        assert_eq!(format_float(2.5), "2.5");
+        assert_eq!(engineering(12345.678), "12.3e3");
+        set_decimal_digits(10, false);
+    }
+}
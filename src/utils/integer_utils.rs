@@ -1,9 +1,113 @@
-pub fn gcd(mut n: i64, mut m: i64) -> i64 {
-    while m != 0 {
-        if m < n {
-            std::mem::swap(&mut m, &mut n);
+use std::cell::Cell;
+
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+
+use crate::exact_math::rationals::Rationals;
+use crate::parsing::ast::Parameters;
+
+/// Upper bound on the size of exact results, past which `^` and `fact` report
+/// an overflow instead of exhausting memory.
+pub const MAX_BITS: u64 = 1 << 20;
+
+thread_local! {
+    static OUTPUT_BASE: Cell<u32> = const { Cell::new(10) };
+    static THOUSANDS_SEPARATOR: Cell<Option<char>> = const { Cell::new(None) };
+}
+
+/// Chooses the base integer results are printed in, bases outside of 2 to 36
+/// falling back to 10.
+pub fn set_output_base(base: u32) {
+    let base = if (2..=36).contains(&base) { base } else { 10 };
+    OUTPUT_BASE.with(|b| b.set(base));
+}
+
+/// Sets the character written between groups of three digits of decimal
+/// results, `None` writing them unseparated.
+pub fn set_thousands_separator(separator: Option<char>) {
+    THOUSANDS_SEPARATOR.with(|s| s.set(separator));
+}
+
+/// Separates the decimal `digits` in groups of three with the configured
+/// separator, `1234567` becoming `1,234,567`.
+pub fn group_thousands(digits: &str) -> String {
+    let separator = match THOUSANDS_SEPARATOR.with(|s| s.get()) {
+        Some(c) => c,
+        None => return digits.to_string(),
+    };
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(separator);
         }
-        m %= n;
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// Exact integer result: a machine integer when it fits, otherwise a rational
+/// with a denominator of one.
+pub fn int_or_big(n: BigInt) -> Parameters {
+    match n.to_i64() {
+        Some(i) => Parameters::Int(i),
+        None => Parameters::Rational(Rationals::from_bigint(BigInt::from(1), n)),
+    }
+}
+
+/// `n` in `base` with the prefix of the base if it has one: `0x` for
+/// hexadecimal, `0o` for octal and `0b` for binary.
+pub fn with_prefix(n: &BigInt, base: u32) -> String {
+    let prefix = match base {
+        2 => "0b",
+        8 => "0o",
+        16 => "0x",
+        _ => "",
+    };
+    let sign = if n.is_negative() { "-" } else { "" };
+    format!("{sign}{prefix}{}", n.magnitude().to_str_radix(base))
+}
+
+/// An integer result in the output base.
+pub fn format_integer(n: &BigInt) -> String {
+    match OUTPUT_BASE.with(|b| b.get()) {
+        10 => {
+            let sign = if n.is_negative() { "-" } else { "" };
+            format!("{sign}{}", group_thousands(&n.magnitude().to_string()))
+        }
+        base => with_prefix(n, base),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigInt;
+
+    use super::{group_thousands, int_or_big, set_thousands_separator, with_prefix};
+    use crate::exact_math::rationals::Rationals;
+    use crate::parsing::ast::Parameters;
+
+    #[test]
+    pub fn test_int_or_big() {
+        assert_eq!(int_or_big(BigInt::from(5)), Parameters::Int(5));
+        let big = BigInt::from(i64::MAX) + 1_i64;
+        let expected = Parameters::Rational(Rationals::from_bigint(BigInt::from(1), big.clone()));
+        assert_eq!(int_or_big(big), expected);
+    }
+
+    #[test]
+    pub fn test_with_prefix() {
+        assert_eq!(with_prefix(&BigInt::from(255), 16), "0xff");
+        assert_eq!(with_prefix(&BigInt::from(-5), 2), "-0b101");
+        assert_eq!(with_prefix(&BigInt::from(8), 8), "0o10");
+        assert_eq!(with_prefix(&BigInt::from(71), 36), "1z");
+    }
+
+    #[test]
+    pub fn test_group_thousands() {
+        assert_eq!(group_thousands("1234567"), "1234567");
+        set_thousands_separator(Some(','));
+        assert_eq!(group_thousands("1234567"), "1,234,567");
+        assert_eq!(group_thousands("123"), "123");
+        set_thousands_separator(None);
     }
-    n
 }
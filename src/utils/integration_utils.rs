@@ -0,0 +1,181 @@
+use crate::errors::calc_error::CalcError;
+
+pub const DEFAULT_TOLERANCE: f64 = 1e-12;
+const MAX_INTERVALS: usize = 2000;
+
+// Gauss-Kronrod nodes on [-1, 1], the Gauss nodes are the odd ones
+const NODES: [f64; 8] = [
+    0.9914553711208126,
+    0.9491079123427585,
+    0.8648644233597691,
+    0.7415311855993945,
+    0.5860872354676911,
+    0.4058451513773972,
+    0.20778495500789848,
+    0.0,
+];
+
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022935322010529224,
+    0.06309209262997856,
+    0.10479001032225019,
+    0.14065325971552592,
+    0.1690047266392679,
+    0.19035057806478542,
+    0.20443294007529889,
+    0.20948214108472782,
+];
+
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.1294849661688697,
+    0.27970539148927664,
+    0.3818300505051189,
+    0.4179591836734694,
+];
+
+type Integrand<'a> = dyn FnMut(f64) -> Result<f64, CalcError> + 'a;
+
+/// The 15 points Kronrod estimate of the integral over `[a, b]` and its
+/// distance to the embedded 7 points Gauss estimate.
+fn kronrod(f: &mut Integrand, a: f64, b: f64) -> Result<(f64, f64), CalcError> {
+    let center = (a + b) / 2.0;
+    let half = (b - a) / 2.0;
+    let fc = f(center)?;
+    let mut kronrod = fc * KRONROD_WEIGHTS[7];
+    let mut gauss = fc * GAUSS_WEIGHTS[3];
+    for j in 0..7 {
+        let dx = half * NODES[j];
+        let sum = f(center - dx)? + f(center + dx)?;
+        kronrod += KRONROD_WEIGHTS[j] * sum;
+        if j % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[j / 2] * sum;
+        }
+    }
+    Ok((kronrod * half, ((kronrod - gauss) * half).abs()))
+}
+
+/// Integrates `f` over `[a, b]` with a finite range.
+fn adaptive(f: &mut Integrand, a: f64, b: f64, tolerance: f64) -> Result<(f64, f64), CalcError> {
+    let (value, error) = kronrod(f, a, b)?;
+    let mut intervals = vec![(a, b, value, error)];
+    loop {
+        let total: f64 = intervals.iter().map(|i| i.2).sum();
+        let error: f64 = intervals.iter().map(|i| i.3).sum();
+        if !total.is_finite() || !error.is_finite() {
+            return Err(CalcError::NoConvergence {
+                function: "integrate".to_string(),
+                estimate: error,
+            });
+        }
+        if error <= tolerance * total.abs().max(1.0) {
+            return Ok((total, error));
+        }
+        if intervals.len() >= MAX_INTERVALS {
+            return Err(CalcError::NoConvergence {
+                function: "integrate".to_string(),
+                estimate: error,
+            });
+        }
+        // the interval with the largest error is split in two
+        let worst = (0..intervals.len())
+            .max_by(|&i, &j| intervals[i].3.total_cmp(&intervals[j].3))
+            .unwrap();
+        let (a, b, _, _) = intervals.swap_remove(worst);
+        let middle = (a + b) / 2.0;
+        let (left, left_error) = kronrod(f, a, middle)?;
+        let (right, right_error) = kronrod(f, middle, b)?;
+        intervals.push((a, middle, left, left_error));
+        intervals.push((middle, b, right, right_error));
+    }
+}
+
+/// Integrates `f` over `[a, b]`, either bound may be infinite. Returns the
+/// value and its estimated absolute error, infinite ranges are mapped to
+/// finite ones by a change of variable.
+pub fn integrate(
+    f: &mut Integrand,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+) -> Result<(f64, f64), CalcError> {
+    if a.is_nan() || b.is_nan() {
+        return Err(CalcError::NoConvergence {
+            function: "integrate".to_string(),
+            estimate: f64::NAN,
+        });
+    }
+    if a == b {
+        return Ok((0.0, 0.0));
+    }
+    if a > b {
+        let (value, error) = integrate(f, b, a, tolerance)?;
+        return Ok((-value, error));
+    }
+    match (a.is_infinite(), b.is_infinite()) {
+        (false, false) => adaptive(f, a, b, tolerance),
+        // x = a + t/(1-t)
+        (false, true) => adaptive(
+            &mut |t| Ok(f(a + t / (1.0 - t))? / ((1.0 - t) * (1.0 - t))),
+            0.0,
+            1.0,
+            tolerance,
+        ),
+        // x = b - (1-t)/t
+        (true, false) => adaptive(
+            &mut |t| Ok(f(b - (1.0 - t) / t)? / (t * t)),
+            0.0,
+            1.0,
+            tolerance,
+        ),
+        // x = t/(1-t²)
+        (true, true) => adaptive(
+            &mut |t| {
+                let d = 1.0 - t * t;
+                Ok(f(t / d)? * (1.0 + t * t) / (d * d))
+            },
+            -1.0,
+            1.0,
+            tolerance,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use super::{integrate, DEFAULT_TOLERANCE};
+
+    #[test]
+    fn test_integrate_polynomial() {
+        let (value, error) = integrate(&mut |x| Ok(x * x), 0.0, 3.0, DEFAULT_TOLERANCE).unwrap();
+        assert!((value - 9.0).abs() < 1e-12);
+        assert!(error < 1e-9);
+    }
+
+    #[test]
+    fn test_integrate_reversed() {
+        let (value, _) = integrate(&mut |x| Ok(x.sin()), PI, 0.0, DEFAULT_TOLERANCE).unwrap();
+        assert!((value + 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_integrate_infinite() {
+        let (value, _) = integrate(
+            &mut |x| Ok((-x * x).exp()),
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            DEFAULT_TOLERANCE,
+        )
+        .unwrap();
+        assert!((value - PI.sqrt()).abs() < 1e-9);
+        let (value, _) = integrate(
+            &mut |x| Ok(1.0 / (x * x)),
+            1.0,
+            f64::INFINITY,
+            DEFAULT_TOLERANCE,
+        )
+        .unwrap();
+        assert!((value - 1.0).abs() < 1e-9);
+    }
+}
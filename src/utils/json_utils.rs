@@ -0,0 +1,187 @@
+use num_bigint::BigInt;
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+
+use crate::errors::calc_error::CalcError;
+use crate::parsing::ast::Parameters;
+
+/// An integer as a JSON number, or as a string of digits when it does not fit
+/// in 64 bits.
+struct Integer<'a>(&'a BigInt);
+
+impl Serialize for Integer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match i64::try_from(self.0) {
+            Ok(i) => serializer.serialize_i64(i),
+            Err(_) => serializer.serialize_str(&self.0.to_string()),
+        }
+    }
+}
+
+/// Every value is an object with a `type`, the other fields depending on it.
+impl Serialize for Parameters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self {
+            Parameters::Int(i) => {
+                map.serialize_entry("type", "int")?;
+                map.serialize_entry("value", i)?;
+            }
+            Parameters::Rational(r) => {
+                let r = r.clone().reduce();
+                if r.is_integer() {
+                    map.serialize_entry("type", "int")?;
+                    map.serialize_entry("value", &Integer(&r.over))?;
+                } else {
+                    map.serialize_entry("type", "rational")?;
+                    map.serialize_entry("num", &Integer(&r.over))?;
+                    map.serialize_entry("den", &Integer(&r.under))?;
+                }
+            }
+            // NaN and the infinities are null
+            Parameters::Float(f) => {
+                map.serialize_entry("type", "float")?;
+                map.serialize_entry("value", f)?;
+            }
+            Parameters::Bool(b) => {
+                map.serialize_entry("type", "bool")?;
+                map.serialize_entry("value", b)?;
+            }
+            Parameters::Str(s) => {
+                map.serialize_entry("type", "string")?;
+                map.serialize_entry("value", s)?;
+            }
+            // status messages such as the confirmation of an assignment
+            Parameters::Identifier(s) => match s.strip_prefix('@') {
+                Some(message) => {
+                    map.serialize_entry("type", "message")?;
+                    map.serialize_entry("value", message.trim())?;
+                }
+                None => {
+                    map.serialize_entry("type", "identifier")?;
+                    map.serialize_entry("value", s)?;
+                }
+            },
+            Parameters::Complex(z) => {
+                map.serialize_entry("type", "complex")?;
+                map.serialize_entry("re", &z.re)?;
+                map.serialize_entry("im", &z.im)?;
+            }
+            Parameters::Quantity(q) => {
+                map.serialize_entry("type", "quantity")?;
+                map.serialize_entry("value", &q.value)?;
+                map.serialize_entry("unit", &q.unit_string())?;
+            }
+            Parameters::InterpreterVector(lst) => match lst.first() {
+                Some(Parameters::InterpreterVector(_)) => {
+                    let rows: Vec<&[Parameters]> = lst
+                        .iter()
+                        .map(|row| match row {
+                            Parameters::InterpreterVector(r) => r.as_slice(),
+                            x => std::slice::from_ref(x),
+                        })
+                        .collect();
+                    map.serialize_entry("type", "matrix")?;
+                    map.serialize_entry("rows", &rows)?;
+                }
+                _ => {
+                    map.serialize_entry("type", "vector")?;
+                    map.serialize_entry("values", lst)?;
+                }
+            },
+            Parameters::Null => map.serialize_entry("type", "null")?,
+            // exact forms without a structure of their own are written as text
+            Parameters::Radical(_) => {
+                map.serialize_entry("type", "radical")?;
+                map.serialize_entry("value", &self.to_string())?;
+            }
+            Parameters::Symbolic(_) => {
+                map.serialize_entry("type", "symbolic")?;
+                map.serialize_entry("value", &self.to_string())?;
+            }
+            Parameters::Polynomial(_) => {
+                map.serialize_entry("type", "polynomial")?;
+                map.serialize_entry("value", &self.to_string())?;
+            }
+            _ => {
+                map.serialize_entry("type", "operator")?;
+                map.serialize_entry("value", &self.to_string())?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl Serialize for CalcError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("type", "error")?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        map.end()
+    }
+}
+
+/// The object written for the `line`th evaluated line, its `input` and either
+/// its result or its error.
+#[derive(Serialize)]
+struct Line<'a> {
+    line: usize,
+    input: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'a Parameters>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a CalcError>,
+}
+
+pub fn json_line(line: usize, input: &str, result: Result<&Parameters, &CalcError>) -> String {
+    let line = Line {
+        line,
+        input,
+        result: result.ok(),
+        error: result.err(),
+    };
+    serde_json::to_string(&line).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigInt;
+
+    use super::json_line;
+    use crate::errors::calc_error::CalcError;
+    use crate::exact_math::rationals::Rationals;
+    use crate::parsing::ast::Parameters;
+
+    #[test]
+    pub fn test_values() {
+        let big = Rationals::from_bigint(BigInt::from(1), BigInt::from(2).pow(70));
+        let vector = Parameters::InterpreterVector(Box::from(vec![
+            Parameters::Rational(Rationals::new(4, 2)),
+            Parameters::Rational(big),
+            Parameters::Float(0.5),
+        ]));
+        assert_eq!(
+            json_line(2, "v", Ok(&vector)),
+            r#"{"line":2,"input":"v","result":{"type":"vector","values":[{"type":"rational","num":1,"den":2},{"type":"int","value":"1180591620717411303424"},{"type":"float","value":0.5}]}}"#
+        );
+        let matrix = Parameters::InterpreterVector(Box::from(vec![Parameters::InterpreterVector(
+            Box::from(vec![
+                Parameters::Bool(true),
+                Parameters::Str("a".to_string()),
+            ]),
+        )]));
+        assert_eq!(
+            serde_json::to_string(&matrix).unwrap(),
+            r#"{"type":"matrix","rows":[[{"type":"bool","value":true},{"type":"string","value":"a"}]]}"#
+        );
+    }
+
+    #[test]
+    pub fn test_error() {
+        assert_eq!(
+            json_line(1, "1/0", Err(&CalcError::DivisionByZero)),
+            r#"{"line":1,"input":"1/0","error":{"type":"error","kind":"division_by_zero","message":"Division by zero"}}"#
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
+    errors::calc_error::CalcError,
     interpreting::function::{add, divide, greater, minus, mult},
     parsing::ast::Parameters,
 };
@@ -22,12 +23,14 @@ pub fn mult_matrix(
     a: Vec<Vec<Parameters>>,
     b: Vec<Vec<Parameters>>,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Vec<Vec<Parameters>> {
+) -> Result<Vec<Vec<Parameters>>, CalcError> {
     let first = a.first().unwrap().len();
     let second = b.len();
 
     if first != second {
-        Vec::new()
+        Err(CalcError::DimensionMismatch(format!(
+            "cannot multiply a matrix with {first} columns by a matrix with {second} rows"
+        )))
     } else {
         let n = a.len();
         let p = b.first().unwrap().len();
@@ -42,9 +45,9 @@ pub fn mult_matrix(
                         a.get(i).unwrap().get(k).unwrap().clone(),
                         b.get(k).unwrap().get(j).unwrap().clone(),
                         ram.as_deref(),
-                    );
+                    )?;
 
-                    sum = add(sum, intermediary, ram.as_deref())
+                    sum = add(sum, intermediary, ram.as_deref())?
                 }
 
                 s.push(sum);
@@ -52,7 +55,7 @@ pub fn mult_matrix(
             res.push(s);
         }
 
-        res
+        Ok(res)
     }
 }
 
@@ -61,7 +64,7 @@ pub fn lup_decompose(
     mut p: &mut Vec<Parameters>,
     n: usize,
     ram: Option<&HashMap<String, Parameters>>,
-) -> i64 {
+) -> Result<i64, CalcError> {
     let mut abs_a;
     let mut max_a;
     let mut ptr: Vec<Parameters>;
@@ -77,7 +80,7 @@ pub fn lup_decompose(
 
         for k in i..n {
             abs_a = ((a[k])[i]).clone().abs(ram.as_deref());
-            match greater(abs_a.clone(), max_a.clone(), ram.as_deref()) {
+            match greater(abs_a.clone(), max_a.clone(), ram.as_deref())? {
                 Parameters::Bool(true) => {
                     max_a = (abs_a).clone();
                     i_max = k;
@@ -87,10 +90,10 @@ pub fn lup_decompose(
         }
 
         match max_a {
-            Parameters::Int(0) => return 0,
+            Parameters::Int(0) => return Ok(0),
             Parameters::Float(f) => {
                 if f.abs() <= 1e-10 {
-                    return 0;
+                    return Ok(0);
                 }
             }
             _ => (),
@@ -105,21 +108,21 @@ pub fn lup_decompose(
             (a)[i] = (a)[i_max].clone();
             (a)[i_max] = ptr.clone();
 
-            (p)[n] = add((p)[n].clone(), Parameters::Int(1), ram.as_deref());
+            (p)[n] = add((p)[n].clone(), Parameters::Int(1), ram.as_deref())?;
         }
 
         for j in (i + 1)..n {
-            (a)[j][i] = divide((a)[j][i].clone(), (a)[i][i].clone(), ram.as_deref());
+            (a)[j][i] = divide((a)[j][i].clone(), (a)[i][i].clone(), ram.as_deref())?;
             for k in (i + 1)..n {
                 (a)[j][k] = minus(
                     (a)[j][k].clone(),
-                    mult((a)[j][i].clone(), (a)[i][k].clone(), ram.as_deref()),
+                    mult((a)[j][i].clone(), (a)[i][k].clone(), ram.as_deref())?,
                     ram.as_deref(),
-                )
+                )?
             }
         }
     }
-    return 1;
+    Ok(1)
 }
 
 pub fn lup_determinant(
@@ -127,29 +130,29 @@ pub fn lup_determinant(
     p: &mut Vec<Parameters>,
     n: usize,
     ram: Option<&HashMap<String, Parameters>>,
-) -> Parameters {
+) -> Result<Parameters, CalcError> {
     let mut det: Parameters = (&a[0][0]).clone();
     for i in 1..n {
-        det = mult(det.clone(), (&a[i][i]).clone(), ram.as_deref())
+        det = mult(det.clone(), (&a[i][i]).clone(), ram.as_deref())?
     }
 
-    match p[n] {
+    Ok(match p[n] {
         Parameters::Int(i) => {
             if (i - (n as i64)) % 2 == 0 {
                 det
             } else {
-                minus(Parameters::Int(0), det, ram.as_deref())
+                minus(Parameters::Int(0), det, ram.as_deref())?
             }
         }
         Parameters::Float(f) => {
             if (f - (n as f64)) % 2.0 == 0.0 {
                 det
             } else {
-                minus(Parameters::Float(0.0), det, ram.as_deref())
+                minus(Parameters::Float(0.0), det, ram.as_deref())?
             }
         }
         _ => Parameters::Float(f64::NAN),
-    }
+    })
 }
 
 pub fn lup_invert(
@@ -158,7 +161,7 @@ pub fn lup_invert(
     n: usize,
     ia: &mut Vec<Vec<Parameters>>,
     ram: Option<&HashMap<String, Parameters>>,
-) {
+) -> Result<(), CalcError> {
     for j in 0..n {
         for i in 0..n {
             ia[i][j] = match &p[i] {
@@ -202,9 +205,9 @@ pub fn lup_invert(
             for k in 0..i {
                 ia[i][j] = minus(
                     (&ia[i][j]).clone(),
-                    mult((&a[i][k]).clone(), (&ia[k][j]).clone(), ram.as_deref()),
+                    mult((&a[i][k]).clone(), (&ia[k][j]).clone(), ram.as_deref())?,
                     ram.as_deref(),
-                );
+                )?;
             }
         }
 
@@ -212,13 +215,14 @@ pub fn lup_invert(
             for k in i + 1..n {
                 ia[i][j] = minus(
                     (&ia[i][j]).clone(),
-                    mult((&a[i][k]).clone(), (&ia[k][j]).clone(), ram.as_deref()),
+                    mult((&a[i][k]).clone(), (&ia[k][j]).clone(), ram.as_deref())?,
                     ram.as_deref(),
-                )
+                )?
             }
-            ia[i][j] = divide((&ia[i][j]).clone(), (&a[i][i]).clone(), ram.as_deref());
+            ia[i][j] = divide((&ia[i][j]).clone(), (&a[i][i]).clone(), ram.as_deref())?;
         }
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -258,16 +262,16 @@ mod test {
 
         println!("{:?}/{:?}", &a, &b);
 
-        let det = lup_determinant(&mut a, &mut b, 3 as usize, None);
+        let det = lup_determinant(&mut a, &mut b, 3 as usize, None).unwrap();
 
         println!("{:?}", det);
         assert_eq!(
             greater(
                 Parameters::Float(1e-10),
-                minus(det, Parameters::Float(60.0), None).abs(None),
+                minus(det, Parameters::Float(60.0), None).unwrap().abs(None),
                 None
             ),
-            Parameters::Bool(true)
+            Ok(Parameters::Bool(true))
         );
     }
 }
@@ -1,3 +1,7 @@
+pub mod float_utils;
 pub mod integer_utils;
+pub mod integration_utils;
+pub mod json_utils;
 pub mod matrix_utils;
 pub mod plot_utils;
+pub mod solve_utils;
@@ -0,0 +1,198 @@
+use crate::errors::calc_error::CalcError;
+
+const MAX_ITERATIONS: usize = 200;
+const TOLERANCE: f64 = 1e-15;
+// a root is only accepted when |f(x)| is below this
+const RESIDUAL: f64 = 1e-9;
+
+type RealFunction<'a> = dyn Fn(f64) -> Result<f64, CalcError> + 'a;
+
+fn no_convergence(estimate: f64) -> CalcError {
+    CalcError::NoConvergence {
+        function: "solve".to_string(),
+        estimate,
+    }
+}
+
+/// Newton's method with a central difference for the derivative, `None` when
+/// the iteration does not settle on a root.
+fn newton(f: &RealFunction, x0: f64) -> Result<Option<f64>, CalcError> {
+    let mut x = x0;
+    for _ in 0..MAX_ITERATIONS {
+        let fx = f(x)?;
+        if fx == 0.0 {
+            return Ok(Some(x));
+        }
+        let h = f64::EPSILON.cbrt() * x.abs().max(1.0);
+        let derivative = (f(x + h)? - f(x - h)?) / (2.0 * h);
+        let step = fx / derivative;
+        if !step.is_finite() {
+            return Ok(None);
+        }
+        x -= step;
+        if step.abs() <= 4.0 * f64::EPSILON * x.abs().max(1.0) {
+            let fx = f(x)?;
+            return Ok((fx.abs() <= RESIDUAL).then_some(x));
+        }
+    }
+    Ok(None)
+}
+
+/// Brent's method on a bracket `[a, b]` where `f` changes sign: inverse
+/// quadratic interpolation and secant steps, with bisection whenever they
+/// do not shrink the bracket fast enough.
+pub fn brent(f: &RealFunction, a: f64, b: f64) -> Result<f64, CalcError> {
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a)?, f(b)?);
+    if fa == 0.0 {
+        return Ok(a);
+    }
+    if fb == 0.0 {
+        return Ok(b);
+    }
+    if fa.is_sign_positive() == fb.is_sign_positive() {
+        return Err(no_convergence(fa.abs().min(fb.abs())));
+    }
+
+    let (mut c, mut fc) = (b, fb);
+    let mut d = b - a;
+    let mut e = d;
+    for _ in 0..MAX_ITERATIONS {
+        // c is the other end of the bracket
+        if fb.is_sign_positive() == fc.is_sign_positive() {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        // b is the best estimate
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * TOLERANCE;
+        let m = 0.5 * (c - b);
+        if m.abs() <= tolerance || fb == 0.0 {
+            // a shrunk bracket around a pole or a jump is not a root
+            if fb.abs() <= RESIDUAL {
+                return Ok(b);
+            }
+            return Err(no_convergence(fb.abs()));
+        }
+        if e.abs() >= tolerance && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                // secant
+                (2.0 * m * s, 1.0 - s)
+            } else {
+                // inverse quadratic interpolation
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * m * q - (tolerance * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+            }
+        } else {
+            d = m;
+            e = m;
+        }
+        a = b;
+        fa = fb;
+        b += if d.abs() > tolerance {
+            d
+        } else {
+            tolerance.copysign(m)
+        };
+        fb = f(b)?;
+    }
+    Err(no_convergence(fb.abs()))
+}
+
+/// Looks for a sign change of `f` on intervals growing around `x0`.
+fn bracket(f: &RealFunction, x0: f64) -> Result<Option<(f64, f64)>, CalcError> {
+    let f0 = f(x0)?;
+    let mut dx = 0.1 * x0.abs().max(1.0);
+    for _ in 0..MAX_ITERATIONS {
+        for x in [x0 - dx, x0 + dx] {
+            let fx = f(x)?;
+            if fx.is_finite() && fx.is_sign_positive() != f0.is_sign_positive() {
+                return Ok(Some((x0.min(x), x0.max(x))));
+            }
+        }
+        dx *= 1.6;
+        if !dx.is_finite() {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+/// A root of `f`, found with Newton's method from `x0` or failing that with
+/// Brent's method on a bracket, either the given one or one searched for
+/// around `x0`.
+pub fn solve(f: &RealFunction, x0: f64, range: Option<(f64, f64)>) -> Result<f64, CalcError> {
+    if let Some(x) = newton(f, x0)? {
+        match range {
+            Some((a, b)) if x < a.min(b) || x > a.max(b) => (),
+            _ => return Ok(x),
+        }
+    }
+    let (a, b) = match range {
+        Some(r) => r,
+        None => match bracket(f, x0)? {
+            Some(r) => r,
+            None => return Err(no_convergence(f(x0)?.abs())),
+        },
+    };
+    brent(f, a, b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{brent, solve};
+
+    #[test]
+    fn test_solve_newton() {
+        let x = solve(&|x| Ok(x * x - 2.0), 1.0, None).unwrap();
+        assert!((x - 2f64.sqrt()).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_solve_bracket() {
+        // Newton from 0 leaves the interval, the root inside it is kept
+        let x = solve(&|x| Ok(x.powi(3) - 2.0 * x + 2.0), 0.0, Some((-3.0, 0.0))).unwrap();
+        assert!((x + 1.7692923542386314).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_brent() {
+        let x = brent(&|x| Ok(x.cos() - x), 0.0, 1.0).unwrap();
+        assert!((x - 0.7390851332151607).abs() < 1e-14);
+        assert!(brent(&|x| Ok(x * x + 1.0), -1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_solve_rejects_poles_and_jumps() {
+        assert!(solve(&|x| Ok(x.tan()), 1.0, Some((1.0, 2.0))).is_err());
+        assert!(solve(&|x| Ok(1.0 / (x - 3.0)), 3.0, Some((2.0, 4.0))).is_err());
+        assert!(solve(&|x| Ok(1.0 / x), 0.0, None).is_err());
+        assert!(solve(&|x| Ok(x.floor() - 0.5), 0.0, Some((0.0, 1.0))).is_err());
+    }
+}